@@ -1,77 +1,399 @@
 use crate::i18n::t;
+use crate::log_sink::{LogSink, parse_log_sink};
+use crate::monitor::{MonitorOptions, parse_duration};
+use crate::path_sync::{LoadOptions, StatusFilter};
+use crate::target_files::DEFAULT_MAX_SCAN_ENTRIES;
 use clap::{Arg, ArgAction, Command};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
 
 pub fn build_cli() -> Command {
     Command::new("chaser")
-        .about(&t("app_description"))
+        .about(t("app_description"))
         .version(env!("CARGO_PKG_VERSION"))
         .subcommand_required(false)
         .arg_required_else_help(false)
+        .args_override_self(true)
+        .arg(
+            Arg::new("no-write")
+                .long("no-write")
+                .help(t("arg_no_write"))
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .help(t("arg_profile"))
+                .global(true),
+        )
+        .arg(
+            Arg::new("keep-going")
+                .long("keep-going")
+                .help(t("arg_keep_going"))
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-auto-create")
+                .long("no-auto-create")
+                .help(t("arg_no_auto_create"))
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("relative-to")
+                .long("relative-to")
+                .value_name("DIR")
+                .help(t("arg_relative_to"))
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value(".")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("dedupe-output")
+                .long("dedupe-output")
+                .help(t("arg_dedupe_output"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("here")
+                .long("here")
+                .help(t("arg_here"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("save")
+                .long("save")
+                .help(t("arg_save"))
+                .action(ArgAction::SetTrue)
+                .requires("here"),
+        )
+        .arg(
+            Arg::new("watch-timeout")
+                .long("watch-timeout")
+                .value_name("DURATION")
+                .help(t("arg_watch_timeout"))
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("max-runtime")
+                .long("max-runtime")
+                .value_name("DURATION")
+                .help(t("arg_max_runtime"))
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("stats-interval")
+                .long("stats-interval")
+                .value_name("DURATION")
+                .help(t("arg_stats_interval"))
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("aggregate")
+                .long("aggregate")
+                .value_name("MS")
+                .help(t("arg_aggregate"))
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("ignore-from")
+                .long("ignore-from")
+                .value_name("FILE")
+                .help(t("arg_ignore_from"))
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("watch-hidden")
+                .long("watch-hidden")
+                .value_name("PATTERN")
+                .help(t("arg_watch_hidden"))
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .help(t("arg_explain"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .help(t("arg_trace"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("once-per-path")
+                .long("once-per-path")
+                .help(t("arg_once_per_path"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("report-missing-on-start")
+                .long("report-missing-on-start")
+                .help(t("arg_report_missing_on_start"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-targets")
+                .long("watch-targets")
+                .help(t("arg_watch_targets"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-roots-from-targets")
+                .long("watch-roots-from-targets")
+                .help(t("arg_watch_roots_from_targets"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("summary-only")
+                .long("summary-only")
+                .help(t("arg_summary_only"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("relative-times")
+                .long("relative-times")
+                .help(t("arg_relative_times"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-symlink-targets")
+                .long("watch-symlink-targets")
+                .help(t("arg_watch_symlink_targets"))
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("log-sink")
+                .long("log-sink")
+                .value_name("SINK")
+                .help(t("arg_log_sink"))
+                .action(ArgAction::Append)
+                .value_parser(parse_log_sink),
+        )
+        .arg(
+            Arg::new("log-file")
+                .long("log-file")
+                .value_name("FILE")
+                .help(t("arg_log_file"))
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("serve")
+                .long("serve")
+                .value_name("ADDR")
+                .help(t("arg_serve"))
+                .value_parser(clap::value_parser!(SocketAddr)),
+        )
+        .arg(
+            Arg::new("grep")
+                .long("grep")
+                .value_name("REGEX")
+                .help(t("arg_grep"))
+                .value_parser(validate_regex),
+        )
         .subcommand(
-            Command::new("add").about(&t("cmd_add")).arg(
-                Arg::new("path")
-                    .help(&t("arg_path"))
-                    .required(true)
-                    .index(1),
-            ),
+            Command::new("add")
+                .about(t("cmd_add"))
+                .arg(
+                    Arg::new("path")
+                        .help(t("arg_path"))
+                        .required_unless_present("paths-from-stdin")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("paths-from-stdin")
+                        .long("paths-from-stdin")
+                        .help(t("arg_paths_from_stdin"))
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("label")
+                        .long("label")
+                        .help(t("arg_label"))
+                        .action(ArgAction::Set),
+                ),
         )
         .subcommand(
-            Command::new("remove").about(&t("cmd_remove")).arg(
+            Command::new("remove").about(t("cmd_remove")).arg(
                 Arg::new("path")
-                    .help(&t("arg_path_remove"))
+                    .help(t("arg_path_remove"))
                     .required(true)
                     .index(1),
             ),
         )
-        .subcommand(Command::new("list").about(&t("cmd_list")))
-        .subcommand(Command::new("config").about(&t("cmd_config")))
+        .subcommand(Command::new("list").about(t("cmd_list")))
+        .subcommand(Command::new("config").about(t("cmd_config")))
         .subcommand(
-            Command::new("recursive").about(&t("cmd_recursive")).arg(
+            Command::new("recursive").about(t("cmd_recursive")).arg(
                 Arg::new("enabled")
-                    .help(&t("arg_recursive_enabled"))
+                    .help(t("arg_recursive_enabled"))
                     .required(true)
                     .action(ArgAction::Set)
                     .index(1),
             ),
         )
         .subcommand(
-            Command::new("ignore").about(&t("cmd_ignore")).arg(
+            Command::new("ignore").about(t("cmd_ignore")).arg(
                 Arg::new("pattern")
-                    .help(&t("arg_ignore_pattern"))
+                    .help(t("arg_ignore_pattern"))
                     .required(true)
                     .index(1),
             ),
         )
-        .subcommand(Command::new("reset").about(&t("cmd_reset")))
         .subcommand(
-            Command::new("lang").about(&t("cmd_lang")).arg(
+            Command::new("clear-ignores")
+                .about(t("cmd_clear_ignores"))
+                .arg(
+                    Arg::new("defaults")
+                        .long("defaults")
+                        .help(t("arg_clear_ignores_defaults"))
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(Command::new("reset").about(t("cmd_reset")))
+        .subcommand(
+            Command::new("lang").about(t("cmd_lang")).arg(
                 Arg::new("language")
-                    .help(&t("arg_language"))
+                    .help(t("arg_language"))
                     .required(true)
                     .action(ArgAction::Set)
                     .index(1),
             ),
         )
         .subcommand(
-            Command::new("add-target").about(&t("cmd_add_target")).arg(
-                Arg::new("file")
-                    .help(&t("arg_target_file"))
-                    .required(true)
-                    .index(1),
-            ),
+            Command::new("add-target")
+                .about(t("cmd_add_target"))
+                .arg(
+                    Arg::new("file")
+                        .help(t("arg_target_file"))
+                        .required_unless_present("paths-from-stdin")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("paths-from-stdin")
+                        .long("paths-from-stdin")
+                        .help(t("arg_paths_from_stdin"))
+                        .conflicts_with("dry-run")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help(t("arg_add_target_dry_run"))
+                        .action(ArgAction::SetTrue),
+                ),
         )
         .subcommand(
             Command::new("remove-target")
-                .about(&t("cmd_remove_target"))
+                .about(t("cmd_remove_target"))
                 .arg(
                     Arg::new("file")
-                        .help(&t("arg_target_file_remove"))
+                        .help(t("arg_target_file_remove"))
                         .required(true)
                         .index(1),
                 ),
         )
-        .subcommand(Command::new("list-targets").about(&t("cmd_list_targets")))
-        .subcommand(Command::new("status").about(&t("cmd_status")))
+        .subcommand(
+            Command::new("format")
+                .about(t("cmd_format"))
+                .arg(
+                    Arg::new("file")
+                        .help(t("arg_format_file"))
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help(t("arg_format_dry_run"))
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about(t("cmd_convert"))
+                .arg(
+                    Arg::new("src")
+                        .help(t("arg_convert_src"))
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("dst")
+                        .help(t("arg_convert_dst"))
+                        .required(true)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("scan")
+                .about(t("cmd_scan"))
+                .arg(
+                    Arg::new("dir")
+                        .help(t("arg_scan_dir"))
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("add")
+                        .long("add")
+                        .help(t("arg_scan_add"))
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("max-entries")
+                        .long("max-entries")
+                        .value_name("N")
+                        .help(t("arg_scan_max_entries"))
+                        .value_parser(clap::value_parser!(u64).range(1..)),
+                ),
+        )
+        .subcommand(Command::new("list-targets").about(t("cmd_list_targets")))
+        .subcommand(Command::new("suggest-watch").about(t("cmd_suggest_watch")))
+        .subcommand(Command::new("reindex").about(t("cmd_reindex")))
+        .subcommand(Command::new("coverage").about(t("cmd_coverage")))
+        .subcommand(Command::new("profiles").about(t("cmd_profiles")))
+        .subcommand(
+            Command::new("version").about(t("cmd_version")).arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .help(t("arg_version_verbose"))
+                    .action(ArgAction::SetTrue),
+            ),
+        )
+        .subcommand(
+            Command::new("status")
+                .about(t("cmd_status"))
+                .arg(
+                    Arg::new("only-existing")
+                        .long("only-existing")
+                        .help(t("arg_only_existing"))
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("only-missing"),
+                )
+                .arg(
+                    Arg::new("only-missing")
+                        .long("only-missing")
+                        .help(t("arg_only_missing"))
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("flat")
+                        .long("flat")
+                        .help(t("arg_status_flat"))
+                        .action(ArgAction::SetTrue),
+                ),
+        )
 }
 
 // 简化版CLI构建器，用于测试，不依赖国际化
@@ -81,13 +403,212 @@ pub fn build_test_cli() -> Command {
         .version(env!("CARGO_PKG_VERSION"))
         .subcommand_required(false)
         .arg_required_else_help(false)
+        .args_override_self(true)
+        .arg(
+            Arg::new("no-write")
+                .long("no-write")
+                .help("Log intended file changes instead of writing them")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .help("Use the config of the named profile")
+                .global(true),
+        )
+        .arg(
+            Arg::new("keep-going")
+                .long("keep-going")
+                .help("Skip target files that fail to load instead of aborting")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("no-auto-create")
+                .long("no-auto-create")
+                .help("Fail on missing target files instead of creating them empty")
+                .action(ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("relative-to")
+                .long("relative-to")
+                .value_name("DIR")
+                .help("Print paths relative to DIR, given as --relative-to=DIR (default: the current directory)")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value(".")
+                .value_parser(clap::value_parser!(PathBuf))
+                .global(true),
+        )
+        .arg(
+            Arg::new("dedupe-output")
+                .long("dedupe-output")
+                .help("Suppress event lines identical to the previous one")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("here")
+                .long("here")
+                .help("Watch the current directory for this session")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("save")
+                .long("save")
+                .help("Persist the --here directory to the config")
+                .action(ArgAction::SetTrue)
+                .requires("here"),
+        )
+        .arg(
+            Arg::new("watch-timeout")
+                .long("watch-timeout")
+                .value_name("DURATION")
+                .help("Exit if no event arrives within DURATION (e.g. 30s, 5m, 2h)")
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("max-runtime")
+                .long("max-runtime")
+                .value_name("DURATION")
+                .help("Exit after DURATION regardless of activity (e.g. 1h)")
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("stats-interval")
+                .long("stats-interval")
+                .value_name("DURATION")
+                .help("Print event counters every DURATION while monitoring")
+                .value_parser(parse_duration),
+        )
+        .arg(
+            Arg::new("aggregate")
+                .long("aggregate")
+                .value_name("MS")
+                .help("Print one grouped summary of the events in each MS-millisecond window")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            Arg::new("ignore-from")
+                .long("ignore-from")
+                .value_name("FILE")
+                .help("Read extra ignore patterns from FILE for this session")
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("watch-hidden")
+                .long("watch-hidden")
+                .value_name("PATTERN")
+                .help("Watch hidden paths matching PATTERN even when ignore_hidden is on")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .help("Print why each event was shown or ignored")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .help("Print every raw event to stderr before filtering")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("once-per-path")
+                .long("once-per-path")
+                .help("Only report the first event of each path")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("report-missing-on-start")
+                .long("report-missing-on-start")
+                .help("List tracked paths that are already missing at startup")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-targets")
+                .long("watch-targets")
+                .help("Warn when an edited target file lists a path that does not exist")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-roots-from-targets")
+                .long("watch-roots-from-targets")
+                .help("Also watch the directories holding the tracked paths, for this run only")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("summary-only")
+                .long("summary-only")
+                .help("Print no events, only the statistics when monitoring ends")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("relative-times")
+                .long("relative-times")
+                .help("Prefix events with the time since monitoring started")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("watch-symlink-targets")
+                .long("watch-symlink-targets")
+                .help("Follow tracked symlinks to the files they point to")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("log-sink")
+                .long("log-sink")
+                .value_name("SINK")
+                .help("Write events to terminal, syslog or file; repeat for several")
+                .action(ArgAction::Append)
+                .value_parser(parse_log_sink),
+        )
+        .arg(
+            Arg::new("log-file")
+                .long("log-file")
+                .value_name("FILE")
+                .help("File appended to by --log-sink file")
+                .value_parser(clap::value_parser!(PathBuf)),
+        )
+        .arg(
+            Arg::new("serve")
+                .long("serve")
+                .value_name("ADDR")
+                .help("Serve /status and /healthz over HTTP on ADDR while monitoring")
+                .value_parser(clap::value_parser!(SocketAddr)),
+        )
+        .arg(
+            Arg::new("grep")
+                .long("grep")
+                .value_name("REGEX")
+                .help("Only show events whose path matches REGEX")
+                .value_parser(validate_regex),
+        )
         .subcommand(
-            Command::new("add").about("Add a path to watch").arg(
-                Arg::new("path")
-                    .help("Path to add to watch list")
-                    .required(true)
-                    .index(1),
-            ),
+            Command::new("add")
+                .about("Add a path to watch")
+                .arg(
+                    Arg::new("path")
+                        .help("Path to add to watch list")
+                        .required_unless_present("paths-from-stdin")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("paths-from-stdin")
+                        .long("paths-from-stdin")
+                        .help("Read newline-separated paths from stdin")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("label")
+                        .long("label")
+                        .help("Optional label describing the watch path")
+                        .action(ArgAction::Set),
+                ),
         )
         .subcommand(
             Command::new("remove")
@@ -120,6 +641,16 @@ pub fn build_test_cli() -> Command {
                     .index(1),
             ),
         )
+        .subcommand(
+            Command::new("clear-ignores")
+                .about("Remove all ignore patterns, defaults included")
+                .arg(
+                    Arg::new("defaults")
+                        .long("defaults")
+                        .help("Start over from the default patterns instead of none")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
         .subcommand(Command::new("reset").about("Reset config to default"))
         .subcommand(
             Command::new("lang").about("Set interface language").arg(
@@ -136,8 +667,21 @@ pub fn build_test_cli() -> Command {
                 .arg(
                     Arg::new("file")
                         .help("Target file path (json, yaml, toml, csv)")
-                        .required(true)
+                        .required_unless_present("paths-from-stdin")
                         .index(1),
+                )
+                .arg(
+                    Arg::new("paths-from-stdin")
+                        .long("paths-from-stdin")
+                        .help("Read newline-separated paths from stdin")
+                        .conflicts_with("dry-run")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Preview the paths the file would track without adding it")
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .subcommand(
@@ -150,31 +694,185 @@ pub fn build_test_cli() -> Command {
                         .index(1),
                 ),
         )
+        .subcommand(
+            Command::new("format")
+                .about("Rewrite a target file in canonical layout")
+                .arg(
+                    Arg::new("file")
+                        .help("Target file to format")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Print the formatted file instead of writing it")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Copy the paths of a target file into a new file of another format")
+                .arg(
+                    Arg::new("src")
+                        .help("Target file to read paths from")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("dst")
+                        .help("New file to write, its extension selects the format")
+                        .required(true)
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("scan")
+                .about("List files under a directory that could serve as target files")
+                .arg(
+                    Arg::new("dir")
+                        .help("Directory to search")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("add")
+                        .long("add")
+                        .help("Add every candidate as a target file")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("max-entries")
+                        .long("max-entries")
+                        .value_name("N")
+                        .help("Give up once the directory holds more entries than this")
+                        .value_parser(clap::value_parser!(u64).range(1..)),
+                ),
+        )
         .subcommand(Command::new("list-targets").about("List all target files"))
-        .subcommand(Command::new("status").about("Show path synchronization status"))
+        .subcommand(
+            Command::new("suggest-watch")
+                .about("Suggest watch directories covering every tracked path"),
+        )
+        .subcommand(
+            Command::new("reindex")
+                .about("Reload every target file and report what changed since the last run"),
+        )
+        .subcommand(
+            Command::new("coverage")
+                .about("Report tracked paths that no registered watcher covers"),
+        )
+        .subcommand(Command::new("profiles").about("List config profiles"))
+        .subcommand(
+            Command::new("version")
+                .about("Show version information")
+                .arg(
+                    Arg::new("verbose")
+                        .long("verbose")
+                        .short('v')
+                        .help("Also list supported formats, locales and the watch backend")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("status")
+                .about("Show path synchronization status")
+                .arg(
+                    Arg::new("only-existing")
+                        .long("only-existing")
+                        .help("Only show tracked paths that exist")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("only-missing"),
+                )
+                .arg(
+                    Arg::new("only-missing")
+                        .long("only-missing")
+                        .help("Only show tracked paths that are missing")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("flat")
+                        .long("flat")
+                        .help("Print one tab-separated line per tracked path")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
 }
 
 #[derive(Debug)]
 pub enum Commands {
-    Add { path: String },
-    Remove { path: String },
+    Add {
+        path: String,
+        label: Option<String>,
+    },
+    Remove {
+        path: String,
+    },
     List,
     Config,
-    Recursive { enabled: String },
-    Ignore { pattern: String },
+    Recursive {
+        enabled: String,
+    },
+    Ignore {
+        pattern: String,
+    },
+    ClearIgnores {
+        keep_defaults: bool,
+    },
     Reset,
-    Lang { language: String },
-    AddTarget { file: String },
-    RemoveTarget { file: String },
+    Lang {
+        language: String,
+    },
+    AddTarget {
+        file: String,
+        dry_run: bool,
+    },
+    /// `add` or `add-target` with `--paths-from-stdin`
+    AddFromStdin {
+        targets: bool,
+    },
+    RemoveTarget {
+        file: String,
+    },
     ListTargets,
-    Status,
+    Status {
+        filter: StatusFilter,
+        keep_going: bool,
+        flat: bool,
+    },
+    Profiles,
+    Version {
+        verbose: bool,
+    },
+    Format {
+        file: String,
+        dry_run: bool,
+    },
+    Convert {
+        src: String,
+        dst: String,
+    },
+    Scan {
+        dir: String,
+        add: bool,
+        max_entries: usize,
+    },
+    SuggestWatch,
+    Reindex,
+    Coverage,
 }
 
 pub fn parse_command(matches: &clap::ArgMatches) -> Option<Commands> {
     match matches.subcommand() {
+        Some(("add" | "add-target", sub_matches)) if get_flag(sub_matches, "paths-from-stdin") => {
+            Some(Commands::AddFromStdin {
+                targets: matches.subcommand_name() == Some("add-target"),
+            })
+        }
         Some(("add", sub_matches)) => {
             let path = sub_matches.get_one::<String>("path").unwrap().clone();
-            Some(Commands::Add { path })
+            let label = get_string(sub_matches, "label");
+            Some(Commands::Add { path, label })
         }
         Some(("remove", sub_matches)) => {
             let path = sub_matches.get_one::<String>("path").unwrap().clone();
@@ -190,25 +888,255 @@ pub fn parse_command(matches: &clap::ArgMatches) -> Option<Commands> {
             let pattern = sub_matches.get_one::<String>("pattern").unwrap().clone();
             Some(Commands::Ignore { pattern })
         }
+        Some(("clear-ignores", sub_matches)) => Some(Commands::ClearIgnores {
+            keep_defaults: get_flag(sub_matches, "defaults"),
+        }),
         Some(("reset", _)) => Some(Commands::Reset),
         Some(("lang", sub_matches)) => {
             let language = sub_matches.get_one::<String>("language").unwrap().clone();
             Some(Commands::Lang { language })
         }
-        Some(("add-target", sub_matches)) => {
-            let file = sub_matches.get_one::<String>("file").unwrap().clone();
-            Some(Commands::AddTarget { file })
-        }
+        Some(("add-target", sub_matches)) => Some(Commands::AddTarget {
+            file: sub_matches.get_one::<String>("file").unwrap().clone(),
+            dry_run: get_flag(sub_matches, "dry-run"),
+        }),
         Some(("remove-target", sub_matches)) => {
             let file = sub_matches.get_one::<String>("file").unwrap().clone();
             Some(Commands::RemoveTarget { file })
         }
         Some(("list-targets", _)) => Some(Commands::ListTargets),
-        Some(("status", _)) => Some(Commands::Status),
+        Some(("suggest-watch", _)) => Some(Commands::SuggestWatch),
+        Some(("reindex", _)) => Some(Commands::Reindex),
+        Some(("coverage", _)) => Some(Commands::Coverage),
+        Some(("profiles", _)) => Some(Commands::Profiles),
+        Some(("version", sub_matches)) => Some(Commands::Version {
+            verbose: get_flag(sub_matches, "verbose"),
+        }),
+        Some(("convert", sub_matches)) => Some(Commands::Convert {
+            src: sub_matches.get_one::<String>("src").unwrap().clone(),
+            dst: sub_matches.get_one::<String>("dst").unwrap().clone(),
+        }),
+        Some(("scan", sub_matches)) => Some(Commands::Scan {
+            dir: sub_matches.get_one::<String>("dir").unwrap().clone(),
+            add: get_flag(sub_matches, "add"),
+            max_entries: sub_matches
+                .get_one::<u64>("max-entries")
+                .map_or(DEFAULT_MAX_SCAN_ENTRIES, |max| *max as usize),
+        }),
+        Some(("format", sub_matches)) => Some(Commands::Format {
+            file: sub_matches.get_one::<String>("file").unwrap().clone(),
+            dry_run: get_flag(sub_matches, "dry-run"),
+        }),
+        Some(("status", sub_matches)) => {
+            let filter = if get_flag(sub_matches, "only-existing") {
+                StatusFilter::OnlyExisting
+            } else if get_flag(sub_matches, "only-missing") {
+                StatusFilter::OnlyMissing
+            } else {
+                StatusFilter::All
+            };
+            Some(Commands::Status {
+                filter,
+                keep_going: get_flag(sub_matches, "keep-going"),
+                flat: get_flag(sub_matches, "flat"),
+            })
+        }
         _ => None,
     }
 }
 
+/// Collect the monitor options given on the root command
+pub fn parse_monitor_options(matches: &clap::ArgMatches) -> MonitorOptions {
+    MonitorOptions {
+        dedupe_output: get_flag(matches, "dedupe-output"),
+        here: get_flag(matches, "here"),
+        save: get_flag(matches, "save"),
+        watch_timeout: get_duration(matches, "watch-timeout"),
+        stats_interval: get_duration(matches, "stats-interval"),
+        aggregate: matches
+            .try_get_one::<u64>("aggregate")
+            .ok()
+            .flatten()
+            .map(|millis| Duration::from_millis(*millis)),
+        max_runtime: get_duration(matches, "max-runtime"),
+        ignore_from: matches
+            .try_get_many::<PathBuf>("ignore-from")
+            .ok()
+            .flatten()
+            .map(|files| files.cloned().collect())
+            .unwrap_or_default(),
+        watch_hidden: matches
+            .try_get_many::<String>("watch-hidden")
+            .ok()
+            .flatten()
+            .map(|patterns| patterns.cloned().collect())
+            .unwrap_or_default(),
+        explain: get_flag(matches, "explain"),
+        grep: get_string(matches, "grep"),
+        trace: get_flag(matches, "trace"),
+        keep_going: get_flag(matches, "keep-going"),
+        no_auto_create: get_flag(matches, "no-auto-create"),
+        once_per_path: get_flag(matches, "once-per-path"),
+        report_missing_on_start: get_flag(matches, "report-missing-on-start"),
+        watch_targets: get_flag(matches, "watch-targets"),
+        watch_roots_from_targets: get_flag(matches, "watch-roots-from-targets"),
+        relative_times: get_flag(matches, "relative-times"),
+        summary_only: get_flag(matches, "summary-only"),
+        watch_symlink_targets: get_flag(matches, "watch-symlink-targets"),
+        log_sinks: matches
+            .try_get_many::<LogSink>("log-sink")
+            .ok()
+            .flatten()
+            .map(|sinks| sinks.copied().collect())
+            .unwrap_or_default(),
+        log_file: matches
+            .try_get_one::<PathBuf>("log-file")
+            .ok()
+            .flatten()
+            .cloned(),
+        serve: matches
+            .try_get_one::<SocketAddr>("serve")
+            .ok()
+            .flatten()
+            .copied(),
+    }
+}
+
+/// How commands load target files, from `--keep-going` and
+/// `--no-auto-create` given before or after the subcommand
+pub fn load_options(matches: &clap::ArgMatches) -> LoadOptions {
+    let flag = |id: &str| match matches.subcommand() {
+        Some((_, sub_matches)) => get_flag(sub_matches, id) || get_flag(matches, id),
+        None => get_flag(matches, id),
+    };
+    LoadOptions {
+        keep_going: flag("keep-going"),
+        auto_create: !flag("no-auto-create"),
+        quiet: false,
+    }
+}
+
+/// The `--relative-to` base, given before or after the subcommand
+pub fn relative_to(matches: &clap::ArgMatches) -> Option<PathBuf> {
+    let get = |matches: &clap::ArgMatches| {
+        matches
+            .try_get_one::<PathBuf>("relative-to")
+            .ok()
+            .flatten()
+            .cloned()
+    };
+    match matches.subcommand() {
+        Some((_, sub_matches)) => get(sub_matches).or_else(|| get(matches)),
+        None => get(matches),
+    }
+}
+
+/// Raw arguments with `defaults` inserted after the program name
+///
+/// Defaults for options the command line sets are dropped, so an explicit
+/// value replaces the configured one instead of adding to it.
+pub fn with_default_monitor_args(
+    cli: &Command,
+    defaults: &[String],
+    args: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let mut args = args.into_iter();
+    let program = args.next();
+    let args: Vec<String> = args.collect();
+
+    let explicit: HashSet<&str> = option_groups(cli, &args)
+        .into_iter()
+        .filter_map(|(id, _)| id)
+        .collect();
+    let defaults = option_groups(cli, defaults)
+        .into_iter()
+        .filter(|(id, _)| id.is_none_or(|id| !explicit.contains(id)))
+        .flat_map(|(_, group)| group.iter().cloned());
+    program
+        .into_iter()
+        .chain(defaults)
+        .chain(args.iter().cloned())
+        .collect()
+}
+
+/// Raw arguments split into options with their separate value, each paired
+/// with the option's id; anything else is a group of its own without an id
+fn option_groups<'a>(cli: &'a Command, args: &'a [String]) -> Vec<(Option<&'a str>, &'a [String])> {
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        let option = if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = long
+                .split_once('=')
+                .map_or((long, false), |(name, _)| (name, true));
+            cli.get_arguments()
+                .find(|a| a.get_long() == Some(name))
+                .map(|a| (a, inline))
+        } else if let Some(short) = arg.strip_prefix('-') {
+            let mut chars = short.chars();
+            chars.next().and_then(|name| {
+                cli.get_arguments()
+                    .find(|a| a.get_short() == Some(name))
+                    .map(|a| (a, !chars.as_str().is_empty()))
+            })
+        } else {
+            None
+        };
+        let len = match option {
+            Some((a, false)) if a.get_action().takes_values() => 2.min(args.len() - i),
+            _ => 1,
+        };
+        groups.push((option.map(|(a, _)| a.get_id().as_str()), &args[i..i + len]));
+        i += len;
+    }
+    groups
+}
+
+/// Find the `--profile` value in raw arguments
+///
+/// The config is loaded before the CLI is parsed, so the profile has to be
+/// known up front.
+pub fn profile_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    let mut profile = None;
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--profile" {
+            profile = args.next();
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            profile = Some(value.to_string());
+        }
+    }
+    profile
+}
+
+/// Read a duration option, treating options the command doesn't define as unset
+fn get_duration(matches: &clap::ArgMatches, id: &str) -> Option<Duration> {
+    matches.try_get_one::<Duration>(id).ok().flatten().copied()
+}
+
+/// Accept a regular expression only if it compiles
+fn validate_regex(pattern: &str) -> Result<String, regex::Error> {
+    regex::Regex::new(pattern).map(|_| pattern.to_string())
+}
+
+/// Read a boolean flag, treating flags the command doesn't define as unset
+fn get_flag(matches: &clap::ArgMatches, id: &str) -> bool {
+    matches
+        .try_get_one::<bool>(id)
+        .ok()
+        .flatten()
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Read an optional string argument, treating arguments the command doesn't define as unset
+fn get_string(matches: &clap::ArgMatches, id: &str) -> Option<String> {
+    matches.try_get_one::<String>(id).ok().flatten().cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -221,7 +1149,7 @@ mod tests {
     #[test]
     fn test_cli_no_command() {
         let cli = setup_test_cli();
-        let matches = cli.try_get_matches_from(&["chaser"]).unwrap();
+        let matches = cli.try_get_matches_from(["chaser"]).unwrap();
         assert!(parse_command(&matches).is_none());
     }
 
@@ -229,11 +1157,27 @@ mod tests {
     fn test_add_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "add", "/path/to/watch"])
+            .try_get_matches_from(["chaser", "add", "/path/to/watch"])
             .unwrap();
         match parse_command(&matches) {
-            Some(Commands::Add { path }) => {
+            Some(Commands::Add { path, label }) => {
                 assert_eq!(path, "/path/to/watch");
+                assert_eq!(label, None);
+            }
+            _ => panic!("Expected Add command"),
+        }
+    }
+
+    #[test]
+    fn test_add_command_with_label() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "add", "/path/to/watch", "--label", "assets"])
+            .unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Add { path, label }) => {
+                assert_eq!(path, "/path/to/watch");
+                assert_eq!(label, Some("assets".to_string()));
             }
             _ => panic!("Expected Add command"),
         }
@@ -243,7 +1187,7 @@ mod tests {
     fn test_remove_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "remove", "/path/to/remove"])
+            .try_get_matches_from(["chaser", "remove", "/path/to/remove"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Remove { path }) => {
@@ -256,7 +1200,7 @@ mod tests {
     #[test]
     fn test_list_command() {
         let cli = setup_test_cli();
-        let matches = cli.try_get_matches_from(&["chaser", "list"]).unwrap();
+        let matches = cli.try_get_matches_from(["chaser", "list"]).unwrap();
         match parse_command(&matches) {
             Some(Commands::List) => {}
             _ => panic!("Expected List command"),
@@ -266,7 +1210,7 @@ mod tests {
     #[test]
     fn test_config_command() {
         let cli = setup_test_cli();
-        let matches = cli.try_get_matches_from(&["chaser", "config"]).unwrap();
+        let matches = cli.try_get_matches_from(["chaser", "config"]).unwrap();
         match parse_command(&matches) {
             Some(Commands::Config) => {}
             _ => panic!("Expected Config command"),
@@ -277,7 +1221,7 @@ mod tests {
     fn test_recursive_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "recursive", "true"])
+            .try_get_matches_from(["chaser", "recursive", "true"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Recursive { enabled }) => {
@@ -288,7 +1232,7 @@ mod tests {
 
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "recursive", "false"])
+            .try_get_matches_from(["chaser", "recursive", "false"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Recursive { enabled }) => {
@@ -302,7 +1246,7 @@ mod tests {
     fn test_ignore_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "ignore", "*.tmp"])
+            .try_get_matches_from(["chaser", "ignore", "*.tmp"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Ignore { pattern }) => {
@@ -312,10 +1256,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_clear_ignores_command() {
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "clear-ignores"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::ClearIgnores {
+                keep_defaults: false
+            })
+        ));
+
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "clear-ignores", "--defaults"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::ClearIgnores {
+                keep_defaults: true
+            })
+        ));
+    }
+
     #[test]
     fn test_reset_command() {
         let cli = setup_test_cli();
-        let matches = cli.try_get_matches_from(&["chaser", "reset"]).unwrap();
+        let matches = cli.try_get_matches_from(["chaser", "reset"]).unwrap();
         match parse_command(&matches) {
             Some(Commands::Reset) => {}
             _ => panic!("Expected Reset command"),
@@ -326,7 +1293,7 @@ mod tests {
     fn test_lang_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "lang", "zh-cn"])
+            .try_get_matches_from(["chaser", "lang", "zh-cn"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Lang { language }) => {
@@ -340,21 +1307,51 @@ mod tests {
     fn test_add_target_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "add-target", "config.json"])
+            .try_get_matches_from(["chaser", "add-target", "config.json"])
             .unwrap();
         match parse_command(&matches) {
-            Some(Commands::AddTarget { file }) => {
+            Some(Commands::AddTarget { file, dry_run }) => {
                 assert_eq!(file, "config.json");
+                assert!(!dry_run);
             }
             _ => panic!("Expected AddTarget command"),
         }
+
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "add-target", "config.json", "--dry-run"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::AddTarget { dry_run: true, .. })
+        ));
+    }
+
+    #[test]
+    fn test_paths_from_stdin_flag() {
+        let parse = |args: &[&str]| {
+            let matches = setup_test_cli().try_get_matches_from(args).unwrap();
+            parse_command(&matches)
+        };
+        assert!(matches!(
+            parse(&["chaser", "add", "--paths-from-stdin"]),
+            Some(Commands::AddFromStdin { targets: false })
+        ));
+        assert!(matches!(
+            parse(&["chaser", "add-target", "--paths-from-stdin"]),
+            Some(Commands::AddFromStdin { targets: true })
+        ));
+        assert!(
+            setup_test_cli()
+                .try_get_matches_from(["chaser", "add"])
+                .is_err()
+        );
     }
 
     #[test]
     fn test_remove_target_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "remove-target", "config.json"])
+            .try_get_matches_from(["chaser", "remove-target", "config.json"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::RemoveTarget { file }) => {
@@ -368,7 +1365,7 @@ mod tests {
     fn test_list_targets_command() {
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "list-targets"])
+            .try_get_matches_from(["chaser", "list-targets"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::ListTargets) => {}
@@ -379,17 +1376,465 @@ mod tests {
     #[test]
     fn test_status_command() {
         let cli = setup_test_cli();
-        let matches = cli.try_get_matches_from(&["chaser", "status"]).unwrap();
+        let matches = cli.try_get_matches_from(["chaser", "status"]).unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Status { filter, flat, .. }) => {
+                assert_eq!(filter, StatusFilter::All);
+                assert!(!flat);
+            }
+            _ => panic!("Expected Status command"),
+        }
+    }
+
+    #[test]
+    fn test_status_filter_flags() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "status", "--only-existing"])
+            .unwrap();
         match parse_command(&matches) {
-            Some(Commands::Status) => {}
+            Some(Commands::Status { filter, .. }) => {
+                assert_eq!(filter, StatusFilter::OnlyExisting);
+            }
             _ => panic!("Expected Status command"),
         }
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "status", "--only-missing"])
+            .unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Status { filter, .. }) => {
+                assert_eq!(filter, StatusFilter::OnlyMissing);
+            }
+            _ => panic!("Expected Status command"),
+        }
+
+        // The two filters are mutually exclusive
+        let cli = setup_test_cli();
+        let result =
+            cli.try_get_matches_from(["chaser", "status", "--only-existing", "--only-missing"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_no_write_flag_is_global() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "add", "/path", "--no-write"])
+            .unwrap();
+        let (_, sub_matches) = matches.subcommand().unwrap();
+        assert!(sub_matches.get_flag("no-write"));
+
+        let cli = setup_test_cli();
+        let matches = cli.try_get_matches_from(["chaser", "--no-write"]).unwrap();
+        assert!(matches.get_flag("no-write"));
+    }
+
+    #[test]
+    fn test_version_command() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "version", "--verbose"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::Version { verbose: true })
+        ));
+    }
+
+    #[test]
+    fn test_format_command() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "format", "targets.json", "--dry-run"])
+            .unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Format { file, dry_run }) => {
+                assert_eq!(file, "targets.json");
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Format command"),
+        }
+    }
+
+    #[test]
+    fn test_convert_command() {
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "convert", "targets.csv", "targets.json"])
+            .unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Convert { src, dst }) => {
+                assert_eq!(src, "targets.csv");
+                assert_eq!(dst, "targets.json");
+            }
+            _ => panic!("Expected Convert command"),
+        }
+    }
+
+    #[test]
+    fn test_scan_command() {
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "scan", "project", "--add"])
+            .unwrap();
+        match parse_command(&matches) {
+            Some(Commands::Scan {
+                dir,
+                add,
+                max_entries,
+            }) => {
+                assert_eq!(dir, "project");
+                assert!(add);
+                assert_eq!(max_entries, DEFAULT_MAX_SCAN_ENTRIES);
+            }
+            _ => panic!("Expected Scan command"),
+        }
+
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "scan", "project", "--max-entries", "500"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::Scan {
+                max_entries: 500,
+                ..
+            })
+        ));
+        assert!(
+            setup_test_cli()
+                .try_get_matches_from(["chaser", "scan", "project", "--max-entries", "0"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_keep_going_flag() {
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "--keep-going"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).keep_going);
+
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "status", "--keep-going"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::Status {
+                keep_going: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_relative_to_flag() {
+        let relative_to_of = |args: &[&str]| {
+            let matches = setup_test_cli().try_get_matches_from(args).unwrap();
+            relative_to(&matches)
+        };
+        assert_eq!(relative_to_of(&["chaser"]), None);
+        assert_eq!(
+            relative_to_of(&["chaser", "--relative-to"]),
+            Some(PathBuf::from("."))
+        );
+        assert_eq!(
+            relative_to_of(&["chaser", "status", "--relative-to=/w"]),
+            Some(PathBuf::from("/w"))
+        );
+
+        // A bare flag never swallows the subcommand after it
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "--relative-to", "status"])
+            .unwrap();
+        assert_eq!(matches.subcommand_name(), Some("status"));
+        assert_eq!(relative_to(&matches), Some(PathBuf::from(".")));
+    }
+
+    #[test]
+    fn test_no_auto_create_flag_is_global() {
+        for args in [
+            &["chaser", "--no-auto-create"][..],
+            &["chaser", "status", "--no-auto-create"],
+        ] {
+            let matches = setup_test_cli().try_get_matches_from(args).unwrap();
+            assert!(!load_options(&matches).auto_create, "{:?}", args);
+        }
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "--no-auto-create"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).no_auto_create);
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "status"])
+            .unwrap();
+        assert!(load_options(&matches).auto_create);
+    }
+
+    #[test]
+    fn test_suggest_watch_command() {
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "suggest-watch"])
+            .unwrap();
+        assert!(matches!(
+            parse_command(&matches),
+            Some(Commands::SuggestWatch)
+        ));
+    }
+
+    #[test]
+    fn test_reindex_command() {
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "reindex"])
+            .unwrap();
+        assert!(matches!(parse_command(&matches), Some(Commands::Reindex)));
+
+        let matches = setup_test_cli()
+            .try_get_matches_from(["chaser", "coverage"])
+            .unwrap();
+        assert!(matches!(parse_command(&matches), Some(Commands::Coverage)));
+    }
+
+    #[test]
+    fn test_profiles_command() {
+        let cli = setup_test_cli();
+        let matches = cli.try_get_matches_from(["chaser", "profiles"]).unwrap();
+        assert!(matches!(parse_command(&matches), Some(Commands::Profiles)));
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "list", "--profile", "work"])
+            .unwrap();
+        let (_, sub_matches) = matches.subcommand().unwrap();
+        assert_eq!(
+            sub_matches.get_one::<String>("profile").map(String::as_str),
+            Some("work")
+        );
+    }
+
+    #[test]
+    fn test_default_monitor_args_precedence() {
+        let defaults = vec![
+            "--dedupe-output".to_string(),
+            "--grep".to_string(),
+            "\\.rs$".to_string(),
+            "--watch-timeout".to_string(),
+            "30s".to_string(),
+        ];
+        let parse = |cli_args: &[&str]| {
+            let args = std::iter::once("chaser")
+                .chain(cli_args.iter().copied())
+                .map(String::from);
+            let matches = setup_test_cli()
+                .try_get_matches_from(with_default_monitor_args(
+                    &setup_test_cli(),
+                    &defaults,
+                    args,
+                ))
+                .unwrap();
+            parse_monitor_options(&matches)
+        };
+
+        // Config defaults apply when the command line is silent
+        let options = parse(&[]);
+        assert!(options.dedupe_output);
+        assert_eq!(options.grep.as_deref(), Some("\\.rs$"));
+        assert_eq!(options.watch_timeout, Some(Duration::from_secs(30)));
+
+        // Explicit flags win, the remaining defaults still apply
+        let options = parse(&["--grep", "\\.toml$", "--explain"]);
+        assert_eq!(options.grep.as_deref(), Some("\\.toml$"));
+        assert_eq!(options.watch_timeout, Some(Duration::from_secs(30)));
+        assert!(options.dedupe_output);
+        assert!(options.explain);
+    }
+
+    #[test]
+    fn test_explicit_values_replace_appended_defaults() {
+        let defaults: Vec<String> = [
+            "--watch-hidden",
+            ".env",
+            "--watch-hidden=.cache",
+            "--dedupe-output",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let args = ["chaser", "--watch-hidden", ".git"].map(String::from);
+
+        let merged = with_default_monitor_args(&setup_test_cli(), &defaults, args);
+        assert_eq!(
+            merged,
+            ["chaser", "--dedupe-output", "--watch-hidden", ".git"]
+        );
+        let matches = setup_test_cli().try_get_matches_from(merged).unwrap();
+        assert_eq!(parse_monitor_options(&matches).watch_hidden, vec![".git"]);
+    }
+
+    #[test]
+    fn test_profile_from_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(profile_from_args(args(&["chaser", "list"])), None);
+        assert_eq!(
+            profile_from_args(args(&["chaser", "--profile", "work", "list"])),
+            Some("work".to_string())
+        );
+        assert_eq!(
+            profile_from_args(args(&["chaser", "list", "--profile=home"])),
+            Some("home".to_string())
+        );
+        assert_eq!(
+            profile_from_args(args(&["chaser", "add", "--", "--profile"])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_monitor_options() {
+        let cli = setup_test_cli();
+        let matches = cli.try_get_matches_from(["chaser"]).unwrap();
+        assert_eq!(parse_monitor_options(&matches), MonitorOptions::default());
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--dedupe-output"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).dedupe_output);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--here", "--save"])
+            .unwrap();
+        let options = parse_monitor_options(&matches);
+        assert!(options.here);
+        assert!(options.save);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--watch-timeout", "30s"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).watch_timeout,
+            Some(Duration::from_secs(30))
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--max-runtime", "1h"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).max_runtime,
+            Some(Duration::from_secs(3600))
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--stats-interval", "1m"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).stats_interval,
+            Some(Duration::from_secs(60))
+        );
+
+        let cli = setup_test_cli();
+        assert!(
+            cli.try_get_matches_from(["chaser", "--watch-timeout", "soon"])
+                .is_err()
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from([
+                "chaser",
+                "--ignore-from",
+                "a.ignore",
+                "--ignore-from",
+                "b.ignore",
+            ])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).ignore_from,
+            vec![PathBuf::from("a.ignore"), PathBuf::from("b.ignore")]
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--watch-hidden", ".env"])
+            .unwrap();
+        assert_eq!(parse_monitor_options(&matches).watch_hidden, vec![".env"]);
+
+        let cli = setup_test_cli();
+        let matches = cli.try_get_matches_from(["chaser", "--explain"]).unwrap();
+        assert!(parse_monitor_options(&matches).explain);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--report-missing-on-start"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).report_missing_on_start);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--watch-targets"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).watch_targets);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--serve", "127.0.0.1:8080"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).serve,
+            Some("127.0.0.1:8080".parse().unwrap())
+        );
+        let cli = setup_test_cli();
+        assert!(
+            cli.try_get_matches_from(["chaser", "--serve", "nowhere"])
+                .is_err()
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--aggregate", "250"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).aggregate,
+            Some(Duration::from_millis(250))
+        );
+        let cli = setup_test_cli();
+        assert!(
+            cli.try_get_matches_from(["chaser", "--aggregate", "0"])
+                .is_err()
+        );
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--watch-roots-from-targets"])
+            .unwrap();
+        assert!(parse_monitor_options(&matches).watch_roots_from_targets);
+
+        let cli = setup_test_cli();
+        let matches = cli
+            .try_get_matches_from(["chaser", "--grep", r"\.rs$"])
+            .unwrap();
+        assert_eq!(
+            parse_monitor_options(&matches).grep.as_deref(),
+            Some(r"\.rs$")
+        );
+
+        // Invalid expressions are rejected while parsing
+        let cli = setup_test_cli();
+        assert!(cli.try_get_matches_from(["chaser", "--grep", "("]).is_err());
+
+        // --save only makes sense together with --here
+        let cli = setup_test_cli();
+        assert!(cli.try_get_matches_from(["chaser", "--save"]).is_err());
     }
 
     #[test]
     fn test_invalid_command() {
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "invalid"]);
+        let result = cli.try_get_matches_from(["chaser", "invalid"]);
         assert!(result.is_err());
     }
 
@@ -398,41 +1843,41 @@ mod tests {
         let cli = setup_test_cli();
 
         // Test Add command without path
-        let result = cli.try_get_matches_from(&["chaser", "add"]);
+        let result = cli.try_get_matches_from(["chaser", "add"]);
         assert!(result.is_err());
 
         // Test Remove command without path
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "remove"]);
+        let result = cli.try_get_matches_from(["chaser", "remove"]);
         assert!(result.is_err());
 
         // Test Recursive command without enabled flag
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "recursive"]);
+        let result = cli.try_get_matches_from(["chaser", "recursive"]);
         assert!(result.is_err());
 
         // Test Ignore command without pattern
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "ignore"]);
+        let result = cli.try_get_matches_from(["chaser", "ignore"]);
         assert!(result.is_err());
 
         // Test Lang command without language
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "lang"]);
+        let result = cli.try_get_matches_from(["chaser", "lang"]);
         assert!(result.is_err());
     }
 
     #[test]
     fn test_help_flag() {
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "--help"]);
+        let result = cli.try_get_matches_from(["chaser", "--help"]);
         assert!(result.is_err()); // Help flag causes parse to "fail" but shows help
     }
 
     #[test]
     fn test_version_flag() {
         let cli = setup_test_cli();
-        let result = cli.try_get_matches_from(&["chaser", "--version"]);
+        let result = cli.try_get_matches_from(["chaser", "--version"]);
         assert!(result.is_err()); // Version flag causes parse to "fail" but shows version
     }
 
@@ -441,10 +1886,10 @@ mod tests {
         // Test paths with spaces and special characters
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "add", "/path with spaces/test"])
+            .try_get_matches_from(["chaser", "add", "/path with spaces/test"])
             .unwrap();
         match parse_command(&matches) {
-            Some(Commands::Add { path }) => {
+            Some(Commands::Add { path, .. }) => {
                 assert_eq!(path, "/path with spaces/test");
             }
             _ => panic!("Expected Add command"),
@@ -453,7 +1898,7 @@ mod tests {
         // Test ignore patterns with special characters
         let cli = setup_test_cli();
         let matches = cli
-            .try_get_matches_from(&["chaser", "ignore", "*.log*"])
+            .try_get_matches_from(["chaser", "ignore", "*.log*"])
             .unwrap();
         match parse_command(&matches) {
             Some(Commands::Ignore { pattern }) => {
@@ -471,7 +1916,7 @@ mod tests {
 
         for value in test_cases {
             let cli = setup_test_cli();
-            let result = cli.try_get_matches_from(&["chaser", "recursive", value]);
+            let result = cli.try_get_matches_from(["chaser", "recursive", value]);
             assert!(
                 result.is_ok(),
                 "Failed to parse recursive with value: {}",
@@ -1,8 +1,43 @@
+use crate::target_files::TargetFileFormat;
+use crate::theme::{Role, Theme, Themed};
+use crate::write_mode::{LineEnding, WriteStrategy};
 use anyhow::{Context, Result};
 use owo_colors::OwoColorize;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Profile selected with `--profile`, if any
+static ACTIVE_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Select the profile whose config file is used for the rest of the process
+pub fn set_profile(name: Option<String>) -> Result<()> {
+    if let Some(name) = &name {
+        validate_profile_name(name)?;
+    }
+    *ACTIVE_PROFILE.write().unwrap() = name;
+    Ok(())
+}
+
+/// The profile selected with `--profile`, if any
+pub fn active_profile() -> Option<String> {
+    ACTIVE_PROFILE.read().unwrap().clone()
+}
+
+/// Profile names become file names, so they must be a single plain component
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || name.starts_with('.')
+        || name.contains(['/', '\\'])
+        || Path::new(name).components().count() != 1
+    {
+        anyhow::bail!("Invalid profile name: '{}'", name);
+    }
+    Ok(())
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Config {
@@ -12,6 +47,168 @@ pub struct Config {
     pub language: Option<String>,
     #[serde(default)]
     pub target_files: Vec<String>,
+    /// Optional labels for watch paths, keyed by path. Organizational only.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub watch_labels: BTreeMap<String, String>,
+    /// Ignore patterns that only apply under one watch path, keyed by path,
+    /// on top of `ignore_patterns`; relative to the watch path like
+    /// `.chaserignore` patterns
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub watch_ignores: BTreeMap<String, Vec<String>>,
+    /// How rename events are printed by the monitor
+    #[serde(default)]
+    pub rename_display: RenameDisplay,
+    /// Match tracked paths in target files regardless of letter case
+    #[serde(default)]
+    pub case_insensitive_tracking: bool,
+    /// Merge patterns from a `.chaserignore` at the root of each watch path
+    #[serde(default)]
+    pub respect_chaserignore: bool,
+    /// Treat target files that list no paths at all as a load error
+    #[serde(default)]
+    pub require_nonempty_targets: bool,
+    /// Lines of CSV target files starting with this character are comments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csv_comment_char: Option<char>,
+    /// Ignore events for dotfiles and paths inside dot-directories
+    #[serde(default)]
+    pub ignore_hidden: bool,
+    /// Print a line for file access events
+    #[serde(default)]
+    pub report_access: bool,
+    /// Rewrite moved paths in JSON/YAML/TOML target files as
+    /// `{ path, last_known_path }` objects instead of bare strings
+    #[serde(default)]
+    pub track_history: bool,
+    /// Also watch the parent directory of watched files so their renames are
+    /// seen; unset uses the platform default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_parent_for_renames: Option<bool>,
+    /// How target files and the config are written; `in_place` for file
+    /// systems that do not support atomic rename
+    #[serde(default)]
+    pub write_strategy: WriteStrategy,
+    /// Newline style of the written config and target files
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// Print at most this many paths of a single event, then "... and N more"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_paths_per_event: Option<usize>,
+    /// Flags put in front of the command line when running the monitor; an
+    /// option given explicitly replaces its default
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_monitor_args: Vec<String>,
+    /// Levels of nesting read when extracting paths from target files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_extract_depth: Option<usize>,
+    /// Color palette of terminal output
+    #[serde(default)]
+    pub theme: Theme,
+    /// In non-recursive mode, also watch directories created inside a watch path
+    #[serde(default)]
+    pub watch_new_subdirs: bool,
+    /// Watch the parent of a deleted watched file and watch the file again
+    /// when it is recreated; unset means enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rearm_deleted_files: Option<bool>,
+    /// Refuse to add watch paths beyond this many
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_watch_paths: Option<usize>,
+    /// Refuse to add ignore patterns beyond this many
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ignore_patterns: Option<usize>,
+}
+
+/// Ignore patterns a new config starts with
+pub const DEFAULT_IGNORE_PATTERNS: [&str; 4] = ["*.tmp", "*.log", ".git/**", "target/**"];
+
+pub const ENV_WATCH_PATHS: &str = "CHASER_WATCH_PATHS";
+pub const ENV_TARGET_FILES: &str = "CHASER_TARGET_FILES";
+pub const ENV_IGNORE: &str = "CHASER_IGNORE";
+pub const ENV_RECURSIVE: &str = "CHASER_RECURSIVE";
+pub const ENV_LANG: &str = "CHASER_LANG";
+
+/// Split a separator-delimited list from an environment variable, dropping
+/// empty entries
+fn split_env_list(value: &str) -> Vec<String> {
+    std::env::split_paths(value)
+        .map(|entry| entry.to_string_lossy().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// Read a yes/no setting, ignoring case and surrounding whitespace
+pub fn parse_bool_flexible(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "y" | "on" | "enable" => Some(true),
+        "false" | "0" | "no" | "n" | "off" | "disable" => Some(false),
+        _ => None,
+    }
+}
+
+/// `path` with a leading `~` replaced by the home directory
+pub fn expand_path(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => Some(""),
+        Some(rest) => rest.strip_prefix(['/', std::path::MAIN_SEPARATOR]),
+        None => None,
+    };
+    match (rest, dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Whether `error` says a path is longer than the OS allows
+pub fn is_path_too_long(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::InvalidFilename
+}
+
+/// `path` in the form the OS accepts beyond its usual length limit: absolute
+/// Windows paths get the `\\?\` prefix, other paths are returned unchanged
+pub fn long_path(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if cfg!(windows) && path.is_absolute() && !text.starts_with(r"\\") {
+        // The prefix turns off separator normalization, so it must be done here
+        return PathBuf::from(format!(r"\\?\{}", text.replace('/', r"\")));
+    }
+    path.to_path_buf()
+}
+
+/// Warning for a path the OS rejected as too long, with a hint about
+/// enabling long path support on Windows
+pub fn path_too_long_warning(path: &str) -> String {
+    let warning = crate::i18n::tf("msg_path_too_long", &[path]);
+    if cfg!(windows) {
+        format!(
+            "{} {}",
+            warning,
+            crate::i18n::t("msg_long_path_hint_windows")
+        )
+    } else {
+        warning
+    }
+}
+
+/// Outcome of adding paths read from a list, one per line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BulkAddReport {
+    pub added: usize,
+    /// Already present
+    pub skipped: usize,
+    /// Rejected, such as target files of an unsupported format
+    pub invalid: usize,
+}
+
+/// Output style for the two phases of a rename
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RenameDisplay {
+    /// Print "rename started" and "rename completed" as separate lines
+    #[default]
+    Verbose,
+    /// Pair a rename's `From` with its `To` and print a single line
+    Combined,
 }
 
 impl Default for Config {
@@ -19,14 +216,30 @@ impl Default for Config {
         Self {
             watch_paths: vec![],
             recursive: true,
-            ignore_patterns: vec![
-                "*.tmp".to_string(),
-                "*.log".to_string(),
-                ".git/**".to_string(),
-                "target/**".to_string(),
-            ],
+            ignore_patterns: DEFAULT_IGNORE_PATTERNS.map(String::from).to_vec(),
             language: None,
             target_files: vec![],
+            watch_labels: BTreeMap::new(),
+            watch_ignores: BTreeMap::new(),
+            rename_display: RenameDisplay::default(),
+            case_insensitive_tracking: false,
+            respect_chaserignore: false,
+            require_nonempty_targets: false,
+            csv_comment_char: None,
+            ignore_hidden: false,
+            report_access: false,
+            track_history: false,
+            watch_parent_for_renames: None,
+            write_strategy: WriteStrategy::default(),
+            line_ending: LineEnding::default(),
+            max_paths_per_event: None,
+            default_monitor_args: vec![],
+            max_extract_depth: None,
+            theme: Theme::default(),
+            watch_new_subdirs: false,
+            rearm_deleted_files: None,
+            max_watch_paths: None,
+            max_ignore_patterns: None,
         }
     }
 }
@@ -34,67 +247,105 @@ impl Default for Config {
 impl Config {
     /// Get the config file path (cross-platform)
     pub fn config_file_path() -> Result<PathBuf> {
-        let config_dir = dirs::config_dir().context("Failed to get config directory")?;
-        let app_config_dir = config_dir.join("chaser");
+        let path = Self::config_path_in(&Self::app_config_dir()?, active_profile().as_deref());
+        if let Some(parent) = path.parent() {
+            Self::ensure_config_dir_exists(parent)?;
+        }
+        Ok(path)
+    }
 
-        Self::ensure_config_dir_exists(&app_config_dir)?;
-        Ok(app_config_dir.join("config.yaml"))
+    /// The `chaser` directory inside the platform config directory
+    pub fn app_config_dir() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir().context("Failed to get config directory")?;
+        Ok(config_dir.join("chaser"))
     }
 
-    fn ensure_config_dir_exists(dir: &Path) -> Result<()> {
-        if !dir.exists() {
-            fs::create_dir_all(dir).context("Failed to create config directory")?;
+    /// Config file for `profile` below `app_config_dir`
+    pub fn config_path_in(app_config_dir: &Path, profile: Option<&str>) -> PathBuf {
+        match profile {
+            Some(name) => app_config_dir
+                .join("profiles")
+                .join(format!("{}.yaml", name)),
+            None => app_config_dir.join("config.yaml"),
         }
-        Ok(())
     }
 
-    /// Load config from file, create default if not exists
-    pub fn load() -> Result<Self> {
-        let config_path = Self::config_file_path()?;
-
-        if config_path.exists() {
-            let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
+    /// Names of the profiles that have a config file
+    pub fn list_profiles() -> Result<Vec<String>> {
+        Self::list_profiles_in(&Self::app_config_dir()?)
+    }
 
-            let config: Config =
-                serde_yaml_ng::from_str(&content).context("Failed to parse config file")?;
+    pub fn list_profiles_in(app_config_dir: &Path) -> Result<Vec<String>> {
+        let profiles_dir = app_config_dir.join("profiles");
+        if !profiles_dir.exists() {
+            return Ok(Vec::new());
+        }
 
-            eprintln!(
-                "{} {}",
-                "✓".green(),
-                format!("Loaded config from: {}", config_path.display()).bright_white()
-            );
-            Ok(config)
-        } else {
-            let default_config = Self::default();
-            default_config.save()?;
-            eprintln!(
-                "{} {}",
-                "✓".green(),
-                format!("Created default config at: {}", config_path.display()).bright_white()
-            );
-            Ok(default_config)
+        let mut profiles = Vec::new();
+        for entry in fs::read_dir(&profiles_dir).context("Failed to read profiles directory")? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "yaml")
+                && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+            {
+                profiles.push(name.to_string());
+            }
         }
+        profiles.sort();
+        Ok(profiles)
     }
 
-    /// Save config to file
-    pub fn save(&self) -> Result<()> {
-        let config_path = Self::config_file_path()?;
+    /// Read a config file without printing anything
+    pub fn read_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).context("Failed to read config file")?;
+        serde_yaml_ng::from_str(&content).context("Failed to parse config file")
+    }
 
+    /// Write a config file without printing anything
+    pub fn write_to(&self, path: &Path) -> Result<()> {
         let content = serde_yaml_ng::to_string(self).context("Failed to serialize config")?;
+        crate::write_mode::write_file(path, self.line_ending.apply(&content))
+            .context("Failed to write config file")
+    }
 
-        fs::write(&config_path, content).context("Failed to write config file")?;
-
-        eprintln!(
-            "{} {}",
-            "✓".green(),
-            format!("Config saved to: {}", config_path.display()).bright_white()
-        );
+    fn ensure_config_dir_exists(dir: &Path) -> Result<()> {
+        if !dir.exists() {
+            crate::write_mode::create_dir_all(dir).context("Failed to create config directory")?;
+        }
         Ok(())
     }
 
+    /// Load config from file, or the default if there is none, without
+    /// printing or creating anything
+    ///
+    /// For reading settings before i18n is initialized; the `*_with_i18n` and
+    /// readonly loaders report loading and creating the file afterwards.
+    pub fn load_quiet() -> Result<Self> {
+        Self::load_quiet_at(&Self::config_path_in(
+            &Self::app_config_dir()?,
+            active_profile().as_deref(),
+        ))
+    }
+
+    /// `load_quiet` for an explicit config file path
+    pub fn load_quiet_at(config_path: &Path) -> Result<Self> {
+        if config_path.exists() {
+            Self::read_from(config_path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
     /// Add a watch path
     pub fn add_path(&mut self, path: String) -> Result<()> {
         if !self.watch_paths.contains(&path) {
+            if let Some(max) = self.max_watch_paths
+                && self.watch_paths.len() >= max
+            {
+                anyhow::bail!(crate::i18n::tf(
+                    "msg_error_watch_path_cap",
+                    &[&max.to_string()]
+                ));
+            }
             self.watch_paths.push(path.clone());
             println!("{}", crate::i18n::tf("msg_path_added", &[&path]).green());
         } else {
@@ -103,10 +354,49 @@ impl Config {
         Ok(())
     }
 
+    /// Remove every ignore pattern, defaults included, or start over from the
+    /// defaults with `keep_defaults`; returns how many patterns were removed
+    pub fn clear_ignore_patterns(&mut self, keep_defaults: bool) -> usize {
+        let removed = std::mem::take(&mut self.ignore_patterns);
+        if keep_defaults {
+            self.ignore_patterns = DEFAULT_IGNORE_PATTERNS.map(String::from).to_vec();
+        }
+        removed
+            .iter()
+            .filter(|pattern| !self.ignore_patterns.contains(pattern))
+            .count()
+    }
+
+    /// Whether every default ignore pattern is still in effect
+    pub fn includes_default_ignores(&self) -> bool {
+        DEFAULT_IGNORE_PATTERNS
+            .iter()
+            .all(|default| self.ignore_patterns.iter().any(|p| p == default))
+    }
+
+    /// Add an ignore pattern, returning false if it was already present
+    pub fn add_ignore_pattern(&mut self, pattern: String) -> Result<bool> {
+        if self.ignore_patterns.contains(&pattern) {
+            return Ok(false);
+        }
+        if let Some(max) = self.max_ignore_patterns
+            && self.ignore_patterns.len() >= max
+        {
+            anyhow::bail!(crate::i18n::tf(
+                "msg_error_ignore_pattern_cap",
+                &[&max.to_string()]
+            ));
+        }
+        self.ignore_patterns.push(pattern);
+        Ok(true)
+    }
+
     /// Remove a watch path
     pub fn remove_path(&mut self, path: &str) -> Result<()> {
         if let Some(pos) = self.watch_paths.iter().position(|p| p == path) {
             self.watch_paths.remove(pos);
+            self.watch_labels.remove(path);
+            self.watch_ignores.remove(path);
             println!("{}", crate::i18n::tf("msg_path_removed", &[path]).green());
         } else {
             println!("{}", crate::i18n::tf("msg_path_not_found", &[path]).red());
@@ -114,11 +404,36 @@ impl Config {
         Ok(())
     }
 
+    /// Set or clear the label of a watch path
+    pub fn set_label(&mut self, path: &str, label: Option<String>) {
+        match label {
+            Some(label) => {
+                self.watch_labels.insert(path.to_string(), label);
+            }
+            None => {
+                self.watch_labels.remove(path);
+            }
+        }
+    }
+
+    /// Get the label of a watch path, if any
+    pub fn label_for(&self, path: &str) -> Option<&str> {
+        self.watch_labels.get(path).map(|s| s.as_str())
+    }
+
     /// List all watch paths
     pub fn list_paths(&self) {
         println!("{}", crate::i18n::t("ui_watch_paths").bright_cyan().bold());
         for (i, path) in self.watch_paths.iter().enumerate() {
-            println!("  {}. {}", format!("{}", i + 1).bright_white(), path.cyan());
+            match self.label_for(path) {
+                Some(label) => println!(
+                    "  {}. {} {}",
+                    format!("{}", i + 1).bright_white(),
+                    path.cyan(),
+                    format!("[{}]", label).dimmed()
+                ),
+                None => println!("  {}. {}", format!("{}", i + 1).bright_white(), path.cyan()),
+            }
         }
 
         println!("\n{}", crate::i18n::t("ui_settings").bright_cyan().bold());
@@ -153,10 +468,7 @@ impl Config {
         let config_path = Self::config_file_path()?;
 
         if config_path.exists() {
-            let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
-
-            let config: Config =
-                serde_yaml_ng::from_str(&content).context("Failed to parse config file")?;
+            let config = Self::read_from(&config_path)?;
 
             println!(
                 "{}",
@@ -182,13 +494,60 @@ impl Config {
         }
     }
 
+    /// Load config like `load_with_i18n`, but fall back to an in-memory
+    /// default with a warning when the config directory isn't writable
+    pub fn load_or_default_readonly() -> Result<Self> {
+        let config_path =
+            Self::config_path_in(&Self::app_config_dir()?, active_profile().as_deref());
+        Self::load_or_default_at(&config_path)
+    }
+
+    /// `load_or_default_readonly` for an explicit config file path
+    pub fn load_or_default_at(config_path: &Path) -> Result<Self> {
+        if config_path.exists() {
+            let config = Self::read_from(config_path)?;
+            println!(
+                "{}",
+                crate::i18n::tf(
+                    "msg_config_loaded",
+                    &[&config_path.display().to_string().cyan().to_string()]
+                )
+                .green()
+            );
+            return Ok(config);
+        }
+
+        let default_config = Self::default();
+        let created = config_path
+            .parent()
+            .map_or(Ok(()), Self::ensure_config_dir_exists)
+            .and_then(|()| default_config.write_to(config_path));
+        match created {
+            Ok(()) => println!(
+                "{}",
+                crate::i18n::tf(
+                    "msg_config_created",
+                    &[&config_path.display().to_string().cyan().to_string()]
+                )
+                .green()
+            ),
+            Err(e) => eprintln!(
+                "{}",
+                crate::i18n::tf(
+                    "msg_config_readonly",
+                    &[&config_path.display().to_string(), &format!("{:#}", e)]
+                )
+                .yellow()
+            ),
+        }
+        Ok(default_config)
+    }
+
     /// Save config with i18n messages (use after i18n is initialized)
     pub fn save_with_i18n(&self) -> Result<()> {
         let config_path = Self::config_file_path()?;
 
-        let content = serde_yaml_ng::to_string(self).context("Failed to serialize config")?;
-
-        fs::write(&config_path, content).context("Failed to write config file")?;
+        self.write_to(&config_path)?;
 
         println!(
             "{}",
@@ -211,7 +570,7 @@ impl Config {
             lang.clone()
         } else {
             // Get system locale - simplified version
-            if let Some(locale) = std::env::var("LANG").ok() {
+            if let Ok(locale) = std::env::var("LANG") {
                 let locale_lower = locale.to_lowercase();
                 if locale_lower.starts_with("zh")
                     && (locale_lower.contains("cn") || locale_lower.contains("hans"))
@@ -231,8 +590,13 @@ impl Config {
         let mut invalid_paths = Vec::new();
 
         for path in &self.watch_paths {
-            if !Path::new(path).exists() {
-                invalid_paths.push(path.clone());
+            match fs::metadata(long_path(Path::new(path))) {
+                Ok(_) => {}
+                // The path may well exist; the OS just can't say
+                Err(e) if is_path_too_long(&e) => {
+                    eprintln!("{}", path_too_long_warning(path).themed(Role::Warn));
+                }
+                Err(_) => invalid_paths.push(path.clone()),
             }
         }
 
@@ -247,6 +611,87 @@ impl Config {
         Ok(())
     }
 
+    /// Drop target files that are another spelling of an earlier entry,
+    /// returning each dropped entry with the entry it duplicates
+    pub fn deduplicate_target_files(&mut self) -> Vec<(String, String)> {
+        let mut seen: HashMap<PathBuf, String> = HashMap::new();
+        let mut collapsed = Vec::new();
+        self.target_files.retain(|target_file| {
+            let key = Self::target_file_key(target_file);
+            match seen.get(&key) {
+                Some(kept) => {
+                    collapsed.push((target_file.clone(), kept.clone()));
+                    false
+                }
+                None => {
+                    seen.insert(key, target_file.clone());
+                    true
+                }
+            }
+        });
+        collapsed
+    }
+
+    /// The configured target file that is the same file as `target_file`,
+    /// however either is spelled
+    pub fn find_target_file(&self, target_file: &str) -> Option<&str> {
+        let key = Self::target_file_key(target_file);
+        self.target_files
+            .iter()
+            .find(|existing| Self::target_file_key(existing) == key)
+            .map(String::as_str)
+    }
+
+    /// What two spellings of one target file have in common
+    fn target_file_key(target_file: &str) -> PathBuf {
+        let path = &expand_path(target_file);
+        path.canonicalize()
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    /// Add each non-empty line of `input` as a watch path
+    pub fn add_paths_from(&mut self, input: impl BufRead) -> Result<BulkAddReport> {
+        let mut report = BulkAddReport::default();
+        for line in input.lines() {
+            let line = line?;
+            let path = line.trim();
+            if path.is_empty() {
+                continue;
+            }
+            if self.watch_paths.iter().any(|existing| existing == path) {
+                report.skipped += 1;
+            } else {
+                report.added += 1;
+            }
+            self.add_path(path.to_string())?;
+        }
+        Ok(report)
+    }
+
+    /// Add each non-empty line of `input` as a target file, rejecting
+    /// unsupported formats
+    pub fn add_target_files_from(&mut self, input: impl BufRead) -> Result<BulkAddReport> {
+        let mut report = BulkAddReport::default();
+        for line in input.lines() {
+            let line = line?;
+            let file = line.trim();
+            if file.is_empty() {
+                continue;
+            }
+            if let Err(e) = TargetFileFormat::from_path(Path::new(file)) {
+                eprintln!("{}", e.to_string().themed(Role::Warn));
+                report.invalid += 1;
+            } else if self.target_files.iter().any(|existing| existing == file) {
+                report.skipped += 1;
+            } else {
+                self.add_target_file(file.to_string())?;
+                report.added += 1;
+            }
+        }
+        Ok(report)
+    }
+
     /// Remove a target file
     pub fn remove_target_file(&mut self, target_file: &str) -> Result<()> {
         self.target_files.retain(|p| p != target_file);
@@ -258,6 +703,35 @@ impl Config {
         &self.target_files
     }
 
+    /// Layer `CHASER_*` environment variables over the loaded config
+    ///
+    /// List variables (`CHASER_WATCH_PATHS`, `CHASER_TARGET_FILES`,
+    /// `CHASER_IGNORE`) use the platform path separator, `:` or `;`. Only call
+    /// this on configs that are not saved again, or the overrides are persisted.
+    pub fn apply_env_overrides(&mut self) -> Result<()> {
+        self.apply_overrides_from(|name| std::env::var(name).ok())
+    }
+
+    fn apply_overrides_from(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(paths) = var(ENV_WATCH_PATHS) {
+            self.watch_paths = split_env_list(&paths);
+        }
+        if let Some(files) = var(ENV_TARGET_FILES) {
+            self.target_files = split_env_list(&files);
+        }
+        if let Some(patterns) = var(ENV_IGNORE) {
+            self.ignore_patterns = split_env_list(&patterns);
+        }
+        if let Some(recursive) = var(ENV_RECURSIVE) {
+            self.recursive = parse_bool_flexible(&recursive)
+                .with_context(|| format!("Invalid {}: '{}'", ENV_RECURSIVE, recursive))?;
+        }
+        if let Some(language) = var(ENV_LANG).filter(|lang| !lang.is_empty()) {
+            self.language = Some(language);
+        }
+        Ok(())
+    }
+
     /// Validate target files have at least one entry
     pub fn validate_target_files(&self) -> Result<()> {
         if self.target_files.is_empty() {
@@ -281,17 +755,107 @@ mod tests {
         let config_dir = temp_dir.path().join("chaser");
         fs::create_dir_all(&config_dir).unwrap();
 
-        let mut config = Config::default();
-        config.watch_paths = vec![temp_dir.path().to_string_lossy().to_string()];
+        let config = Config {
+            watch_paths: vec![temp_dir.path().to_string_lossy().to_string()],
+            ..Default::default()
+        };
 
         (config, temp_dir)
     }
 
+    #[test]
+    fn test_path_too_long_detection() {
+        assert!(is_path_too_long(&io::Error::from(
+            io::ErrorKind::InvalidFilename
+        )));
+        assert!(!is_path_too_long(&io::Error::from(io::ErrorKind::NotFound)));
+        assert_eq!(
+            long_path(Path::new("relative/a")),
+            PathBuf::from("relative/a")
+        );
+
+        // Far beyond PATH_MAX; on Windows the prefix lifts the limit instead
+        let long = format!("/{}", vec!["d".repeat(200); 40].join("/"));
+        if cfg!(unix) {
+            let error = fs::metadata(long_path(Path::new(&long))).unwrap_err();
+            assert!(is_path_too_long(&error), "{:?}", error);
+
+            // Warned about rather than reported as missing
+            let config = Config {
+                watch_paths: vec![long, "/definitely/missing/path".to_string()],
+                ..Default::default()
+            };
+            assert_eq!(config.validate_paths(), vec!["/definitely/missing/path"]);
+        }
+    }
+
+    #[test]
+    fn test_load_or_default_at_survives_unwritable_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        // A regular file where the config directory should be makes every
+        // write below it fail, even when running as root
+        let blocker = temp_dir.path().join("chaser");
+        fs::write(&blocker, "").unwrap();
+        let config_path = blocker.join("config.yaml");
+
+        let config = Config::load_or_default_at(&config_path).unwrap();
+
+        assert_eq!(config.watch_paths, Config::default().watch_paths);
+        assert!(!config_path.exists());
+    }
+
+    #[test]
+    fn test_load_quiet_at_creates_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("chaser");
+        let config_path = config_dir.join("config.yaml");
+
+        // Without a config file: the default, and no file or directory to report
+        let config = Config::load_quiet_at(&config_path).unwrap();
+        assert_eq!(config, Config::default());
+        assert!(!config_dir.exists());
+
+        // With one: its settings, and the file left as it was
+        fs::create_dir_all(&config_dir).unwrap();
+        let saved = Config {
+            language: Some("zh-cn".to_string()),
+            ..Default::default()
+        };
+        saved.write_to(&config_path).unwrap();
+        let written = fs::read_to_string(&config_path).unwrap();
+        assert_eq!(Config::load_quiet_at(&config_path).unwrap(), saved);
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), written);
+    }
+
+    #[test]
+    fn test_write_to_uses_line_ending() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.yaml");
+
+        let config = Config {
+            line_ending: LineEnding::CrLf,
+            ..Default::default()
+        };
+        config.write_to(&config_path).unwrap();
+        let bytes = fs::read(&config_path).unwrap();
+        let newlines = bytes.iter().filter(|b| **b == b'\n').count();
+        assert!(newlines > 0);
+        assert_eq!(bytes.windows(2).filter(|w| w == b"\r\n").count(), newlines);
+        assert_eq!(Config::read_from(&config_path).unwrap(), config);
+
+        let config = Config {
+            line_ending: LineEnding::Lf,
+            ..Default::default()
+        };
+        config.write_to(&config_path).unwrap();
+        assert!(!fs::read(&config_path).unwrap().contains(&b'\r'));
+    }
+
     #[test]
     fn test_config_default() {
         let config = Config::default();
         assert_eq!(config.watch_paths, Vec::<String>::new());
-        assert_eq!(config.recursive, true);
+        assert!(config.recursive);
         assert_eq!(
             config.ignore_patterns,
             vec!["*.tmp", "*.log", ".git/**", "target/**"]
@@ -324,6 +888,65 @@ mod tests {
         assert!(path.file_name().unwrap() == "config.yaml");
     }
 
+    #[test]
+    fn test_config_path_in_profile() {
+        let dir = Path::new("/cfg/chaser");
+        assert_eq!(Config::config_path_in(dir, None), dir.join("config.yaml"));
+        assert_eq!(
+            Config::config_path_in(dir, Some("work")),
+            dir.join("profiles").join("work.yaml")
+        );
+    }
+
+    #[test]
+    fn test_profiles_are_isolated() {
+        let temp_dir = TempDir::new().unwrap();
+        let work_path = Config::config_path_in(temp_dir.path(), Some("work"));
+        let home_path = Config::config_path_in(temp_dir.path(), Some("home"));
+        fs::create_dir_all(work_path.parent().unwrap()).unwrap();
+
+        let mut work = Config::default();
+        work.add_path("./work".to_string()).unwrap();
+        work.write_to(&work_path).unwrap();
+
+        let mut home = Config::default();
+        home.add_path("./home".to_string()).unwrap();
+        home.write_to(&home_path).unwrap();
+
+        assert_eq!(
+            Config::read_from(&work_path).unwrap().watch_paths,
+            vec!["./work"]
+        );
+        assert_eq!(
+            Config::read_from(&home_path).unwrap().watch_paths,
+            vec!["./home"]
+        );
+        assert_eq!(
+            Config::list_profiles_in(temp_dir.path()).unwrap(),
+            vec!["home", "work"]
+        );
+    }
+
+    #[test]
+    fn test_list_profiles_without_profiles_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(
+            Config::list_profiles_in(temp_dir.path())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("client-a_2").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../escape").is_err());
+        assert!(validate_profile_name("a/b").is_err());
+        assert!(validate_profile_name(".hidden").is_err());
+    }
+
     #[test]
     fn test_add_path() {
         let mut config = Config::default();
@@ -341,6 +964,72 @@ mod tests {
         assert_eq!(config.watch_paths.len(), initial_count + 1);
     }
 
+    #[test]
+    fn test_caps_reject_growth_beyond_limit() {
+        let mut config = Config {
+            watch_paths: vec!["./a".to_string()],
+            max_watch_paths: Some(2),
+            max_ignore_patterns: Some(4),
+            ..Default::default()
+        };
+
+        assert!(config.add_path("./b".to_string()).is_ok());
+        assert!(config.add_path("./c".to_string()).is_err());
+        // Re-adding a listed path is still fine at the cap
+        assert!(config.add_path("./a".to_string()).is_ok());
+        assert_eq!(config.watch_paths, vec!["./a", "./b"]);
+
+        // The defaults already hold four patterns
+        assert!(!config.add_ignore_pattern("*.tmp".to_string()).unwrap());
+        assert!(config.add_ignore_pattern("*.bak".to_string()).is_err());
+        assert_eq!(config.ignore_patterns.len(), 4);
+
+        config.max_ignore_patterns = None;
+        assert!(config.add_ignore_pattern("*.bak".to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_clear_ignore_patterns() {
+        let mut config = Config::default();
+        assert!(config.includes_default_ignores());
+
+        assert_eq!(config.clear_ignore_patterns(false), 4);
+        assert!(config.ignore_patterns.is_empty());
+        assert!(!config.includes_default_ignores());
+        assert!(config.add_ignore_pattern("*.bak".to_string()).unwrap());
+        assert_eq!(config.ignore_patterns, vec!["*.bak"]);
+
+        // Starting over from the defaults only removes the extra pattern
+        assert_eq!(config.clear_ignore_patterns(true), 1);
+        assert_eq!(config.ignore_patterns, DEFAULT_IGNORE_PATTERNS);
+    }
+
+    #[test]
+    fn test_add_from_line_lists() {
+        let mut config = Config::default();
+        let paths = std::io::Cursor::new("./a\n./b\n\n./a\n");
+        assert_eq!(
+            config.add_paths_from(paths).unwrap(),
+            BulkAddReport {
+                added: 2,
+                skipped: 1,
+                invalid: 0
+            }
+        );
+        assert_eq!(config.watch_paths, vec!["./a", "./b"]);
+
+        let files = std::io::Cursor::new("a.json\nb.yml\nnotes.txt\na.json\n");
+        assert_eq!(
+            config.add_target_files_from(files).unwrap(),
+            BulkAddReport {
+                added: 2,
+                skipped: 1,
+                invalid: 1
+            }
+        );
+        assert_eq!(config.target_files, vec!["a.json", "b.yml"]);
+    }
+
     #[test]
     fn test_remove_path() {
         let mut config = Config::default();
@@ -359,6 +1048,94 @@ mod tests {
         assert_eq!(config.watch_paths.len(), initial_count - 1);
     }
 
+    #[test]
+    fn test_watch_labels_round_trip() {
+        let mut config = Config {
+            watch_paths: vec!["./labeled".to_string(), "./plain".to_string()],
+            ..Default::default()
+        };
+        config.set_label("./labeled", Some("assets".to_string()));
+
+        let yaml_str = serde_yaml_ng::to_string(&config).unwrap();
+        let deserialized: Config = serde_yaml_ng::from_str(&yaml_str).unwrap();
+        assert_eq!(config, deserialized);
+        assert_eq!(deserialized.label_for("./labeled"), Some("assets"));
+        assert_eq!(deserialized.label_for("./plain"), None);
+
+        // Unlabeled configs don't serialize the field at all
+        let unlabeled = Config::default();
+        let yaml_str = serde_yaml_ng::to_string(&unlabeled).unwrap();
+        assert!(!yaml_str.contains("watch_labels"));
+    }
+
+    #[test]
+    fn test_watch_ignores_round_trip() {
+        let yaml_str = r#"
+watch_paths:
+  - ./web
+  - ./docs
+recursive: true
+ignore_patterns: ["*.tmp"]
+language: null
+watch_ignores:
+  ./web:
+    - node_modules/**
+"#;
+        let mut config: Config = serde_yaml_ng::from_str(yaml_str).unwrap();
+        assert_eq!(config.watch_ignores["./web"], vec!["node_modules/**"]);
+        let round_trip: Config =
+            serde_yaml_ng::from_str(&serde_yaml_ng::to_string(&config).unwrap()).unwrap();
+        assert_eq!(config, round_trip);
+
+        // Removing the watch path drops its patterns
+        config.remove_path("./web").unwrap();
+        assert!(config.watch_ignores.is_empty());
+        assert!(
+            !serde_yaml_ng::to_string(&config)
+                .unwrap()
+                .contains("watch_ignores")
+        );
+    }
+
+    #[test]
+    fn test_watch_labels_backward_compatible() {
+        // Config written before labels existed
+        let yaml_str = r#"
+watch_paths:
+  - ./old
+recursive: true
+ignore_patterns: []
+language: null
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml_str).unwrap();
+        assert_eq!(config.watch_paths, vec!["./old"]);
+        assert!(config.watch_labels.is_empty());
+        assert_eq!(config.rename_display, RenameDisplay::Verbose);
+    }
+
+    #[test]
+    fn test_rename_display_from_yaml() {
+        let yaml_str = r#"
+watch_paths: []
+recursive: true
+ignore_patterns: []
+language: null
+rename_display: combined
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml_str).unwrap();
+        assert_eq!(config.rename_display, RenameDisplay::Combined);
+    }
+
+    #[test]
+    fn test_remove_path_clears_label() {
+        let mut config = Config::default();
+        config.add_path("./labeled".to_string()).unwrap();
+        config.set_label("./labeled", Some("docs".to_string()));
+
+        config.remove_path("./labeled").unwrap();
+        assert_eq!(config.label_for("./labeled"), None);
+    }
+
     #[test]
     fn test_set_language() {
         let mut config = Config::default();
@@ -416,6 +1193,110 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial]
+    fn test_apply_env_overrides() {
+        let separator = if cfg!(windows) { ";" } else { ":" };
+        let mut config = Config {
+            watch_paths: vec!["./from-file".to_string()],
+            target_files: vec!["targets.json".to_string()],
+            ..Default::default()
+        };
+
+        unsafe {
+            env::set_var(ENV_WATCH_PATHS, ["./a", "", "./b"].join(separator));
+            env::set_var(ENV_IGNORE, "*.bak");
+            env::set_var(ENV_RECURSIVE, "off");
+            env::set_var(ENV_LANG, "zh-cn");
+        }
+        let result = config.apply_env_overrides();
+        unsafe {
+            for name in [ENV_WATCH_PATHS, ENV_IGNORE, ENV_RECURSIVE, ENV_LANG] {
+                env::remove_var(name);
+            }
+        }
+        result.unwrap();
+
+        assert_eq!(config.watch_paths, vec!["./a", "./b"]);
+        assert_eq!(config.ignore_patterns, vec!["*.bak"]);
+        assert!(!config.recursive);
+        assert_eq!(config.language.as_deref(), Some("zh-cn"));
+        // Unset variables leave the file config alone
+        assert_eq!(config.target_files, vec!["targets.json"]);
+    }
+
+    #[test]
+    fn test_parse_bool_flexible() {
+        for value in [
+            "true", "1", "yes", "y", "on", "enable", " TRUE ", "Yes", "ENABLE\n",
+        ] {
+            assert_eq!(parse_bool_flexible(value), Some(true), "{:?}", value);
+        }
+        for value in [
+            "false",
+            "0",
+            "no",
+            "n",
+            "off",
+            "disable",
+            " Off",
+            "N",
+            "\tDisable",
+        ] {
+            assert_eq!(parse_bool_flexible(value), Some(false), "{:?}", value);
+        }
+        for value in ["", "sometimes", "2", "enabled", "y es"] {
+            assert_eq!(parse_bool_flexible(value), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_apply_env_overrides_rejects_bad_bool() {
+        let mut config = Config::default();
+        let result = config
+            .apply_overrides_from(|name| (name == ENV_RECURSIVE).then(|| "sometimes".to_string()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_deduplicate_target_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("sub")).unwrap();
+        let target = temp_dir.path().join("targets.json");
+        fs::write(&target, "[]").unwrap();
+        let other_spelling = temp_dir.path().join("sub").join("..").join("targets.json");
+
+        let mut config = Config {
+            target_files: vec![
+                target.display().to_string(),
+                other_spelling.display().to_string(),
+                "/nonexistent/other.json".to_string(),
+            ],
+            ..Default::default()
+        };
+        let collapsed = config.deduplicate_target_files();
+
+        assert_eq!(
+            collapsed,
+            vec![(
+                other_spelling.display().to_string(),
+                target.display().to_string()
+            )]
+        );
+        assert_eq!(
+            config.target_files,
+            vec![
+                target.display().to_string(),
+                "/nonexistent/other.json".to_string()
+            ]
+        );
+        assert_eq!(
+            config.find_target_file(&other_spelling.display().to_string()),
+            Some(target.display().to_string().as_str())
+        );
+        assert_eq!(config.find_target_file("/nonexistent/third.json"), None);
+    }
+
     #[test]
     fn test_validate_paths() {
         let (mut config, temp_dir) = create_test_config_with_temp_dir();
@@ -448,11 +1329,13 @@ mod tests {
         let config_path = temp_dir.path().join("config.yaml");
 
         // Create a test config
-        let mut original_config = Config::default();
-        original_config.watch_paths = vec!["./test1".to_string(), "./test2".to_string()];
-        original_config.recursive = false;
-        original_config.ignore_patterns = vec!["*.test".to_string()];
-        original_config.language = Some("zh-cn".to_string());
+        let original_config = Config {
+            watch_paths: vec!["./test1".to_string(), "./test2".to_string()],
+            recursive: false,
+            ignore_patterns: vec!["*.test".to_string()],
+            language: Some("zh-cn".to_string()),
+            ..Default::default()
+        };
 
         // Save config
         let yaml_content = serde_yaml_ng::to_string(&original_config).unwrap();
@@ -3,6 +3,40 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use sys_locale::get_locale;
 
+/// Locale used as the fallback and listed first among available locales
+const DEFAULT_LOCALE: &str = "en";
+
+/// Locale tag prefixes and the supported locale they select, most specific first
+const LOCALE_ALIASES: &[(&str, &str)] = &[
+    // Only simplified Chinese is translated, so traditional tags use it until a
+    // traditional locale is added
+    ("zh-hant", "zh-cn"),
+    ("zh-tw", "zh-cn"),
+    ("zh-hk", "zh-cn"),
+    ("zh", "zh-cn"),
+    ("en", "en"),
+    ("c", "en"),
+    ("posix", "en"),
+];
+
+/// Separator between list items when no locale provides one
+const DEFAULT_LIST_SEPARATOR: &str = ", ";
+/// Separator between groups of three digits when no locale provides one
+const DEFAULT_DIGIT_GROUP_SEPARATOR: &str = ",";
+
+/// `n` with `separator` between groups of three digits
+fn group_digits(n: u64, separator: &str) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Locale {
     strings: HashMap<String, String>,
@@ -63,8 +97,11 @@ impl I18n {
         &self.current_locale
     }
 
+    /// List loaded locales in a stable order: the default `en` first, then alphabetical
     pub fn available_locales(&self) -> Vec<&str> {
-        self.locales.keys().map(|s| s.as_str()).collect()
+        let mut locales: Vec<&str> = self.locales.keys().map(|s| s.as_str()).collect();
+        locales.sort_by_key(|locale| (*locale != DEFAULT_LOCALE, *locale));
+        locales
     }
 
     pub fn t(&self, key: &str) -> String {
@@ -72,7 +109,7 @@ impl I18n {
             locale
                 .strings
                 .get(key)
-                .map(|s| s.clone())
+                .cloned()
                 .unwrap_or_else(|| key.to_string())
         } else {
             key.to_string()
@@ -90,34 +127,67 @@ impl I18n {
         result
     }
 
+    /// A formatting string of the current locale, or `default` if it has none
+    fn format_string(&self, key: &str, default: &str) -> String {
+        self.locales
+            .get(&self.current_locale)
+            .and_then(|locale| locale.strings.get(key))
+            .cloned()
+            .unwrap_or_else(|| default.to_string())
+    }
+
+    /// `items` joined with the current locale's list separator
+    pub fn format_list<S: AsRef<str>>(&self, items: &[S]) -> String {
+        let separator = self.format_string("fmt_list_separator", DEFAULT_LIST_SEPARATOR);
+        items
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<_>>()
+            .join(&separator)
+    }
+
+    /// `n` with the current locale's digit grouping, e.g. `12,345`
+    pub fn format_number(&self, n: u64) -> String {
+        let separator =
+            self.format_string("fmt_digit_group_separator", DEFAULT_DIGIT_GROUP_SEPARATOR);
+        group_digits(n, &separator)
+    }
+
     fn get_system_locale() -> String {
-        if let Ok(lang) = std::env::var("LANG") {
-            if let Some(locale) = Self::parse_locale(&lang) {
-                return locale;
-            }
+        if let Ok(lang) = std::env::var("LANG")
+            && !lang.is_empty()
+        {
+            // A set but unsupported LANG falls back to the default locale
+            return Self::parse_locale(&lang).unwrap_or_else(|| DEFAULT_LOCALE.to_string());
         }
 
-        if let Some(locale) = get_locale() {
-            if let Some(parsed) = Self::parse_locale(&locale) {
-                return parsed;
-            }
+        if let Some(locale) = get_locale()
+            && let Some(parsed) = Self::parse_locale(&locale)
+        {
+            return parsed;
         }
 
         "en".to_string()
     }
 
+    /// The supported locale for a POSIX locale (`zh_TW.UTF-8@euro`) or BCP-47
+    /// tag (`zh-Hant-TW`), or None when no supported locale matches
     fn parse_locale(locale_str: &str) -> Option<String> {
-        let locale_lower = locale_str.to_lowercase();
-
-        if locale_lower.starts_with("zh") {
-            Some("zh-cn".to_string())
-        } else if locale_lower.starts_with("en") {
-            Some("en".to_string())
-        } else if locale_lower.starts_with("fr") {
-            Some("en".to_string())
-        } else {
-            None
-        }
+        let tag = locale_str
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .replace('_', "-")
+            .to_lowercase();
+
+        LOCALE_ALIASES
+            .iter()
+            .find(|(prefix, _)| {
+                tag.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+            })
+            .map(|(_, locale)| locale.to_string())
     }
 
     pub fn is_locale_supported(&self, locale: &str) -> bool {
@@ -144,65 +214,87 @@ pub fn init_i18n_with_locale(locale: &str) -> Result<()> {
 }
 
 pub fn set_locale(locale: &str) {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(mut i18n) = i18n_mutex.lock() {
-            i18n.set_locale(locale);
-        }
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(mut i18n) = i18n_mutex.lock()
+    {
+        i18n.set_locale(locale);
     }
 }
 
 pub fn get_current_locale() -> String {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(i18n) = i18n_mutex.lock() {
-            return i18n.get_current_locale().to_string();
-        }
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n.get_current_locale().to_string();
     }
     "en".to_string()
 }
 
 pub fn available_locales() -> Vec<String> {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(i18n) = i18n_mutex.lock() {
-            return i18n
-                .available_locales()
-                .iter()
-                .map(|s| s.to_string())
-                .collect();
-        }
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n
+            .available_locales()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
     }
     vec!["en".to_string()]
 }
 
 pub fn is_locale_supported(locale: &str) -> bool {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(i18n) = i18n_mutex.lock() {
-            return i18n.is_locale_supported(locale);
-        }
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n.is_locale_supported(locale);
     }
     false
 }
 
 pub fn t(key: &str) -> String {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(i18n) = i18n_mutex.lock() {
-            return i18n.t(key).to_string();
-        }
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n.t(key).to_string();
     }
     key.to_string()
 }
 
-pub fn tf(key: &str, args: &[&str]) -> String {
-    if let Some(i18n_mutex) = I18N.get() {
-        if let Ok(i18n) = i18n_mutex.lock() {
-            let template = i18n.t(key);
-            let mut result = template;
+pub fn format_list<S: AsRef<str>>(items: &[S]) -> String {
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n.format_list(items);
+    }
+    items
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(DEFAULT_LIST_SEPARATOR)
+}
+
+pub fn format_number(n: u64) -> String {
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        return i18n.format_number(n);
+    }
+    group_digits(n, DEFAULT_DIGIT_GROUP_SEPARATOR)
+}
 
-            for (i, arg) in args.iter().enumerate() {
-                result = result.replace(&format!("{{{}}}", i), arg);
-            }
+pub fn tf(key: &str, args: &[&str]) -> String {
+    if let Some(i18n_mutex) = I18N.get()
+        && let Ok(i18n) = i18n_mutex.lock()
+    {
+        let template = i18n.t(key);
+        let mut result = template;
 
-            return result;
+        for (i, arg) in args.iter().enumerate() {
+            result = result.replace(&format!("{{{}}}", i), arg);
         }
+
+        return result;
     }
 
     let mut result = key.to_string();
@@ -290,6 +382,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_locale() {
+        let parse = |locale: &str| I18n::parse_locale(locale);
+        assert_eq!(parse("zh-Hant").as_deref(), Some("zh-cn"));
+        assert_eq!(parse("zh-Hant-TW").as_deref(), Some("zh-cn"));
+        assert_eq!(parse("zh_TW.UTF-8").as_deref(), Some("zh-cn"));
+        assert_eq!(parse("zh-Hans-CN").as_deref(), Some("zh-cn"));
+        assert_eq!(parse("en-US").as_deref(), Some("en"));
+        assert_eq!(parse("C").as_deref(), Some("en"));
+        assert_eq!(parse("C.UTF-8").as_deref(), Some("en"));
+        assert_eq!(parse("POSIX").as_deref(), Some("en"));
+        assert_eq!(parse("de_DE"), None);
+        // Tags only match whole subtags
+        assert_eq!(parse("cy_GB"), None);
+        assert_eq!(parse("eng"), None);
+        assert_eq!(parse(""), None);
+    }
+
     #[test]
     fn test_set_locale() {
         let mut i18n = I18n {
@@ -387,6 +497,22 @@ mod tests {
         assert_eq!(i18n.tf("non_existing", &["test"]), "non_existing");
     }
 
+    #[test]
+    fn test_format_list_and_number_per_locale() {
+        let en = I18n::with_locale("en").unwrap();
+        let zh = I18n::with_locale("zh-cn").unwrap();
+        let items = ["json", "yaml", "toml"];
+
+        assert_eq!(en.format_list(&items), "json, yaml, toml");
+        assert_eq!(zh.format_list(&items), "json、yaml、toml");
+        assert_eq!(en.format_list::<&str>(&[]), "");
+
+        assert_eq!(en.format_number(1234567), "1,234,567");
+        assert_eq!(zh.format_number(999), "999");
+        assert_eq!(group_digits(1000, " "), "1 000");
+        assert_eq!(group_digits(0, ","), "0");
+    }
+
     #[test]
     fn test_available_locales() {
         let locales = available_locales();
@@ -396,6 +522,29 @@ mod tests {
         assert!(locales.contains(&"en".to_string()));
     }
 
+    #[test]
+    fn test_available_locales_order() {
+        let mut i18n = I18n {
+            current_locale: "en".to_string(),
+            locales: HashMap::new(),
+        };
+
+        for name in ["zh-cn", "ja", "en", "de", "fr"] {
+            i18n.locales.insert(
+                name.to_string(),
+                Locale {
+                    strings: HashMap::new(),
+                },
+            );
+        }
+
+        // "en" comes first, the rest alphabetically, regardless of HashMap order
+        assert_eq!(
+            i18n.available_locales(),
+            vec!["en", "de", "fr", "ja", "zh-cn"]
+        );
+    }
+
     #[test]
     fn test_is_locale_supported() {
         // Without proper initialization, the global functions return fallback values
@@ -0,0 +1,802 @@
+use crate::i18n::tf;
+use crate::{IgnoreRule, matches_relative_ignore_pattern, matching_ignore_pattern};
+use anyhow::{Context, Result, bail};
+use notify::Event;
+use notify::EventKind;
+use notify::event::{ModifyKind, RenameMode};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-project ignore file looked up at the root of each watch path
+pub const CHASERIGNORE_FILE: &str = ".chaserignore";
+
+/// Drop the paths of `event` that `is_ignored`, returning whether the event
+/// still has anything to report
+///
+/// Both paths of a rename pair are kept or dropped together, so a pair with
+/// an ignored side is dropped whole.
+fn retain_unignored_paths(event: &mut Event, mut is_ignored: impl FnMut(&Path) -> bool) -> bool {
+    if event.paths.is_empty() {
+        return true;
+    }
+    if matches!(
+        event.kind,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both))
+    ) {
+        return !event.paths.iter().any(|path| is_ignored(path));
+    }
+    event.paths.retain(|path| !is_ignored(path));
+    !event.paths.is_empty()
+}
+
+/// Read newline-delimited ignore patterns from a file such as `.chaserignore`
+///
+/// Blank lines and lines starting with `#` are skipped; surrounding whitespace
+/// is trimmed. Patterns are returned in file order.
+pub fn load_ignore_file(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read ignore file {}", path.display()))?;
+    Ok(parse_ignore_patterns(&content))
+}
+
+/// Parse ignore file content into patterns
+pub fn parse_ignore_patterns(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Append session patterns after the configured ones, skipping duplicates
+///
+/// Config patterns always come first so that, should negated patterns ever be
+/// supported, patterns from an ignore file can override the config.
+pub fn merge_ignore_patterns(configured: &mut Vec<String>, extra: Vec<String>) {
+    for pattern in extra {
+        if !configured.contains(&pattern) {
+            configured.push(pattern);
+        }
+    }
+}
+
+/// Which rule decided whether an event is shown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IgnoreVerdict {
+    /// The event kind is not reported (access events without `report_access`)
+    Unreported,
+    /// A hidden path suppressed by `ignore_hidden`
+    Hidden,
+    /// The path matched this ignore pattern
+    Pattern(String),
+    /// A hidden path re-admitted by this include pattern
+    Included(String),
+    /// No rule matched
+    Unmatched,
+    /// The event has no path matching `--grep`
+    Grep,
+    /// The event concerns nothing inside the watch paths
+    OutsideWatchPaths,
+    /// The path was already shown once under `--once-per-path`
+    AlreadySeen,
+}
+
+impl IgnoreVerdict {
+    pub fn is_ignored(&self) -> bool {
+        !matches!(self, Self::Included(_) | Self::Unmatched)
+    }
+
+    /// One line explaining the decision for `path`, for `--explain`
+    pub fn explanation(&self, path: &str) -> String {
+        match self {
+            Self::Unreported => tf("msg_explain_unreported", &[path]),
+            Self::Hidden => tf("msg_explain_hidden", &[path]),
+            Self::Pattern(pattern) => tf("msg_explain_pattern", &[path, pattern]),
+            Self::Included(include) => tf("msg_explain_included", &[path, include]),
+            Self::Unmatched => tf("msg_explain_unmatched", &[path]),
+            Self::Grep => tf("msg_explain_grep", &[path]),
+            Self::OutsideWatchPaths => tf("msg_explain_outside", &[path]),
+            Self::AlreadySeen => tf("msg_explain_seen", &[path]),
+        }
+    }
+}
+
+/// Default size of the ignore decision cache used by the monitor
+pub const IGNORE_CACHE_CAPACITY: usize = 4096;
+
+/// Evaluates ignore patterns, optionally remembering recent decisions per path
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreMatcher {
+    patterns: Vec<String>,
+    /// Watch roots as configured, each with its canonical form
+    roots: Vec<(PathBuf, Option<PathBuf>)>,
+    /// Suppress dotfiles and paths inside dot-directories
+    ignore_hidden: bool,
+    /// Hidden paths matching one of these are watched despite `ignore_hidden`
+    hidden_includes: Vec<String>,
+    cache: Option<DecisionCache>,
+    /// Paths suppressed per pattern since the matcher was created
+    pattern_hits: HashMap<String, u64>,
+    /// Paths suppressed as hidden since the matcher was created
+    hidden_hits: u64,
+}
+
+impl IgnoreMatcher {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns,
+            roots: Vec::new(),
+            ignore_hidden: false,
+            hidden_includes: Vec::new(),
+            cache: None,
+            pattern_hits: HashMap::new(),
+            hidden_hits: 0,
+        }
+    }
+
+    /// Suppress hidden paths, except those matching one of `includes`
+    ///
+    /// An include only overrides the hidden-path rule; regular ignore patterns
+    /// still apply to re-admitted paths.
+    pub fn with_hidden(mut self, ignore_hidden: bool, includes: Vec<String>) -> Self {
+        self.ignore_hidden = ignore_hidden;
+        self.hidden_includes = includes;
+        self.clear_cache();
+        self
+    }
+
+    /// Match paths below these watch roots relative to the root
+    ///
+    /// Paths outside every root keep matching against the full path.
+    pub fn with_roots<P: AsRef<Path>>(mut self, roots: &[P]) -> Self {
+        self.roots = roots
+            .iter()
+            .map(|root| {
+                let root = root.as_ref();
+                (root.to_path_buf(), root.canonicalize().ok())
+            })
+            .collect();
+        self.clear_cache();
+        self
+    }
+
+    /// Cache up to `capacity` decisions, evicting the least recently used
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = (capacity > 0).then(|| DecisionCache::new(capacity));
+        self
+    }
+
+    /// Reject patterns the matcher can't apply as written, so they are never
+    /// saved
+    ///
+    /// The pattern is parsed as the matcher parses it; what is left to compare
+    /// literally must not be empty, which would match every path, nor hold
+    /// glob syntax the matcher would take literally.
+    pub fn validate_pattern(pattern: &str) -> Result<()> {
+        let text = IgnoreRule::parse(pattern).text();
+        if text.trim().is_empty() {
+            bail!(tf("msg_error_pattern_empty", &[pattern]));
+        }
+        if let Some(open) = text.rfind('[')
+            && !text[open..].contains(']')
+        {
+            bail!(tf("msg_error_pattern_bracket", &[pattern]));
+        }
+        if text.contains('*') {
+            bail!(tf("msg_error_pattern_wildcard", &[pattern]));
+        }
+        Ok(())
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Replace the pattern set, dropping every cached decision
+    pub fn set_patterns(&mut self, patterns: Vec<String>) {
+        self.patterns = patterns;
+        self.clear_cache();
+    }
+
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Number of cached decisions
+    pub fn cached_len(&self) -> usize {
+        self.cache.as_ref().map_or(0, DecisionCache::len)
+    }
+
+    /// Check whether `path` matches any pattern, counting the deciding rule
+    pub fn is_ignored(&mut self, path: &str) -> bool {
+        let verdict = match self.cache.as_mut().and_then(|cache| cache.get(path)) {
+            Some(verdict) => verdict,
+            None => {
+                let verdict = self.explain(path);
+                if let Some(cache) = &mut self.cache {
+                    cache.insert(path, verdict.clone());
+                }
+                verdict
+            }
+        };
+        match &verdict {
+            IgnoreVerdict::Pattern(pattern) => {
+                *self.pattern_hits.entry(pattern.clone()).or_default() += 1;
+            }
+            IgnoreVerdict::Hidden => self.hidden_hits += 1,
+            _ => {}
+        }
+        verdict.is_ignored()
+    }
+
+    /// How many paths each pattern suppressed, in pattern order
+    ///
+    /// Patterns with zero hits are candidates for removal.
+    pub fn pattern_stats(&self) -> Vec<(&str, u64)> {
+        self.patterns
+            .iter()
+            .map(|pattern| {
+                let hits = self.pattern_hits.get(pattern).copied().unwrap_or(0);
+                (pattern.as_str(), hits)
+            })
+            .collect()
+    }
+
+    /// How many paths were suppressed as hidden
+    pub fn hidden_hits(&self) -> u64 {
+        self.hidden_hits
+    }
+
+    /// Check whether any path of `event` matches any pattern
+    pub fn should_ignore(&mut self, event: &Event) -> bool {
+        event
+            .paths
+            .iter()
+            .any(|path| self.is_ignored(&path.to_string_lossy()))
+    }
+
+    /// Drop the ignored paths of `event`, returning whether any are left
+    pub fn retain_unignored(&mut self, event: &mut Event) -> bool {
+        retain_unignored_paths(event, |path| self.is_ignored(&path.to_string_lossy()))
+    }
+
+    /// `path` relative to the deepest watch root containing it
+    fn relative_to_root(&self, path: &str) -> Option<String> {
+        let path = Path::new(path);
+        self.roots
+            .iter()
+            .flat_map(|(root, canonical)| std::iter::once(root).chain(canonical))
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .min_by_key(|relative| relative.components().count())
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Explain the decision for the first ignored path of `event`, or for
+    /// its first path when none is ignored
+    ///
+    /// Bypasses the decision cache.
+    pub fn explain_event(&self, event: &Event) -> IgnoreVerdict {
+        let mut verdicts = event
+            .paths
+            .iter()
+            .map(|path| self.explain(&path.to_string_lossy()));
+        let first = verdicts.next().unwrap_or(IgnoreVerdict::Unmatched);
+        if first.is_ignored() {
+            return first;
+        }
+        verdicts.find(IgnoreVerdict::is_ignored).unwrap_or(first)
+    }
+
+    /// The rule deciding whether `path` is ignored
+    pub fn explain(&self, path: &str) -> IgnoreVerdict {
+        let relative = self.relative_to_root(path);
+        let mut included = None;
+        if self.ignore_hidden {
+            let checked = relative.as_deref().unwrap_or(path);
+            if is_hidden(checked) {
+                match self
+                    .hidden_includes
+                    .iter()
+                    .find(|include| matches_relative_ignore_pattern(checked, include))
+                {
+                    Some(include) => included = Some(include.clone()),
+                    None => return IgnoreVerdict::Hidden,
+                }
+            }
+        }
+
+        let pattern = match relative {
+            Some(relative) => self
+                .patterns
+                .iter()
+                .find(|pattern| matches_relative_ignore_pattern(&relative, pattern))
+                .map(String::as_str),
+            None => matching_ignore_pattern(path, &self.patterns),
+        };
+        match (pattern, included) {
+            (Some(pattern), _) => IgnoreVerdict::Pattern(pattern.to_string()),
+            (None, Some(include)) => IgnoreVerdict::Included(include),
+            (None, None) => IgnoreVerdict::Unmatched,
+        }
+    }
+}
+
+/// Check whether any component of `path` is a dotfile or dot-directory
+pub fn is_hidden(path: &str) -> bool {
+    Path::new(path).components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| name.starts_with('.') && name != "." && name != "..")
+    })
+}
+
+/// Bounded least-recently-used map from path to ignore decision
+#[derive(Debug, Clone)]
+struct DecisionCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (IgnoreVerdict, u64)>,
+    recency: BTreeMap<u64, String>,
+}
+
+impl DecisionCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, path: &str) -> Option<IgnoreVerdict> {
+        let tick = self.next_tick();
+        let (verdict, last_used) = self.entries.get_mut(path)?;
+        self.recency.remove(last_used);
+        *last_used = tick;
+        let verdict = verdict.clone();
+        self.recency.insert(tick, path.to_string());
+        Some(verdict)
+    }
+
+    fn insert(&mut self, path: &str, verdict: IgnoreVerdict) {
+        if self.entries.len() >= self.capacity
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        let tick = self.next_tick();
+        self.entries.insert(path.to_string(), (verdict, tick));
+        self.recency.insert(tick, path.to_string());
+    }
+}
+
+/// Ignore patterns that only apply below one watch root
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootIgnore {
+    pub root: PathBuf,
+    pub patterns: Vec<String>,
+}
+
+/// Ignore patterns of single watch roots, from `.chaserignore` files and the
+/// config's `watch_ignores`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RootIgnores {
+    pub roots: Vec<RootIgnore>,
+}
+
+impl RootIgnores {
+    /// Load the `.chaserignore` at the root of each watch path, if present
+    ///
+    /// Unreadable files are reported in the returned error list and skipped.
+    pub fn discover(watch_paths: &[String]) -> (Self, Vec<anyhow::Error>) {
+        let mut roots = Vec::new();
+        let mut errors = Vec::new();
+
+        for watch_path in watch_paths {
+            let ignore_file = Path::new(watch_path).join(CHASERIGNORE_FILE);
+            if !ignore_file.is_file() {
+                continue;
+            }
+            match load_ignore_file(&ignore_file) {
+                Ok(patterns) => roots.push(RootIgnore {
+                    root: PathBuf::from(watch_path),
+                    patterns,
+                }),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (Self { roots }, errors)
+    }
+
+    /// Add `patterns` for `root`, after any it already has
+    pub fn add_patterns(&mut self, root: &Path, patterns: &[String]) {
+        match self.roots.iter_mut().find(|existing| existing.root == root) {
+            Some(existing) => existing.patterns.extend_from_slice(patterns),
+            None => self.roots.push(RootIgnore {
+                root: root.to_path_buf(),
+                patterns: patterns.to_vec(),
+            }),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Check whether any path of `event` is ignored by the file of its root
+    pub fn should_ignore(&self, event: &Event) -> bool {
+        self.matching_pattern(event).is_some()
+    }
+
+    /// Drop the paths of `event` ignored by the file of their root,
+    /// returning whether any are left
+    pub fn retain_unignored(&self, event: &mut Event) -> bool {
+        retain_unignored_paths(event, |path| self.path_pattern(path).is_some())
+    }
+
+    /// The first root pattern that ignores a path of `event`
+    pub fn matching_pattern(&self, event: &Event) -> Option<&str> {
+        event.paths.iter().find_map(|path| self.path_pattern(path))
+    }
+
+    /// The first pattern of the root of `path` that ignores it
+    pub fn path_pattern(&self, path: &Path) -> Option<&str> {
+        self.roots.iter().find_map(|root| {
+            let relative = Self::relative_to(path, &root.root)?;
+            root.patterns
+                .iter()
+                .find(|pattern| matches_relative_ignore_pattern(&relative, pattern))
+                .map(String::as_str)
+        })
+    }
+
+    /// `path` relative to `root`, as `/`-separated text
+    fn relative_to(path: &Path, root: &Path) -> Option<String> {
+        let relative = match path.strip_prefix(root) {
+            Ok(relative) => relative.to_path_buf(),
+            // Events may report absolute paths for a root configured relatively
+            Err(_) => path
+                .strip_prefix(root.canonicalize().ok()?)
+                .ok()?
+                .to_path_buf(),
+        };
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::should_ignore_event;
+    use notify::{Event, EventKind, event::CreateKind};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_ignore_patterns_skips_comments_and_blanks() {
+        let content = "# build output\n*.o\n\n  dist/**  \n#*.keep\n";
+        assert_eq!(parse_ignore_patterns(content), vec!["*.o", "dist/**"]);
+    }
+
+    #[test]
+    fn test_load_ignore_file_patterns_take_effect() {
+        let temp_dir = TempDir::new().unwrap();
+        let ignore_file = temp_dir.path().join("ignore.txt");
+        fs::write(&ignore_file, "# generated\n*.bak\ncache/**\n").unwrap();
+
+        let mut patterns = vec!["*.tmp".to_string()];
+        merge_ignore_patterns(&mut patterns, load_ignore_file(&ignore_file).unwrap());
+        assert_eq!(patterns, vec!["*.tmp", "*.bak", "cache/**"]);
+
+        let event = |path: &str| Event {
+            kind: EventKind::Create(CreateKind::File),
+            paths: vec![PathBuf::from(path)],
+            attrs: Default::default(),
+        };
+        assert!(should_ignore_event(&event("/p/file.bak"), &patterns));
+        assert!(should_ignore_event(&event("/p/cache/x.txt"), &patterns));
+        assert!(!should_ignore_event(&event("/p/file.txt"), &patterns));
+    }
+
+    #[test]
+    fn test_load_ignore_file_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(load_ignore_file(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_chaserignore_filters_events_per_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let with_file = temp_dir.path().join("project");
+        let without_file = temp_dir.path().join("other");
+        fs::create_dir_all(&with_file).unwrap();
+        fs::create_dir_all(&without_file).unwrap();
+        fs::write(
+            with_file.join(CHASERIGNORE_FILE),
+            "# local
+*.bak
+",
+        )
+        .unwrap();
+
+        let (ignores, errors) = RootIgnores::discover(&[
+            with_file.to_string_lossy().to_string(),
+            without_file.to_string_lossy().to_string(),
+        ]);
+        assert!(errors.is_empty());
+        assert_eq!(ignores.roots.len(), 1);
+
+        let event = |path: PathBuf| Event {
+            kind: EventKind::Create(CreateKind::File),
+            paths: vec![path],
+            attrs: Default::default(),
+        };
+        assert!(ignores.should_ignore(&event(with_file.join("a.bak"))));
+        assert!(!ignores.should_ignore(&event(with_file.join("a.txt"))));
+        // The other root has no .chaserignore, so its .bak files still count
+        assert!(!ignores.should_ignore(&event(without_file.join("a.bak"))));
+    }
+
+    #[test]
+    fn test_explain_reports_deciding_rule() {
+        let matcher = IgnoreMatcher::new(vec!["*.tmp".to_string(), "cache/**".to_string()])
+            .with_roots(&["/w"])
+            .with_hidden(true, vec![".env".to_string()]);
+
+        assert_eq!(
+            matcher.explain("/w/cache/a.txt"),
+            IgnoreVerdict::Pattern("cache/**".to_string())
+        );
+        assert_eq!(matcher.explain("/w/.git/HEAD"), IgnoreVerdict::Hidden);
+        assert_eq!(
+            matcher.explain("/w/.env"),
+            IgnoreVerdict::Included(".env".to_string())
+        );
+        assert_eq!(matcher.explain("/w/src/main.rs"), IgnoreVerdict::Unmatched);
+
+        // The event verdict names the path that was ignored
+        let event = Event::new(EventKind::Any)
+            .add_path(PathBuf::from("/w/src/main.rs"))
+            .add_path(PathBuf::from("/w/x.tmp"));
+        assert_eq!(
+            matcher.explain_event(&event),
+            IgnoreVerdict::Pattern("*.tmp".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_pattern() {
+        for valid in [
+            "*.tmp",
+            "target/**",
+            "/build/**",
+            "/dist",
+            "node_modules",
+            "a[1].txt",
+        ] {
+            assert!(IgnoreMatcher::validate_pattern(valid).is_ok(), "{}", valid);
+        }
+        for invalid in [
+            "[", "logs/[a", "", "/", "foo*bar", "a/**/b", "*", "**", "/**", "*.",
+        ] {
+            assert!(
+                IgnoreMatcher::validate_pattern(invalid).is_err(),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_retain_unignored_filters_each_path() {
+        let mut matcher = IgnoreMatcher::new(vec!["*.tmp".to_string()]);
+        let mut event = Event::new(EventKind::Remove(notify::event::RemoveKind::Any))
+            .add_path(PathBuf::from("/w/a.tmp"))
+            .add_path(PathBuf::from("/w/b.txt"))
+            .add_path(PathBuf::from("/w/c.tmp"));
+        assert!(matcher.retain_unignored(&mut event));
+        assert_eq!(event.paths, vec![PathBuf::from("/w/b.txt")]);
+
+        let mut all_ignored = Event::new(EventKind::Any).add_path(PathBuf::from("/w/x.tmp"));
+        assert!(!matcher.retain_unignored(&mut all_ignored));
+
+        // A rename pair is never split
+        let mut rename = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(PathBuf::from("/w/a.tmp"))
+            .add_path(PathBuf::from("/w/a.txt"));
+        assert!(!matcher.retain_unignored(&mut rename));
+        assert_eq!(rename.paths.len(), 2);
+
+        let roots = RootIgnores {
+            roots: vec![RootIgnore {
+                root: PathBuf::from("/w"),
+                patterns: vec!["build/**".to_string()],
+            }],
+        };
+        let mut event = Event::new(EventKind::Any)
+            .add_path(PathBuf::from("/w/build/out.o"))
+            .add_path(PathBuf::from("/w/src/lib.rs"));
+        assert!(roots.retain_unignored(&mut event));
+        assert_eq!(event.paths, vec![PathBuf::from("/w/src/lib.rs")]);
+    }
+
+    #[test]
+    fn test_root_patterns_only_apply_under_their_root() {
+        let mut roots = RootIgnores::default();
+        roots.add_patterns(Path::new("/web"), &["dist/**".to_string()]);
+        roots.add_patterns(Path::new("/docs"), &["*.pdf".to_string()]);
+        roots.add_patterns(Path::new("/web"), &["*.map".to_string()]);
+        assert_eq!(roots.roots.len(), 2);
+
+        let event = |path: &str| Event::new(EventKind::Any).add_path(PathBuf::from(path));
+        assert!(roots.should_ignore(&event("/web/dist/app.js")));
+        assert!(roots.should_ignore(&event("/web/app.js.map")));
+        assert!(roots.should_ignore(&event("/docs/guide.pdf")));
+        // The same names under the other root are not ignored
+        assert!(!roots.should_ignore(&event("/docs/dist/app.js")));
+        assert!(!roots.should_ignore(&event("/web/guide.pdf")));
+        assert!(!roots.should_ignore(&event("/other/dist/app.js")));
+    }
+
+    #[test]
+    fn test_pattern_stats_count_suppressed_paths() {
+        let patterns = vec![
+            "*.tmp".to_string(),
+            "cache/**".to_string(),
+            "*.bak".to_string(),
+        ];
+        let mut matcher = IgnoreMatcher::new(patterns)
+            .with_roots(&["/w"])
+            .with_hidden(true, Vec::new())
+            .with_cache(16);
+
+        for path in [
+            "/w/a.tmp",
+            "/w/b.tmp",
+            "/w/a.tmp",
+            "/w/cache/x",
+            "/w/src/main.rs",
+            "/w/.git/HEAD",
+        ] {
+            matcher.is_ignored(path);
+        }
+
+        // Cached decisions still count
+        assert_eq!(
+            matcher.pattern_stats(),
+            vec![("*.tmp", 3), ("cache/**", 1), ("*.bak", 0)]
+        );
+        assert_eq!(matcher.hidden_hits(), 1);
+    }
+
+    #[test]
+    fn test_explanation_lines() {
+        let matched = IgnoreVerdict::Pattern("*.tmp".to_string()).explanation("/w/a.tmp");
+        assert!(matched.contains("/w/a.tmp"));
+        assert!(matched.contains("*.tmp"));
+
+        let unmatched = IgnoreVerdict::Unmatched.explanation("/w/a.rs");
+        assert!(unmatched.contains("/w/a.rs"));
+        assert_ne!(unmatched, IgnoreVerdict::Hidden.explanation("/w/a.rs"));
+        assert!(!IgnoreVerdict::Unmatched.is_ignored());
+        assert!(IgnoreVerdict::Pattern("*.tmp".to_string()).is_ignored());
+    }
+
+    #[test]
+    fn test_ignore_matcher_cache_matches_fresh_evaluation() {
+        let patterns = vec!["*.tmp".to_string(), "target/**".to_string()];
+        let mut cached = IgnoreMatcher::new(patterns.clone()).with_cache(16);
+        let mut fresh = IgnoreMatcher::new(patterns);
+
+        let paths = ["/p/a.tmp", "/p/target/x", "/p/src/main.rs", "/p/a.tmp"];
+        for path in paths {
+            assert_eq!(cached.is_ignored(path), fresh.is_ignored(path), "{}", path);
+        }
+        assert_eq!(cached.cached_len(), 3);
+        assert_eq!(fresh.cached_len(), 0);
+    }
+
+    #[test]
+    fn test_ignore_matcher_cache_is_bounded() {
+        let mut matcher = IgnoreMatcher::new(vec!["*.tmp".to_string()]).with_cache(2);
+        matcher.is_ignored("/a.tmp");
+        matcher.is_ignored("/b.rs");
+        // Touch /a.tmp so /b.rs becomes the least recently used entry
+        matcher.is_ignored("/a.tmp");
+        matcher.is_ignored("/c.rs");
+
+        assert_eq!(matcher.cached_len(), 2);
+        let cache = matcher.cache.as_ref().unwrap();
+        assert!(cache.entries.contains_key("/a.tmp"));
+        assert!(!cache.entries.contains_key("/b.rs"));
+    }
+
+    #[test]
+    fn test_ignore_matcher_pattern_change_clears_cache() {
+        let mut matcher = IgnoreMatcher::new(vec!["*.tmp".to_string()]).with_cache(16);
+        assert!(matcher.is_ignored("/a.tmp"));
+        assert_eq!(matcher.cached_len(), 1);
+
+        matcher.set_patterns(vec!["*.rs".to_string()]);
+        assert_eq!(matcher.cached_len(), 0);
+        assert!(!matcher.is_ignored("/a.tmp"));
+
+        matcher.clear_cache();
+        assert_eq!(matcher.cached_len(), 0);
+    }
+
+    #[test]
+    fn test_ignore_matcher_relative_to_root() {
+        let patterns = vec!["target/**".to_string(), "/build/**".to_string()];
+        let mut absolute = IgnoreMatcher::new(patterns.clone());
+        let mut relative = IgnoreMatcher::new(patterns).with_roots(&["/work/target-app"]);
+
+        let source = "/work/target-app/src/main.rs";
+        assert!(absolute.is_ignored(source));
+        assert!(!relative.is_ignored(source));
+
+        assert!(relative.is_ignored("/work/target-app/target/debug/app"));
+        assert!(relative.is_ignored("/work/target-app/build/out.o"));
+        assert!(!relative.is_ignored("/work/target-app/src/build/out.o"));
+        // Outside every root the full path is used
+        assert!(relative.is_ignored("/other/target/x"));
+    }
+
+    #[test]
+    fn test_is_hidden() {
+        assert!(is_hidden(".env"));
+        assert!(is_hidden("config/.secret/key"));
+        assert!(!is_hidden("./src/main.rs"));
+        assert!(!is_hidden("../src/main.rs"));
+    }
+
+    #[test]
+    fn test_hidden_include_beats_ignore_hidden() {
+        let mut matcher = IgnoreMatcher::new(vec!["*.tmp".to_string()])
+            .with_roots(&["/work/app"])
+            .with_hidden(true, vec![".env".to_string()]);
+
+        assert!(!matcher.is_ignored("/work/app/.env"));
+        assert!(matcher.is_ignored("/work/app/.bashrc"));
+        assert!(matcher.is_ignored("/work/app/.cache/data"));
+        assert!(!matcher.is_ignored("/work/app/src/main.rs"));
+        // Regular ignore patterns still apply to re-admitted paths
+        let mut matcher = IgnoreMatcher::new(vec![".env".to_string()])
+            .with_hidden(true, vec![".env".to_string()]);
+        assert!(matcher.is_ignored("/work/app/.env"));
+
+        // A hidden watch root does not hide everything beneath it
+        let mut matcher = IgnoreMatcher::new(Vec::new())
+            .with_roots(&["/home/u/.config/app"])
+            .with_hidden(true, Vec::new());
+        assert!(!matcher.is_ignored("/home/u/.config/app/settings.json"));
+    }
+
+    #[test]
+    fn test_merge_ignore_patterns_skips_duplicates() {
+        let mut patterns = vec!["*.tmp".to_string()];
+        merge_ignore_patterns(&mut patterns, vec!["*.tmp".to_string(), "*.o".to_string()]);
+        assert_eq!(patterns, vec!["*.tmp", "*.o"]);
+    }
+}
@@ -1,19 +1,134 @@
 pub mod cli;
 pub mod config;
 pub mod i18n;
+pub mod ignore;
+pub mod log_sink;
+pub mod monitor;
 pub mod path_sync;
+pub mod serve;
+pub mod state;
 pub mod target_files;
+pub mod theme;
+pub mod write_mode;
 
+use crate::i18n::{available_locales, format_list, t, tf};
+use crate::target_files::TargetFileFormat;
+use notify::event::{CreateKind, ModifyKind, RemoveKind};
 use notify::{Event, EventKind};
 
+/// Version line, plus supported formats, locales and the watch backend when
+/// `verbose`, for bug reports
+pub fn version_info(verbose: bool) -> String {
+    let mut lines = vec![format!("chaser {}", env!("CARGO_PKG_VERSION"))];
+    if verbose {
+        let formats: Vec<&str> = TargetFileFormat::ALL.iter().map(|f| f.name()).collect();
+        let backend = std::any::type_name::<notify::RecommendedWatcher>();
+        let backend = backend.rsplit("::").next().unwrap_or(backend);
+        lines.push(tf("msg_version_formats", &[&format_list(&formats)]));
+        lines.push(tf(
+            "msg_version_locales",
+            &[&format_list(&available_locales())],
+        ));
+        lines.push(tf("msg_version_backend", &[backend]));
+    }
+    lines.join("\n")
+}
+
 /// Check if an event should be ignored based on patterns
 pub fn should_ignore_event(event: &Event, ignore_patterns: &[String]) -> bool {
-    event.paths.iter().any(|path| {
-        let path_str = path.to_string_lossy();
-        ignore_patterns
-            .iter()
-            .any(|pattern| matches_ignore_pattern(&path_str, pattern))
-    })
+    event
+        .paths
+        .iter()
+        .any(|path| should_ignore_path(&path.to_string_lossy(), ignore_patterns))
+}
+
+/// Check if a single path matches any of the ignore patterns
+pub fn should_ignore_path(path: &str, ignore_patterns: &[String]) -> bool {
+    matching_ignore_pattern(path, ignore_patterns).is_some()
+}
+
+/// The first of the ignore patterns that matches a path, if any
+pub fn matching_ignore_pattern<'p>(path: &str, ignore_patterns: &'p [String]) -> Option<&'p str> {
+    ignore_patterns
+        .iter()
+        .find(|pattern| matches_ignore_pattern(path, pattern))
+        .map(String::as_str)
+}
+
+/// Check a path relative to its watch root against one ignore pattern
+///
+/// Evaluated like gitignore: a leading `/` anchors the pattern to the root,
+/// `dir/**` matches that directory at any depth otherwise, `*.ext` matches the
+/// extension, and anything else is a substring match.
+pub fn matches_relative_ignore_pattern(relative: &str, pattern: &str) -> bool {
+    IgnoreRule::parse(pattern).matches(relative)
+}
+
+/// An ignore pattern as the matcher applies it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IgnoreRule<'a> {
+    /// A leading `/` ties the pattern to the watch root
+    pub anchored: bool,
+    pub kind: IgnoreRuleKind<'a>,
+}
+
+/// How an [`IgnoreRule`] matches, with the text it matches literally
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreRuleKind<'a> {
+    /// `dir/**`: the directory and everything below it
+    Directory(&'a str),
+    /// `*.ext`: files with this extension
+    Extension(&'a str),
+    /// Anything else: a substring, or a path prefix when anchored
+    Literal(&'a str),
+}
+
+impl<'a> IgnoreRule<'a> {
+    pub fn parse(pattern: &'a str) -> Self {
+        let (anchored, pattern) = match pattern.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+        let kind = if is_directory_pattern(pattern) {
+            let dir = pattern.trim_end_matches("/**").trim_end_matches("**");
+            IgnoreRuleKind::Directory(dir.trim_end_matches('/'))
+        } else if let Some(ext) = pattern.strip_prefix("*.") {
+            IgnoreRuleKind::Extension(ext)
+        } else {
+            IgnoreRuleKind::Literal(pattern)
+        };
+        Self { anchored, kind }
+    }
+
+    /// The part of the pattern compared as written
+    pub fn text(&self) -> &'a str {
+        match self.kind {
+            IgnoreRuleKind::Directory(text)
+            | IgnoreRuleKind::Extension(text)
+            | IgnoreRuleKind::Literal(text) => text,
+        }
+    }
+
+    /// Check a path relative to its watch root
+    pub fn matches(&self, relative: &str) -> bool {
+        match self.kind {
+            IgnoreRuleKind::Directory(dir) => {
+                let at_root = relative == dir || relative.starts_with(&format!("{}/", dir));
+                at_root
+                    || (!self.anchored
+                        && (relative.contains(&format!("/{}/", dir))
+                            || relative.ends_with(&format!("/{}", dir))))
+            }
+            IgnoreRuleKind::Extension(ext) => {
+                let file_name_matches = relative.ends_with(&format!(".{}", ext));
+                file_name_matches && (!self.anchored || !relative.contains('/'))
+            }
+            IgnoreRuleKind::Literal(text) if self.anchored => {
+                relative == text || relative.starts_with(&format!("{}/", text))
+            }
+            IgnoreRuleKind::Literal(text) => relative.contains(text),
+        }
+    }
 }
 
 fn matches_ignore_pattern(path: &str, pattern: &str) -> bool {
@@ -47,20 +162,55 @@ fn matches_extension_pattern(path: &str, pattern: &str) -> bool {
     }
 }
 
+/// Check whether the monitor reports events of this kind
+///
+/// Access events are noisy and only reported when `report_access` is set.
+pub fn is_reported_event(kind: &EventKind, report_access: bool) -> bool {
+    match kind {
+        EventKind::Access(_) => report_access,
+        _ => true,
+    }
+}
+
 /// Convert event type to human-readable description
 pub fn get_event_description(event: &Event) -> String {
-    match event.kind {
-        EventKind::Create(_) => "Created".to_string(),
-        EventKind::Modify(_) => "Modified".to_string(),
-        EventKind::Remove(_) => "Removed".to_string(),
-        EventKind::Access(_) => "Accessed".to_string(),
-        EventKind::Any | EventKind::Other => "Other".to_string(),
+    t(event_description_key(&event.kind))
+}
+
+/// Locale key describing `kind`
+///
+/// Sub-kinds the backend reports, such as file or folder and rename, are
+/// kept; the generic description is used when it reports none.
+fn event_description_key(kind: &EventKind) -> &'static str {
+    match kind {
+        EventKind::Create(CreateKind::File) => "msg_event_file_created",
+        EventKind::Create(CreateKind::Folder) => "msg_event_folder_created",
+        EventKind::Create(_) => "msg_event_created",
+        EventKind::Modify(ModifyKind::Name(_)) => "msg_event_renamed",
+        EventKind::Modify(ModifyKind::Data(_)) => "msg_event_content_changed",
+        EventKind::Modify(ModifyKind::Metadata(_)) => "msg_event_metadata_changed",
+        EventKind::Modify(_) => "msg_event_modified",
+        EventKind::Remove(RemoveKind::File) => "msg_event_file_removed",
+        EventKind::Remove(RemoveKind::Folder) => "msg_event_folder_removed",
+        EventKind::Remove(_) => "msg_event_removed",
+        EventKind::Access(_) => "msg_event_accessed",
+        EventKind::Any | EventKind::Other => "msg_event_other",
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    #[test]
+    fn test_version_info_lists_formats_and_locales() {
+        let info = version_info(true);
+        assert!(info.starts_with(&format!("chaser {}", env!("CARGO_PKG_VERSION"))));
+        for name in ["json", "yaml", "toml", "csv", "en", "zh-cn"] {
+            assert!(info.contains(name), "{name} missing from {info}");
+        }
+        assert_eq!(version_info(false).lines().count(), 1);
+    }
     use notify::{Event, EventKind, event::CreateKind};
     use std::path::PathBuf;
 
@@ -72,6 +222,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_relative_vs_absolute_matching() {
+        // The watch root's own name contains "target"
+        let absolute = "/work/target-app/src/main.rs";
+        let relative = "src/main.rs";
+        let pattern = "target/**".to_string();
+
+        assert!(should_ignore_path(absolute, std::slice::from_ref(&pattern)));
+        assert!(!matches_relative_ignore_pattern(relative, &pattern));
+    }
+
+    #[test]
+    fn test_ignore_rule_parse() {
+        let rule = IgnoreRule::parse("/target/**");
+        assert!(rule.anchored);
+        assert_eq!(rule.kind, IgnoreRuleKind::Directory("target"));
+        assert_eq!(
+            IgnoreRule::parse("*.log").kind,
+            IgnoreRuleKind::Extension("log")
+        );
+        let rule = IgnoreRule::parse("a/**/b");
+        assert_eq!(rule.kind, IgnoreRuleKind::Directory("a/**/b"));
+        assert_eq!(rule.text(), "a/**/b");
+        assert_eq!(
+            IgnoreRule::parse("notes").kind,
+            IgnoreRuleKind::Literal("notes")
+        );
+    }
+
+    #[test]
+    fn test_relative_anchored_patterns() {
+        assert!(matches_relative_ignore_pattern(
+            "target/debug/app",
+            "/target/**"
+        ));
+        assert!(matches_relative_ignore_pattern("target", "/target/**"));
+        assert!(!matches_relative_ignore_pattern(
+            "sub/target/x",
+            "/target/**"
+        ));
+        assert!(matches_relative_ignore_pattern("sub/target/x", "target/**"));
+        assert!(matches_relative_ignore_pattern("build/out.o", "build/**"));
+        assert!(!matches_relative_ignore_pattern(
+            "rebuild/out.o",
+            "build/**"
+        ));
+
+        assert!(matches_relative_ignore_pattern("a/b.log", "*.log"));
+        assert!(!matches_relative_ignore_pattern("a/catalog", "*.log"));
+        assert!(matches_relative_ignore_pattern("b.log", "/*.log"));
+        assert!(!matches_relative_ignore_pattern("a/b.log", "/*.log"));
+
+        assert!(matches_relative_ignore_pattern("notes.txt", "/notes.txt"));
+        assert!(!matches_relative_ignore_pattern(
+            "a/notes.txt",
+            "/notes.txt"
+        ));
+        assert!(matches_relative_ignore_pattern("a/notes.txt", "notes"));
+    }
+
+    #[test]
+    fn test_is_reported_event_access() {
+        use notify::event::AccessKind;
+
+        let access = EventKind::Access(AccessKind::Read);
+        assert!(!is_reported_event(&access, false));
+        assert!(is_reported_event(&access, true));
+        assert!(is_reported_event(
+            &EventKind::Create(CreateKind::File),
+            false
+        ));
+    }
+
     #[test]
     fn test_should_ignore_event_extension_patterns() {
         let ignore_patterns = vec!["*.tmp".to_string(), "*.log".to_string()];
@@ -185,34 +408,39 @@ mod tests {
 
     #[test]
     fn test_get_event_description() {
-        let event = create_test_event(vec!["/test"], EventKind::Create(CreateKind::File));
-        assert_eq!(get_event_description(&event), "Created");
-
-        let event = create_test_event(
-            vec!["/test"],
-            EventKind::Modify(notify::event::ModifyKind::Data(
-                notify::event::DataChange::Content,
-            )),
-        );
-        assert_eq!(get_event_description(&event), "Modified");
-
-        let event = create_test_event(
-            vec!["/test"],
-            EventKind::Remove(notify::event::RemoveKind::File),
-        );
-        assert_eq!(get_event_description(&event), "Removed");
-
-        let event = create_test_event(
-            vec!["/test"],
-            EventKind::Access(notify::event::AccessKind::Read),
-        );
-        assert_eq!(get_event_description(&event), "Accessed");
-
-        let event = create_test_event(vec!["/test"], EventKind::Any);
-        assert_eq!(get_event_description(&event), "Other");
+        use notify::event::{AccessKind, DataChange, MetadataKind, RenameMode};
+        let english = crate::i18n::I18n::with_locale("en").unwrap();
+        let cases = [
+            (EventKind::Create(CreateKind::File), "File created"),
+            (EventKind::Create(CreateKind::Folder), "Folder created"),
+            (EventKind::Create(CreateKind::Any), "Created"),
+            (
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                "Renamed",
+            ),
+            (
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                "Content changed",
+            ),
+            (
+                EventKind::Modify(ModifyKind::Metadata(MetadataKind::Permissions)),
+                "Metadata changed",
+            ),
+            (EventKind::Modify(ModifyKind::Any), "Modified"),
+            (EventKind::Remove(RemoveKind::File), "File removed"),
+            (EventKind::Remove(RemoveKind::Folder), "Folder removed"),
+            (EventKind::Remove(RemoveKind::Other), "Removed"),
+            (EventKind::Access(AccessKind::Read), "Accessed"),
+            (EventKind::Any, "Other"),
+            (EventKind::Other, "Other"),
+        ];
 
-        let event = create_test_event(vec!["/test"], EventKind::Other);
-        assert_eq!(get_event_description(&event), "Other");
+        for (kind, expected) in cases {
+            let key = event_description_key(&kind);
+            assert_eq!(english.t(key), expected, "{:?}", kind);
+            let event = create_test_event(vec!["/test"], kind);
+            assert_eq!(get_event_description(&event), t(key));
+        }
     }
 
     #[test]
@@ -0,0 +1,203 @@
+use crate::i18n::{t, tf};
+use anyhow::{Context, Result, bail};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Where the monitor's event lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSink {
+    Terminal,
+    /// The system logger, which journald also reads from
+    Syslog,
+    /// Appended to the `--log-file` file
+    File,
+}
+
+/// Parse a `--log-sink` value
+pub fn parse_log_sink(input: &str) -> Result<LogSink> {
+    match input.trim().to_lowercase().as_str() {
+        "terminal" => Ok(LogSink::Terminal),
+        "syslog" => Ok(LogSink::Syslog),
+        "file" => Ok(LogSink::File),
+        _ => bail!(
+            "Invalid log sink: '{}' (use terminal, syslog or file)",
+            input
+        ),
+    }
+}
+
+/// Severity of an event line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+/// A destination for event lines
+pub trait EventSink {
+    fn write(&mut self, level: EventLevel, line: &str) -> io::Result<()>;
+}
+
+/// Prints lines to stdout as they are, colors included
+pub struct TerminalSink;
+
+impl EventSink for TerminalSink {
+    fn write(&mut self, _level: EventLevel, line: &str) -> io::Result<()> {
+        println!("{}", line);
+        Ok(())
+    }
+}
+
+/// Appends lines without colors to a file
+pub struct FileSink {
+    file: File,
+}
+
+impl FileSink {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| tf("msg_log_file_open_failed", &[&path.display().to_string()]))?;
+        Ok(Self { file })
+    }
+}
+
+impl EventSink for FileSink {
+    fn write(&mut self, level: EventLevel, line: &str) -> io::Result<()> {
+        let level = match level {
+            EventLevel::Info => "INFO",
+            EventLevel::Warn => "WARN",
+            EventLevel::Error => "ERROR",
+        };
+        writeln!(self.file, "{} {}", level, strip_ansi(line))
+    }
+}
+
+/// Sends lines to the local syslog socket
+#[cfg(unix)]
+pub struct SyslogSink {
+    socket: std::os::unix::net::UnixDatagram,
+}
+
+#[cfg(unix)]
+impl SyslogSink {
+    /// Sockets of the system logger on Linux and macOS
+    const SOCKETS: [&str; 2] = ["/dev/log", "/var/run/syslog"];
+
+    pub fn connect() -> Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        if Self::SOCKETS
+            .iter()
+            .any(|path| socket.connect(path).is_ok())
+        {
+            Ok(Self { socket })
+        } else {
+            bail!(t("msg_syslog_unavailable"))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl EventSink for SyslogSink {
+    fn write(&mut self, level: EventLevel, line: &str) -> io::Result<()> {
+        // Facility user (1), severity info (6) or warning (4)
+        let priority = match level {
+            EventLevel::Info => 8 + 6,
+            EventLevel::Warn => 8 + 4,
+            EventLevel::Error => 8 + 3,
+        };
+        let record = format!(
+            "<{}>chaser[{}]: {}",
+            priority,
+            std::process::id(),
+            strip_ansi(line)
+        );
+        self.socket.send(record.as_bytes()).map(|_| ())
+    }
+}
+
+/// Open the selected sinks; no selection means the terminal
+pub fn open_sinks(sinks: &[LogSink], log_file: Option<&Path>) -> Result<Vec<Box<dyn EventSink>>> {
+    if sinks.is_empty() {
+        return Ok(vec![Box::new(TerminalSink)]);
+    }
+
+    let mut opened: Vec<Box<dyn EventSink>> = Vec::new();
+    for sink in sinks {
+        match sink {
+            LogSink::Terminal => opened.push(Box::new(TerminalSink)),
+            LogSink::File => {
+                let Some(path) = log_file else {
+                    bail!(t("msg_log_file_required"));
+                };
+                opened.push(Box::new(FileSink::open(path)?));
+            }
+            #[cfg(unix)]
+            LogSink::Syslog => opened.push(Box::new(SyslogSink::connect()?)),
+            #[cfg(not(unix))]
+            LogSink::Syslog => bail!(t("msg_syslog_unavailable")),
+        }
+    }
+    Ok(opened)
+}
+
+/// `line` without ANSI color escape sequences
+pub fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip to the final byte of the CSI sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_log_sink() {
+        assert_eq!(parse_log_sink("syslog").unwrap(), LogSink::Syslog);
+        assert_eq!(parse_log_sink("File").unwrap(), LogSink::File);
+        assert!(parse_log_sink("journal").is_err());
+    }
+
+    #[test]
+    fn test_open_sinks_selection() {
+        assert_eq!(open_sinks(&[], None).unwrap().len(), 1);
+        assert!(open_sinks(&[LogSink::File], None).is_err());
+
+        let temp_dir = TempDir::new().unwrap();
+        let log_file = temp_dir.path().join("events.log");
+        let mut sinks = open_sinks(&[LogSink::Terminal, LogSink::File], Some(&log_file)).unwrap();
+        assert_eq!(sinks.len(), 2);
+        sinks[1]
+            .write(EventLevel::Warn, "\u{1b}[31mDeleted: /w/a\u{1b}[39m")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&log_file).unwrap(),
+            "WARN Deleted: /w/a\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\u{1b}[1;32mok\u{1b}[0m done"), "ok done");
+        assert_eq!(strip_ansi("plain"), "plain");
+    }
+}
@@ -1,25 +1,67 @@
-mod cli;
-mod config;
-mod i18n;
-mod path_sync;
-mod target_files;
-
-use anyhow::Result;
-use chaser::should_ignore_event;
-use cli::{Commands, build_cli, parse_command};
-use config::Config;
-use i18n::{available_locales, init_i18n_with_locale, is_locale_supported, set_locale, t, tf};
+use anyhow::{Context, Result};
+use chaser::cli::{
+    Commands, build_cli, load_options, parse_command, parse_monitor_options, profile_from_args,
+    relative_to, with_default_monitor_args,
+};
+use chaser::config::{
+    Config, RenameDisplay, active_profile, expand_path, parse_bool_flexible, set_profile,
+};
+use chaser::i18n::{
+    available_locales, format_list, format_number, init_i18n_with_locale, is_locale_supported,
+    set_locale, t, tf,
+};
+use chaser::ignore::{
+    CHASERIGNORE_FILE, IGNORE_CACHE_CAPACITY, IgnoreMatcher, IgnoreVerdict, RootIgnores,
+    load_ignore_file, merge_ignore_patterns,
+};
+use chaser::log_sink::{EventLevel, open_sinks};
+use chaser::monitor::{
+    EVENT_DEDUP_WINDOW, EventAggregator, EventCounters, EventDeduper, EventOutput, FileRearm,
+    MonitorOptions, RENAME_PAIR_WINDOW, RearmAction, RelativeClock, RenameConsumer, RenamePrinter,
+    RenameSync, RenameTracker, SeenPaths, WatchExit, WatchInput, WatchTiming, dedupe_watch_paths,
+    dispatch_rename, display_path, effective_watch_paths, event_path_lines, flush_renames,
+    format_trace, matches_grep, new_subdirs_to_watch, parent_rename_watches,
+    renames_need_parent_watch, run_watch_loop, set_display_base, target_file_watch_dirs,
+    touches_watch_paths,
+};
+use chaser::path_sync::{
+    LoadOptions, PathSyncManager, StatusFilter, load_sync_manager, missing_watch_roots,
+    preview_target_paths, print_diff, save_state, suggest_watch_roots, uncovered_paths,
+};
+use chaser::serve::spawn_status_server;
+use chaser::state::{PathDrift, StateStore, Transaction};
+use chaser::target_files::{
+    PathUpdate, TargetFile, convert_target_file, scan_target_candidates, set_max_extract_depth,
+};
+use chaser::theme::{Role, Themed, set_theme};
+use chaser::write_mode::{is_temp_write_path, set_line_ending, set_no_write, set_write_strategy};
+use chaser::{get_event_description, is_reported_event, version_info};
 use notify::{
     Config as NotifyConfig, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
 use owo_colors::OwoColorize;
-use path_sync::PathSyncManager;
-use std::path::Path;
+use regex::Regex;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 fn main() -> Result<()> {
-    // Load config first to get language preference
-    let config = Config::load().unwrap_or_default();
+    // The config is loaded before the CLI is parsed (it decides the language),
+    // so the sandbox flag has to be picked up from the raw arguments first
+    set_no_write(std::env::args().any(|arg| arg == "--no-write"));
+    set_profile(profile_from_args(std::env::args()))?;
+
+    // Load config first to get language preference; the commands load (and
+    // on first run create) it again with localized messages
+    let mut config = Config::load_quiet().unwrap_or_default();
+    config.apply_env_overrides()?;
+    set_write_strategy(config.write_strategy);
+    set_line_ending(config.line_ending);
+    set_theme(config.theme);
+    if let Some(depth) = config.max_extract_depth {
+        set_max_extract_depth(depth);
+    }
     let locale = config.get_effective_language();
 
     // Initialize i18n with the preferred language
@@ -28,19 +70,46 @@ fn main() -> Result<()> {
     // Build CLI with internationalized strings
     let cli = build_cli();
     let matches = cli.get_matches();
+    set_display_base(relative_to(&matches));
 
     match parse_command(&matches) {
-        Some(command) => handle_command(command),
-        None => run_monitor(),
+        // Coverage checks the watches the monitor would register, so it takes
+        // the monitor's flags and defaults
+        Some(Commands::Coverage) => {
+            coverage(&monitor_options(&config, &matches)?, load_options(&matches))
+        }
+        Some(command) => handle_command(command, load_options(&matches)),
+        None => run_monitor(monitor_options(&config, &matches)?),
     }
 }
 
-fn handle_command(command: Commands) -> Result<()> {
-    let mut config = Config::load_with_i18n()?;
+/// The monitor options from `matches`, with `default_monitor_args` merged in
+/// when configured
+fn monitor_options(config: &Config, matches: &clap::ArgMatches) -> Result<MonitorOptions> {
+    if config.default_monitor_args.is_empty() {
+        return Ok(parse_monitor_options(matches));
+    }
+    let args =
+        with_default_monitor_args(&build_cli(), &config.default_monitor_args, std::env::args());
+    let matches = build_cli()
+        .try_get_matches_from(args)
+        .context(t("msg_invalid_default_monitor_args"))?;
+    // The globals were taken from the raw arguments; the defaults may set them too
+    set_no_write(matches.get_flag("no-write"));
+    set_profile(matches.get_one::<String>("profile").cloned())?;
+    set_display_base(relative_to(&matches));
+    Ok(parse_monitor_options(&matches))
+}
+
+fn handle_command(command: Commands, load: LoadOptions) -> Result<()> {
+    let mut config = Config::load_or_default_readonly()?;
 
     match command {
-        Commands::Add { path } => {
-            config.add_path(path)?;
+        Commands::Add { path, label } => {
+            config.add_path(path.clone())?;
+            if label.is_some() {
+                config.set_label(&path, label);
+            }
             config.save_with_i18n()?;
         }
         Commands::Remove { path } => {
@@ -56,95 +125,280 @@ fn handle_command(command: Commands) -> Result<()> {
                 "{}",
                 tf(
                     "msg_config_location",
-                    &[&config_path.display().to_string().cyan().to_string()]
+                    &[&config_path
+                        .display()
+                        .to_string()
+                        .themed(Role::Path)
+                        .to_string()]
                 )
             );
-            println!("{}", t("msg_config_edit_hint").bright_white());
+            println!("{}", t("msg_config_edit_hint").themed(Role::Text));
         }
         Commands::Recursive { enabled } => {
-            let enabled_bool = match enabled.to_lowercase().as_str() {
-                "true" | "1" | "yes" | "on" => true,
-                "false" | "0" | "no" | "off" => false,
-                _ => {
-                    println!("{}", tf("msg_recursive_invalid", &[&enabled]).red());
-                    return Ok(());
-                }
-            };
+            let enabled_bool = parse_bool_flexible(&enabled)
+                .with_context(|| tf("msg_recursive_invalid", &[&enabled]))?;
             config.recursive = enabled_bool;
             println!(
                 "{}",
-                tf("msg_recursive_set", &[&enabled_bool.to_string()]).green()
+                tf("msg_recursive_set", &[&enabled_bool.to_string()]).themed(Role::Success)
             );
             config.save_with_i18n()?;
         }
         Commands::Ignore { pattern } => {
-            if !config.ignore_patterns.contains(&pattern) {
-                config.ignore_patterns.push(pattern.clone());
-                println!("{}", tf("msg_ignore_added", &[&pattern]).green());
+            IgnoreMatcher::validate_pattern(&pattern)?;
+            if config.add_ignore_pattern(pattern.clone())? {
+                println!(
+                    "{}",
+                    tf("msg_ignore_added", &[&pattern]).themed(Role::Success)
+                );
                 config.save_with_i18n()?;
             } else {
-                println!("{}", tf("msg_ignore_exists", &[&pattern]).yellow());
+                println!(
+                    "{}",
+                    tf("msg_ignore_exists", &[&pattern]).themed(Role::Warn)
+                );
             }
         }
+        Commands::ClearIgnores { keep_defaults } => {
+            let removed = config.clear_ignore_patterns(keep_defaults);
+            config.save_with_i18n()?;
+            let key = if keep_defaults {
+                "msg_ignores_reset_to_defaults"
+            } else {
+                "msg_ignores_cleared"
+            };
+            println!(
+                "{}",
+                tf(key, &[&format_number(removed as u64)]).themed(Role::Success)
+            );
+        }
         Commands::Reset => {
             config = Config::default();
             config.save_with_i18n()?;
-            println!("{}", t("msg_config_reset").green());
+            println!("{}", t("msg_config_reset").themed(Role::Success));
         }
         Commands::Lang { language } => {
             if is_locale_supported(&language) {
                 config.set_language(Some(language.clone()))?;
                 config.save_with_i18n()?;
                 set_locale(&language);
-                println!("{}", tf("msg_language_set", &[&language]).green());
+                println!(
+                    "{}",
+                    tf("msg_language_set", &[&language]).themed(Role::Success)
+                );
             } else {
-                let available = available_locales().join(", ");
+                let available = format_list(&available_locales());
                 println!(
                     "{}",
-                    tf("msg_language_invalid", &[&language, &available]).red()
+                    tf("msg_language_invalid", &[&language, &available]).themed(Role::Error)
                 );
             }
         }
-        Commands::AddTarget { file } => {
+        Commands::AddTarget {
+            file,
+            dry_run: true,
+        } => {
+            apply_overrides(&mut config)?;
+            preview_target(&config, &file)?;
+        }
+        Commands::AddTarget { file, .. } => {
             config.add_target_file(file.clone())?;
             config.save_with_i18n()?;
-            println!("{}", tf("msg_target_added", &[&file]).green());
+            println!("{}", tf("msg_target_added", &[&file]).themed(Role::Success));
+        }
+        Commands::AddFromStdin { targets } => {
+            let input = std::io::stdin().lock();
+            let report = if targets {
+                config.add_target_files_from(input)?
+            } else {
+                config.add_paths_from(input)?
+            };
+            if report.added > 0 {
+                config.save_with_i18n()?;
+            }
+            println!(
+                "{}",
+                tf(
+                    "msg_bulk_add_summary",
+                    &[
+                        &format_number(report.added as u64),
+                        &format_number(report.skipped as u64),
+                        &format_number(report.invalid as u64)
+                    ]
+                )
+                .themed(Role::Success)
+            );
         }
         Commands::RemoveTarget { file } => {
             config.remove_target_file(&file)?;
             config.save_with_i18n()?;
-            println!("{}", tf("msg_target_removed", &[&file]).green());
+            println!(
+                "{}",
+                tf("msg_target_removed", &[&file]).themed(Role::Success)
+            );
+        }
+        Commands::Version { verbose } => {
+            println!("{}", version_info(verbose));
+        }
+        Commands::Profiles => {
+            let profiles = Config::list_profiles()?;
+            if profiles.is_empty() {
+                println!("{}", t("msg_no_profiles").themed(Role::Warn));
+            } else {
+                let active = active_profile();
+                println!("{}", t("msg_profiles_header").themed(Role::Heading));
+                for profile in profiles {
+                    if active.as_deref() == Some(profile.as_str()) {
+                        println!("  * {}", profile.themed(Role::Success));
+                    } else {
+                        println!("    {}", profile.themed(Role::Text));
+                    }
+                }
+            }
         }
         Commands::ListTargets => {
             let target_files = config.list_target_files();
             if target_files.is_empty() {
-                println!("{}", t("msg_no_targets").yellow());
+                println!("{}", t("msg_no_targets").themed(Role::Warn));
             } else {
                 println!("{}", t("msg_target_files"));
                 for file in target_files {
-                    println!("  - {}", file.bright_white());
+                    println!("  - {}", file.themed(Role::Text));
+                }
+            }
+        }
+        Commands::SuggestWatch => {
+            apply_overrides(&mut config)?;
+            suggest_watch(&config);
+        }
+        Commands::Reindex => {
+            apply_overrides(&mut config)?;
+            reindex(&config, load)?;
+        }
+        Commands::Coverage => unreachable!("coverage is dispatched with the monitor options"),
+        Commands::Scan {
+            dir,
+            add,
+            max_entries,
+        } => {
+            let candidates = scan_target_candidates(Path::new(&dir), max_entries)?;
+            if candidates.is_empty() {
+                println!("{}", tf("msg_scan_none", &[&dir]).themed(Role::Warn));
+                return Ok(());
+            }
+            println!("{}", t("msg_scan_header").themed(Role::Heading));
+            for candidate in &candidates {
+                println!(
+                    "  {}",
+                    tf(
+                        "msg_scan_candidate",
+                        &[
+                            &candidate.path.display().to_string(),
+                            &format_number(candidate.paths.len() as u64)
+                        ]
+                    )
+                    .themed(Role::Text)
+                );
+            }
+            if add {
+                for candidate in candidates {
+                    let file = std::path::absolute(&candidate.path)
+                        .unwrap_or(candidate.path)
+                        .display()
+                        .to_string();
+                    if let Some(existing) = config.find_target_file(&file) {
+                        println!(
+                            "{}",
+                            tf("msg_scan_already_added", &[&file, existing]).themed(Role::Muted)
+                        );
+                        continue;
+                    }
+                    config.add_target_file(file.clone())?;
+                    println!("{}", tf("msg_target_added", &[&file]).themed(Role::Success));
                 }
+                config.save_with_i18n()?;
             }
         }
-        Commands::Status => {
-            show_sync_status(&config)?;
+        Commands::Format { file, dry_run } => {
+            let target = TargetFile::new(PathBuf::from(&file))?;
+            if dry_run {
+                print!("{}", target.formatted_content()?);
+            } else {
+                match target.format_in_place()? {
+                    PathUpdate::Updated => println!(
+                        "{}",
+                        tf("msg_target_formatted", &[&file]).themed(Role::Success)
+                    ),
+                    PathUpdate::Previewed(diff) => print_diff(&diff),
+                    PathUpdate::Unchanged | PathUpdate::MissingOnDisk => println!(
+                        "{}",
+                        tf("msg_target_already_formatted", &[&file]).themed(Role::Muted)
+                    ),
+                }
+            }
+        }
+        Commands::Convert { src, dst } => {
+            let count = convert_target_file(Path::new(&src), Path::new(&dst))?;
+            println!(
+                "{}",
+                tf(
+                    "msg_target_converted",
+                    &[&format_number(count as u64), &src, &dst]
+                )
+                .themed(Role::Success)
+            );
+        }
+        Commands::Status {
+            filter,
+            keep_going,
+            flat,
+        } => {
+            apply_overrides(&mut config)?;
+            show_sync_status(&config, filter, LoadOptions { keep_going, ..load }, flat)?;
         }
     }
 
     Ok(())
 }
 
-fn run_monitor() -> Result<()> {
-    let config = Config::load_with_i18n()?;
+fn run_monitor(options: MonitorOptions) -> Result<()> {
+    let mut config = Config::load_or_default_readonly()?;
+
+    let current_dir = std::env::current_dir()?;
+    if options.here && options.save {
+        config.add_path(current_dir.display().to_string())?;
+        config.save_with_i18n()?;
+    }
+    // Applied after the --save above so environment values never reach the file
+    apply_overrides(&mut config)?;
+    resume_pending_sync(&config, options.load_options());
+    add_session_watch_paths(&mut config, &options)?;
+
+    // Session-only patterns, appended after the configured ones
+    for ignore_file in &options.ignore_from {
+        let patterns = load_ignore_file(ignore_file)?;
+        println!(
+            "{}",
+            tf(
+                "msg_ignore_file_loaded",
+                &[
+                    &ignore_file.display().to_string(),
+                    &patterns.len().to_string()
+                ]
+            )
+            .themed(Role::Success)
+        );
+        merge_ignore_patterns(&mut config.ignore_patterns, patterns);
+    }
 
     // Validate paths
     let invalid_paths = config.validate_paths();
     if !invalid_paths.is_empty() {
-        println!("{}", t("msg_invalid_paths_warning").yellow());
+        println!("{}", t("msg_invalid_paths_warning").themed(Role::Warn));
         for path in &invalid_paths {
-            println!("  - {}", path.red());
+            println!("  - {}", path.themed(Role::Error));
         }
-        println!("{}", t("msg_add_valid_paths_hint").bright_white());
+        println!("{}", t("msg_add_valid_paths_hint").themed(Role::Text));
     }
 
     let valid_paths: Vec<_> = config
@@ -154,46 +408,181 @@ fn run_monitor() -> Result<()> {
         .collect();
 
     if valid_paths.is_empty() {
-        println!("{}", t("msg_no_valid_paths").red());
+        println!("{}", t("msg_no_valid_paths").themed(Role::Error));
+        println!("{}", t("msg_here_hint").themed(Role::Text));
         return Ok(());
     }
 
     // Show target files list on startup
     if !config.target_files.is_empty() {
-        println!("\n{}", t("msg_target_files_header").bright_yellow());
+        println!("\n{}", t("msg_target_files_header").themed(Role::Heading));
         for (i, target_file) in config.target_files.iter().enumerate() {
             let exists = Path::new(target_file).exists();
             let status = if exists {
-                t("msg_target_file_exists").green().to_string()
+                t("msg_target_file_exists")
+                    .themed(Role::Success)
+                    .to_string()
             } else {
-                t("msg_target_file_missing").red().to_string()
+                t("msg_target_file_missing").themed(Role::Error).to_string()
             };
-            println!("  {} {} {}", i + 1, status, target_file.bright_white());
+            println!("  {} {} {}", i + 1, status, target_file.themed(Role::Text));
         }
         println!();
     }
 
-    println!("{}", t("msg_monitoring_start").bright_green());
+    println!("{}", t("msg_monitoring_start").themed(Role::Success));
     println!(
         "{}",
-        tf("msg_monitoring_paths", &[&valid_paths.len().to_string()]).bright_white()
+        tf(
+            "msg_monitoring_paths",
+            &[&format_number(valid_paths.len() as u64)]
+        )
+        .themed(Role::Text)
     );
     for path in &valid_paths {
-        println!("  - {}", path.cyan());
+        println!("  - {}", path.themed(Role::Path));
     }
     println!(
         "{}",
-        tf("msg_monitoring_recursive", &[&config.recursive.to_string()]).bright_white()
+        tf("msg_monitoring_recursive", &[&config.recursive.to_string()]).themed(Role::Text)
     );
 
-    watch(&config)
+    if options.report_missing_on_start && !config.target_files.is_empty() {
+        report_missing_paths(&config, options.load_options())?;
+    }
+
+    watch(&config, &options)
+}
+
+/// Apply `--here` and `--watch-roots-from-targets` to the watch paths of this
+/// session; none of it is saved
+fn add_session_watch_paths(config: &mut Config, options: &MonitorOptions) -> Result<()> {
+    config.watch_paths =
+        effective_watch_paths(&config.watch_paths, options.here, &std::env::current_dir()?);
+    if options.watch_roots_from_targets {
+        let target_files = load_target_files_skipping_errors(config);
+        for root in missing_watch_roots(&target_files, &config.watch_paths) {
+            println!(
+                "{}",
+                tf("msg_watch_root_from_targets", &[&root]).themed(Role::Success)
+            );
+            config.watch_paths.push(root);
+        }
+    }
+    Ok(())
+}
+
+/// Everything the monitor registers a watcher for
+struct WatchRoots {
+    /// The existing watch paths, duplicates collapsed
+    paths: Vec<String>,
+    collapsed: Vec<(String, String)>,
+    /// Parents of watch paths, so renames of the paths themselves are seen
+    parents: Vec<PathBuf>,
+    /// Directories of target files outside the watch paths
+    target_dirs: Vec<PathBuf>,
+    /// Directories of symlink targets outside the watch paths
+    symlink_dirs: Vec<PathBuf>,
+}
+
+impl WatchRoots {
+    fn plan(config: &Config, options: &MonitorOptions) -> Result<Self> {
+        let existing_paths: Vec<String> = config
+            .watch_paths
+            .iter()
+            .filter(|p| Path::new(p).exists())
+            .cloned()
+            .collect();
+        let deduped = dedupe_watch_paths(&existing_paths);
+        let parents = parent_rename_watches(
+            &deduped.paths,
+            config
+                .watch_parent_for_renames
+                .unwrap_or_else(renames_need_parent_watch),
+            config.recursive,
+        );
+
+        let mut target_dirs = Vec::new();
+        if options.watch_targets && !config.target_files.is_empty() {
+            let target_files: Vec<String> = config
+                .target_files
+                .iter()
+                .map(|file| expand_path(file).display().to_string())
+                .collect();
+            target_dirs = target_file_watch_dirs(&target_files, &deduped.paths, config.recursive);
+        }
+
+        let mut symlink_dirs = Vec::new();
+        if options.watch_symlink_targets && !config.target_files.is_empty() {
+            let targets: Vec<String> = load_sync_manager(config, options.load_options())?
+                .with_symlink_targets(true)
+                .symlink_targets()
+                .iter()
+                .map(|target| target.display().to_string())
+                .collect();
+            symlink_dirs = target_file_watch_dirs(&targets, &deduped.paths, config.recursive);
+        }
+
+        Ok(Self {
+            paths: deduped.paths,
+            collapsed: deduped.collapsed,
+            parents,
+            target_dirs,
+            symlink_dirs,
+        })
+    }
+
+    /// Each directory to watch, its mode, and the message key announcing it
+    fn watches(
+        &self,
+        recursive_mode: RecursiveMode,
+    ) -> Vec<(PathBuf, RecursiveMode, &'static str)> {
+        let flat = |dirs: &[PathBuf], key| {
+            dirs.iter()
+                .map(move |dir| (dir.clone(), RecursiveMode::NonRecursive, key))
+                .collect::<Vec<_>>()
+        };
+        let mut watches: Vec<_> = self
+            .paths
+            .iter()
+            .map(|path| (PathBuf::from(path), recursive_mode, "msg_watching_path"))
+            .collect();
+        watches.extend(flat(&self.parents, "msg_watching_parent"));
+        watches.extend(flat(&self.target_dirs, "msg_watching_target_dir"));
+        watches.extend(flat(&self.symlink_dirs, "msg_watching_symlink_target_dir"));
+        watches
+    }
 }
 
-fn watch(config: &Config) -> Result<()> {
+fn watch(config: &Config, options: &MonitorOptions) -> Result<()> {
     let (tx, rx) = channel();
+    // Dropping the sender ends the watch loop, which is how Ctrl-C stops it
+    let sender = Arc::new(Mutex::new(Some(tx)));
+    if options.summary_only {
+        let sender = Arc::clone(&sender);
+        ctrlc::set_handler(move || {
+            if sender
+                .lock()
+                .map(|mut tx| tx.take())
+                .ok()
+                .flatten()
+                .is_none()
+            {
+                // A second Ctrl-C while the summary is pending exits right away
+                std::process::exit(130);
+            }
+        })?;
+    }
 
     // Create file watcher
-    let mut watcher = RecommendedWatcher::new(tx, NotifyConfig::default())?;
+    let mut watcher = RecommendedWatcher::new(
+        move |res| {
+            if let Some(tx) = &*sender.lock().unwrap() {
+                let _ = tx.send(res);
+            }
+        },
+        NotifyConfig::default(),
+    )?;
 
     // Watch all configured paths
     let recursive_mode = if config.recursive {
@@ -202,232 +591,883 @@ fn watch(config: &Config) -> Result<()> {
         RecursiveMode::NonRecursive
     };
 
-    for path in &config.watch_paths {
-        if Path::new(path).exists() {
-            watcher.watch(Path::new(path), recursive_mode)?;
-            println!("{}", tf("msg_watching_path", &[path]).bright_green());
+    let roots = WatchRoots::plan(config, options)?;
+    for (duplicate, kept) in &roots.collapsed {
+        println!(
+            "{}",
+            tf("msg_watch_path_duplicate", &[duplicate, kept]).themed(Role::Warn)
+        );
+    }
+    for (dir, mode, key) in roots.watches(recursive_mode) {
+        watcher.watch(&dir, mode)?;
+        println!(
+            "{}",
+            tf(key, &[&dir.display().to_string()]).themed(Role::Success)
+        );
+    }
+    let WatchRoots {
+        paths: watched_paths,
+        parents: parent_watches,
+        target_dirs,
+        symlink_dirs,
+        ..
+    } = roots;
+
+    let mut rearm = config.rearm_deleted_files.unwrap_or(true).then(|| {
+        FileRearm::new(
+            watched_paths
+                .iter()
+                .map(PathBuf::from)
+                .filter(|path| path.is_file()),
+        )
+    });
+    let mut rearm_parents: Vec<PathBuf> = Vec::new();
+
+    let mut target_watch = if options.watch_targets && !config.target_files.is_empty() {
+        Some(load_sync_manager(config, options.load_options())?)
+    } else {
+        None
+    };
+
+    // Events in the directories of symlink targets are reported, so moves of
+    // the files tracked symlinks point to are seen
+    let mut reported_roots = watched_paths.clone();
+    reported_roots.extend(symlink_dirs.iter().map(|dir| dir.display().to_string()));
+
+    let mut root_ignores = if config.respect_chaserignore {
+        let (root_ignores, errors) = RootIgnores::discover(&watched_paths);
+        for root in &root_ignores.roots {
+            println!(
+                "{}",
+                tf(
+                    "msg_ignore_file_loaded",
+                    &[
+                        &root.root.join(CHASERIGNORE_FILE).display().to_string(),
+                        &root.patterns.len().to_string()
+                    ]
+                )
+                .themed(Role::Success)
+            );
+        }
+        for e in errors {
+            println!(
+                "{}",
+                tf("msg_ignore_file_failed", &[&e.to_string()]).themed(Role::Warn)
+            );
+        }
+        root_ignores
+    } else {
+        RootIgnores::default()
+    };
+    for (path, patterns) in &config.watch_ignores {
+        if config.watch_paths.contains(path) {
+            root_ignores.add_patterns(Path::new(path), patterns);
         }
     }
 
-    println!("{}", t("msg_monitoring_started").bright_green().bold());
+    if let Some(addr) = options.serve {
+        let manager = load_sync_manager(
+            config,
+            LoadOptions {
+                keep_going: true,
+                ..options.load_options()
+            },
+        )?;
+        let bound = spawn_status_server(addr, Arc::new(Mutex::new(manager)))?;
+        println!(
+            "{}",
+            tf("msg_serving_status", &[&bound.to_string()]).themed(Role::Success)
+        );
+    }
 
-    for res in rx {
-        match res {
-            Ok(event) => {
-                if should_ignore_event(&event, &config.ignore_patterns) {
-                    continue;
+    println!(
+        "{}",
+        t("msg_monitoring_started").themed(Role::Success).bold()
+    );
+
+    let mut output = EventOutput::new(options)
+        .with_sinks(open_sinks(&options.log_sinks, options.log_file.as_deref())?);
+    // The display and path sync both subscribe to this watcher's renames; sync
+    // pairs them on its own, once per move whatever the display, and before
+    // the display filters, which only decide what is shown
+    let mut renames = RenameTracker::new(config.rename_display, RENAME_PAIR_WINDOW);
+    let mut printer = RenamePrinter::new(config.rename_display);
+    let mut sync_renames = RenameTracker::pairing(RENAME_PAIR_WINDOW);
+    let mut rename_sync = RenameSync { config, options };
+    let timing = WatchTiming {
+        idle_timeout: options.watch_timeout,
+        max_runtime: options.max_runtime,
+        stats_interval: options.stats_interval,
+        aggregate_interval: options.aggregate,
+        rename_flush_interval: (config.rename_display == RenameDisplay::Combined)
+            .then_some(RENAME_PAIR_WINDOW),
+    };
+    let mut aggregator = options
+        .aggregate
+        .map(|_| EventAggregator::new(&reported_roots));
+    let grep = options.grep.as_deref().map(Regex::new).transpose()?;
+    let mut counters = EventCounters::default();
+    let clock = RelativeClock::start();
+    let mut last_event = None;
+    let mut seen_paths = SeenPaths::default();
+    let mut event_deduper = EventDeduper::new(EVENT_DEDUP_WINDOW);
+    let mut ignore_matcher = IgnoreMatcher::new(config.ignore_patterns.clone())
+        .with_roots(&watched_paths)
+        .with_hidden(config.ignore_hidden, options.watch_hidden.clone())
+        .with_cache(IGNORE_CACHE_CAPACITY);
+    let exit = run_watch_loop(&rx, timing, |input| match input {
+        WatchInput::Item(Ok(mut event)) => {
+            if options.trace {
+                eprintln!("{}", format_trace(&event).dimmed());
+            }
+            if event_deduper.is_repeat(&event, Instant::now()) {
+                return false;
+            }
+            // The paths to explain once every filter has had its say
+            let explained = options.explain.then(|| event.paths.clone());
+            if let Some(manager) = target_watch.as_mut() {
+                check_edited_targets(manager, &event);
+            }
+            for action in rearm
+                .as_mut()
+                .map(|r| r.on_event(&event))
+                .unwrap_or_default()
+            {
+                apply_rearm(
+                    action,
+                    &mut watcher,
+                    recursive_mode,
+                    &parent_watches,
+                    &mut rearm_parents,
+                );
+            }
+            // New directories are watched whether or not their event is shown
+            for dir in new_subdirs_to_watch(&event, config.recursive, config.watch_new_subdirs) {
+                match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+                    Ok(()) => println!(
+                        "{}",
+                        tf("msg_watching_new_subdir", &[&dir.display().to_string()])
+                            .themed(Role::Success)
+                    ),
+                    Err(e) => println!(
+                        "{}",
+                        tf(
+                            "msg_watch_new_subdir_failed",
+                            &[&dir.display().to_string(), &e.to_string()]
+                        )
+                        .themed(Role::Warn)
+                    ),
                 }
-                handle_event(event);
             }
-            Err(e) => println!(
+            let temp_write = event.paths.iter().any(|path| is_temp_write_path(path));
+            let outside = (!parent_watches.is_empty()
+                || !rearm_parents.is_empty()
+                || !target_dirs.is_empty()
+                || !symlink_dirs.is_empty())
+                && !touches_watch_paths(&event, &reported_roots);
+            if !temp_write && !outside {
+                dispatch_rename(
+                    &event,
+                    &mut sync_renames,
+                    Instant::now(),
+                    &mut output,
+                    &mut [&mut rename_sync],
+                );
+            }
+            let dropped = if !is_reported_event(&event.kind, config.report_access) || temp_write {
+                Some(IgnoreVerdict::Unreported)
+            } else if !matches_grep(&event, grep.as_ref()) {
+                Some(IgnoreVerdict::Grep)
+            } else if !ignore_matcher.retain_unignored(&mut event)
+                || !root_ignores.retain_unignored(&mut event)
+            {
+                // Every path was ignored; each is explained by its own rule
+                Some(IgnoreVerdict::Unmatched)
+            } else if outside {
+                Some(IgnoreVerdict::OutsideWatchPaths)
+            } else {
+                None
+            };
+            if let Some(verdict) = dropped {
+                if let Some(paths) = &explained {
+                    explain_paths(
+                        &mut output,
+                        paths,
+                        &event.paths,
+                        Some(verdict),
+                        &ignore_matcher,
+                        &root_ignores,
+                    );
+                }
+                counters.record_ignored();
+                return false;
+            }
+            let repeat = options.once_per_path && !seen_paths.first_seen(&event);
+            if let Some(paths) = &explained {
+                explain_paths(
+                    &mut output,
+                    paths,
+                    &event.paths,
+                    repeat.then_some(IgnoreVerdict::AlreadySeen),
+                    &ignore_matcher,
+                    &root_ignores,
+                );
+            }
+            if repeat {
+                return false;
+            }
+            counters.record(&get_event_description(&event));
+            last_event = Some(Instant::now());
+            if let Some(aggregator) = aggregator.as_mut() {
+                // Aggregated output replaces the per-event lines
+                aggregator.record(&event);
+                return true;
+            }
+            handle_event(
+                event,
+                &mut output,
+                &mut renames,
+                config.max_paths_per_event,
+                &mut [&mut printer],
+            );
+            true
+        }
+        WatchInput::Item(Err(e)) => {
+            println!(
                 "{}",
-                tf("msg_monitoring_error", &[&format!("{:?}", e)]).red()
-            ),
+                tf("msg_monitoring_error", &[&format!("{:?}", e)]).themed(Role::Error)
+            );
+            false
+        }
+        WatchInput::StatsDue => {
+            if options.summary_only {
+                return false;
+            }
+            print_stats_line(&counters);
+            if let Some(at) = last_event {
+                println!(
+                    "{}",
+                    tf("msg_stats_last_event", &[&clock.ago(at, Instant::now())])
+                        .themed(Role::Muted)
+                );
+            }
+            print_ignore_stats(&ignore_matcher);
+            false
+        }
+        WatchInput::AggregateDue => {
+            if let Some(aggregator) = aggregator.as_mut() {
+                for line in aggregator.take_report() {
+                    output.emit(line.themed(Role::Info));
+                }
+            }
+            false
+        }
+        WatchInput::RenamesDue => {
+            let now = Instant::now();
+            flush_renames(&mut sync_renames, now, &mut output, &mut [&mut rename_sync]);
+            if aggregator.is_none() {
+                flush_renames(&mut renames, now, &mut output, &mut [&mut printer]);
+            }
+            false
         }
+    });
+
+    // Report the events of the unfinished window
+    if let Some(aggregator) = aggregator.as_mut() {
+        for line in aggregator.take_report() {
+            output.emit(line.themed(Role::Info));
+        }
+    }
+
+    if options.stats_interval.is_some() {
+        print_ignore_stats(&ignore_matcher);
+    }
+
+    if exit == WatchExit::IdleTimeout
+        && let Some(timeout) = options.watch_timeout
+    {
+        println!(
+            "{}",
+            tf("msg_watch_idle_timeout", &[&format!("{:?}", timeout)]).themed(Role::Warn)
+        );
+    }
+    if exit == WatchExit::MaxRuntime
+        && let Some(runtime) = options.max_runtime
+    {
+        println!(
+            "{}",
+            tf("msg_watch_max_runtime", &[&format!("{:?}", runtime)]).themed(Role::Warn)
+        );
+    }
+    if options.summary_only || exit == WatchExit::MaxRuntime {
+        print_stats_line(&counters);
     }
 
     Ok(())
 }
 
-fn handle_event(event: Event) {
+fn print_stats_line(counters: &EventCounters) {
+    println!(
+        "{}",
+        tf(
+            "msg_stats_line",
+            &[
+                &counters.seen.to_string(),
+                &counters.ignored.to_string(),
+                &counters.kind_summary()
+            ]
+        )
+        .themed(Role::Heading)
+    );
+}
+
+fn handle_event(
+    event: Event,
+    output: &mut EventOutput,
+    renames: &mut RenameTracker,
+    max_paths: Option<usize>,
+    consumers: &mut [&mut dyn RenameConsumer],
+) {
     match event.kind {
         EventKind::Create(_) => {
-            for path in &event.paths {
-                println!(
-                    "{}",
-                    tf(
-                        "msg_file_created",
-                        &[&path.display().to_string().cyan().to_string()]
-                    )
-                    .green()
-                );
-            }
+            emit_paths(
+                output,
+                &event.paths,
+                max_paths,
+                "msg_file_created",
+                EventLevel::Info,
+                |line| line.themed(Role::Success).to_string(),
+            );
         }
         EventKind::Modify(modify_kind) => {
             match modify_kind {
-                notify::event::ModifyKind::Name(name_kind) => {
-                    match name_kind {
-                        notify::event::RenameMode::Both => {
-                            // This is the actual rename event with both old and new paths
-                            if event.paths.len() >= 2 {
-                                let old_path = &event.paths[0];
-                                let new_path = &event.paths[1];
-
-                                println!("{}", t("msg_file_renamed").yellow());
-                                println!(
-                                    "{}",
-                                    tf(
-                                        "msg_rename_from",
-                                        &[&old_path.display().to_string().cyan().to_string()]
-                                    )
-                                );
-                                println!(
-                                    "{}",
-                                    tf(
-                                        "msg_rename_to",
-                                        &[&new_path.display().to_string().cyan().to_string()]
-                                    )
-                                );
-
-                                // Try to sync path changes to target files
-                                let config = Config::load_with_i18n().unwrap_or_default();
-                                if !config.target_files.is_empty() {
-                                    // Convert absolute paths to relative paths for better matching
-                                    let current_dir = std::env::current_dir().unwrap_or_default();
-
-                                    let old_path_str =
-                                        if let Ok(relative) = old_path.strip_prefix(&current_dir) {
-                                            format!("./{}", relative.display())
-                                        } else {
-                                            old_path.display().to_string()
-                                        };
-
-                                    let new_path_str =
-                                        if let Ok(relative) = new_path.strip_prefix(&current_dir) {
-                                            format!("./{}", relative.display())
-                                        } else {
-                                            new_path.display().to_string()
-                                        };
-
-                                    match PathSyncManager::new(
-                                        config.target_files.clone(),
-                                        config.watch_paths.clone(),
-                                    ) {
-                                        Ok(mut manager) => {
-                                            match manager
-                                                .sync_path_change(&old_path_str, &new_path_str)
-                                            {
-                                                Ok(()) => {
-                                                    println!(
-                                                        "{}",
-                                                        tf(
-                                                            "msg_target_files_updated",
-                                                            &[&old_path_str, &new_path_str]
-                                                        )
-                                                        .bright_green()
-                                                    );
-                                                }
-                                                Err(e) => {
-                                                    println!(
-                                                        "{}",
-                                                        tf(
-                                                            "msg_failed_to_update_target_files",
-                                                            &[&e.to_string()]
-                                                        )
-                                                        .red()
-                                                    );
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            println!(
-                                                "{}",
-                                                tf(
-                                                    "msg_could_not_initialize_path_sync",
-                                                    &[&e.to_string()]
-                                                )
-                                                .red()
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        notify::event::RenameMode::From => {
-                            // First phase of rename, can be ignored for cleaner output
-                            println!(
-                                "{}",
-                                tf(
-                                    "msg_rename_started",
-                                    &[&event.paths[0].display().to_string().cyan().to_string()]
-                                )
-                                .yellow()
-                            );
-                        }
-                        notify::event::RenameMode::To => {
-                            // Second phase of rename, can be ignored for cleaner output
-                            println!(
-                                "{}",
-                                tf(
-                                    "msg_rename_completed",
-                                    &[&event.paths[0].display().to_string().cyan().to_string()]
-                                )
-                                .yellow()
-                            );
-                        }
-                        _ => {
-                            for path in &event.paths {
-                                println!(
-                                    "{}",
-                                    tf(
-                                        "msg_name_modified",
-                                        &[&path.display().to_string().cyan().to_string()]
-                                    )
-                                    .yellow()
-                                );
-                            }
-                        }
+                notify::event::ModifyKind::Name(_) => {
+                    if !dispatch_rename(&event, renames, Instant::now(), output, consumers) {
+                        emit_paths(
+                            output,
+                            &event.paths,
+                            max_paths,
+                            "msg_name_modified",
+                            EventLevel::Info,
+                            |line| line.themed(Role::Warn).to_string(),
+                        );
                     }
                 }
                 notify::event::ModifyKind::Data(_) => {
-                    for path in &event.paths {
-                        println!(
-                            "{}",
-                            tf(
-                                "msg_file_content_modified",
-                                &[&path.display().to_string().cyan().to_string()]
-                            )
-                            .blue()
-                        );
-                    }
+                    emit_paths(
+                        output,
+                        &event.paths,
+                        max_paths,
+                        "msg_file_content_modified",
+                        EventLevel::Info,
+                        |line| line.themed(Role::Info).to_string(),
+                    );
                 }
                 notify::event::ModifyKind::Metadata(_) => {
                     // Metadata changes are usually not important, ignore them
                 }
                 _ => {
-                    for path in &event.paths {
-                        println!(
-                            "{}",
-                            tf(
-                                "msg_file_modified",
-                                &[&path.display().to_string().cyan().to_string()]
-                            )
-                            .blue()
-                        );
-                    }
+                    emit_paths(
+                        output,
+                        &event.paths,
+                        max_paths,
+                        "msg_file_modified",
+                        EventLevel::Info,
+                        |line| line.themed(Role::Info).to_string(),
+                    );
                 }
             }
         }
         EventKind::Remove(_) => {
-            for path in &event.paths {
-                println!(
+            emit_paths(
+                output,
+                &event.paths,
+                max_paths,
+                "msg_file_deleted",
+                EventLevel::Warn,
+                |line| line.themed(Role::Error).to_string(),
+            );
+        }
+        EventKind::Access(_) => {
+            // Only reaches here when report_access is enabled
+            emit_paths(
+                output,
+                &event.paths,
+                max_paths,
+                "msg_file_accessed",
+                EventLevel::Info,
+                |line| line.themed(Role::Muted).to_string(),
+            );
+        }
+        EventKind::Any | EventKind::Other => {}
+    }
+}
+
+/// Emit one `key` line per path, styled by `style`, capped at `max_paths`
+fn emit_paths(
+    output: &mut EventOutput,
+    paths: &[PathBuf],
+    max_paths: Option<usize>,
+    key: &str,
+    level: EventLevel,
+    style: fn(String) -> String,
+) {
+    let lines = event_path_lines(paths, max_paths, |path| {
+        tf(key, &[&display_path(path).themed(Role::Path).to_string()])
+    });
+    for line in lines {
+        output.emit_at(level, style(line));
+    }
+}
+
+fn show_sync_status(
+    config: &Config,
+    filter: StatusFilter,
+    load: LoadOptions,
+    flat: bool,
+) -> Result<()> {
+    config.validate_target_files()?;
+
+    if flat {
+        if !config.target_files.is_empty() {
+            let manager = load_sync_manager(config, load)?;
+            for line in manager.flat_status_lines(filter) {
+                println!("{}", line);
+            }
+        }
+        return Ok(());
+    }
+
+    println!("{}", t("msg_sync_status_header").themed(Role::Heading));
+    println!("{}", "─".repeat(50).themed(Role::Muted));
+
+    if config.target_files.is_empty() {
+        println!("{}", t("msg_no_targets_configured").themed(Role::Warn));
+        return Ok(());
+    }
+
+    let manager = load_sync_manager(config, load)?;
+    report_state_drift(&manager);
+    manager.print_status(filter);
+
+    Ok(())
+}
+
+/// Print how many paths each ignore pattern suppressed, flagging unused ones
+fn print_ignore_stats(ignore_matcher: &IgnoreMatcher) {
+    println!("{}", t("msg_ignore_stats_header").themed(Role::Heading));
+    for (pattern, hits) in ignore_matcher.pattern_stats() {
+        let line = if hits == 0 {
+            tf("msg_ignore_stats_unused", &[pattern])
+        } else {
+            tf("msg_ignore_stats_line", &[pattern, &format_number(hits)])
+        };
+        println!("  {}", line.themed(Role::Heading));
+    }
+    if ignore_matcher.hidden_hits() > 0 {
+        println!(
+            "  {}",
+            tf(
+                "msg_ignore_stats_hidden",
+                &[&ignore_matcher.hidden_hits().to_string()]
+            )
+            .themed(Role::Heading)
+        );
+    }
+}
+
+/// Explain, for each of the event's original `paths`, why the monitor shows or
+/// drops it: paths the ignore rules removed by their rule, the rest by
+/// `dropped`, or as shown when the event got through
+fn explain_paths(
+    output: &mut EventOutput,
+    paths: &[PathBuf],
+    kept: &[PathBuf],
+    dropped: Option<IgnoreVerdict>,
+    ignore_matcher: &IgnoreMatcher,
+    root_ignores: &RootIgnores,
+) {
+    for path in paths {
+        let rule = || {
+            let verdict = ignore_matcher.explain(&path.to_string_lossy());
+            match root_ignores.path_pattern(path) {
+                Some(pattern) if !verdict.is_ignored() => {
+                    IgnoreVerdict::Pattern(pattern.to_string())
+                }
+                _ => verdict,
+            }
+        };
+        let verdict = match &dropped {
+            Some(verdict) if kept.contains(path) && verdict.is_ignored() => verdict.clone(),
+            _ => rule(),
+        };
+        output.emit(verdict.explanation(&display_path(path)).dimmed());
+    }
+}
+
+/// Apply the environment overrides to a loaded config and collapse target
+/// files listed under several spellings, warning once per duplicate
+fn apply_overrides(config: &mut Config) -> Result<()> {
+    config.apply_env_overrides()?;
+    for (duplicate, kept) in config.deduplicate_target_files() {
+        eprintln!(
+            "{}",
+            tf("msg_duplicate_target_file", &[&duplicate, &kept]).themed(Role::Warn)
+        );
+    }
+    Ok(())
+}
+
+/// Apply a watch change for a deleted or recreated watched file
+///
+/// Parents already watched for renames are left alone; `rearm_parents` holds
+/// the parents watched only while a file is missing.
+fn apply_rearm(
+    action: RearmAction,
+    watcher: &mut RecommendedWatcher,
+    mode: RecursiveMode,
+    parent_watches: &[PathBuf],
+    rearm_parents: &mut Vec<PathBuf>,
+) {
+    match action {
+        RearmAction::WatchParent(parent) => {
+            if parent_watches.contains(&parent) {
+                return;
+            }
+            match watcher.watch(&parent, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    println!(
+                        "{}",
+                        tf("msg_watched_file_deleted", &[&parent.display().to_string()])
+                            .themed(Role::Warn)
+                    );
+                    rearm_parents.push(parent);
+                }
+                Err(e) => println!(
+                    "{}",
+                    tf(
+                        "msg_watch_parent_failed",
+                        &[&parent.display().to_string(), &e.to_string()]
+                    )
+                    .themed(Role::Warn)
+                ),
+            }
+        }
+        RearmAction::Rearm {
+            file,
+            release_parent,
+        } => {
+            match watcher.watch(&file, mode) {
+                Ok(()) => println!(
+                    "{}",
+                    tf("msg_watched_file_rearmed", &[&file.display().to_string()])
+                        .themed(Role::Success)
+                ),
+                Err(e) => println!(
                     "{}",
                     tf(
-                        "msg_file_deleted",
-                        &[&path.display().to_string().cyan().to_string()]
+                        "msg_rearm_watched_file_failed",
+                        &[&file.display().to_string(), &e.to_string()]
                     )
-                    .red()
+                    .themed(Role::Warn)
+                ),
+            }
+            if let Some(parent) = release_parent
+                && let Some(index) = rearm_parents.iter().position(|p| *p == parent)
+            {
+                rearm_parents.remove(index);
+                let _ = watcher.unwatch(&parent);
+            }
+        }
+    }
+}
+
+/// Reload the target files changed by `event` and warn about the paths they
+/// newly list that don't exist
+fn check_edited_targets(manager: &mut PathSyncManager, event: &Event) {
+    if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+        return;
+    }
+    for path in &event.paths {
+        if !manager.is_target_file(path) {
+            continue;
+        }
+        let target = path.display().to_string();
+        match manager.reload_target(path) {
+            Ok(missing) => {
+                for missing_path in missing {
+                    println!(
+                        "{}",
+                        tf("msg_target_lists_missing_path", &[&target, &missing_path])
+                            .themed(Role::Warn)
+                    );
+                }
+            }
+            Err(e) => println!(
+                "{}",
+                tf("msg_target_reload_failed", &[&target, &format!("{:#}", e)]).themed(Role::Error)
+            ),
+        }
+    }
+}
+
+/// Print the tracked paths that don't exist, before any event is handled
+fn report_missing_paths(config: &Config, load: LoadOptions) -> Result<()> {
+    let missing = load_sync_manager(config, load)?.missing_paths();
+    if missing.is_empty() {
+        println!("{}", t("msg_missing_on_start_none").themed(Role::Success));
+        return Ok(());
+    }
+    println!(
+        "{}",
+        tf(
+            "msg_missing_on_start_header",
+            &[&format_number(missing.len() as u64)]
+        )
+        .themed(Role::Warn)
+    );
+    for path in &missing {
+        println!("  - {}", path.themed(Role::Error));
+    }
+    Ok(())
+}
+
+/// Print the paths `file` would contribute as a target file, without adding it
+fn preview_target(config: &Config, file: &str) -> Result<()> {
+    let target_file = TargetFile::new(expand_path(file))?;
+    let (in_scope, out_of_scope) = preview_target_paths(&target_file, &config.watch_paths);
+
+    println!(
+        "{}",
+        tf(
+            "msg_target_preview_header",
+            &[file, &format_number(target_file.paths.len() as u64)]
+        )
+        .themed(Role::Heading)
+    );
+    for entry in &in_scope {
+        println!(
+            "  {} {}",
+            "✓".themed(Role::Success),
+            entry.path.themed(Role::Text)
+        );
+    }
+    for entry in &out_of_scope {
+        println!(
+            "  {} {} {}",
+            "✗".themed(Role::Warn),
+            entry.path.themed(Role::Muted),
+            t("msg_target_preview_outside").themed(Role::Warn)
+        );
+    }
+    println!("{}", t("msg_target_preview_not_added").themed(Role::Muted));
+    Ok(())
+}
+
+/// Load every configured target file, warning about and skipping the ones
+/// that fail to load
+fn load_target_files_skipping_errors(config: &Config) -> Vec<TargetFile> {
+    config
+        .target_files
+        .iter()
+        .filter_map(|target| match TargetFile::new(PathBuf::from(target)) {
+            Ok(target_file) => Some(target_file),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    tf("msg_target_file_skipped", &[target, &format!("{:#}", e)])
+                        .themed(Role::Warn)
                 );
+                None
             }
+        })
+        .collect()
+}
+
+/// Print the directories that would cover every tracked path, marking the
+/// ones already inside a watch path
+fn suggest_watch(config: &Config) {
+    let target_files = load_target_files_skipping_errors(config);
+    let roots = suggest_watch_roots(&target_files);
+    if roots.is_empty() {
+        println!("{}", t("msg_suggest_watch_none").themed(Role::Warn));
+        return;
+    }
+    println!("{}", t("msg_suggest_watch_header").themed(Role::Heading));
+    for root in roots {
+        if PathSyncManager::is_in_watch_dirs(&root, &config.watch_paths) {
+            println!(
+                "  {} {}",
+                root.themed(Role::Text),
+                t("msg_suggest_watch_covered").themed(Role::Muted)
+            );
+        } else {
+            println!("  {}", root.themed(Role::Text));
         }
-        EventKind::Access(_) => {}
-        EventKind::Any | EventKind::Other => {}
     }
 }
 
-fn show_sync_status(config: &Config) -> Result<()> {
-    config.validate_target_files()?;
+/// Complete a sync that an earlier run left unfinished
+fn resume_pending_sync(config: &Config, load: LoadOptions) {
+    let pending = Transaction::journal_file_path().is_ok_and(|journal| journal.exists());
+    if !pending || config.target_files.is_empty() {
+        return;
+    }
 
-    println!("{}", t("msg_sync_status_header").bright_blue());
-    println!("{}", "─".repeat(50).bright_black());
+    let resumed = load_sync_manager(config, load)
+        .and_then(|mut manager| Ok((manager.resume_pending()?, manager)));
+    match resumed {
+        Ok((Some(summary), manager)) => {
+            println!(
+                "{}",
+                tf(
+                    "msg_transaction_resumed",
+                    &[&format_number(summary.targets.len() as u64)]
+                )
+                .themed(Role::Success)
+            );
+            // The resumed rewrites are not drift on the next status
+            save_state(&manager);
+        }
+        Ok((None, _)) => {}
+        Err(e) => println!(
+            "{}",
+            tf("msg_failed_to_update_target_files", &[&e.to_string()]).themed(Role::Error)
+        ),
+    }
+}
 
+/// Report changes made to tracked paths since the last recorded run, then
+/// record the current state
+fn report_state_drift(manager: &PathSyncManager) {
+    let store = StateStore::load().unwrap_or_default();
+    for (target, changes) in manager.detect_drift(&store) {
+        println!("{}", tf("msg_drift_header", &[&target]).themed(Role::Warn));
+        for change in changes {
+            println!("  {}", drift_line(change));
+        }
+    }
+    save_state(manager);
+}
+
+fn drift_line(change: PathDrift) -> String {
+    match change {
+        PathDrift::Added(path) => tf("msg_drift_added", &[&path]),
+        PathDrift::Removed(path) => tf("msg_drift_removed", &[&path]),
+        PathDrift::Appeared(path) => tf("msg_drift_appeared", &[&path]),
+        PathDrift::Disappeared(path) => tf("msg_drift_disappeared", &[&path]),
+        PathDrift::Modified(path) => tf("msg_drift_modified", &[&path]),
+    }
+}
+
+/// Reload every target file from scratch and report how the tracked paths
+/// differ from the last recorded run, then record the new state
+fn reindex(config: &Config, load: LoadOptions) -> Result<()> {
     if config.target_files.is_empty() {
-        println!("{}", t("msg_no_targets_configured").yellow());
+        println!("{}", t("msg_no_targets_configured").themed(Role::Warn));
         return Ok(());
     }
 
-    let manager = PathSyncManager::new(config.target_files.clone(), config.watch_paths.clone())?;
-    manager.print_status();
+    // Files that fail to load are reported below rather than aborting
+    let mut manager = load_sync_manager(
+        config,
+        LoadOptions {
+            keep_going: true,
+            ..load
+        },
+    )?;
+    manager.restore_state(&StateStore::load().unwrap_or_default());
+    let report = manager.refresh_with_report();
+
+    for (target, changes) in &report.changes {
+        println!(
+            "{}",
+            tf("msg_reindex_header", &[target]).themed(Role::Heading)
+        );
+        for change in changes {
+            println!("  {}", drift_line(change.clone()));
+        }
+    }
+    for failed in &report.failed {
+        println!(
+            "{}",
+            tf("msg_reindex_failed", &[&failed.path, &failed.error]).themed(Role::Error)
+        );
+    }
+    if report.is_empty() {
+        println!("{}", t("msg_reindex_unchanged").themed(Role::Success));
+    }
+    save_state(&manager);
+    Ok(())
+}
 
+/// Register every watcher the monitor would, and list the tracked paths none
+/// of the live ones cover
+fn coverage(options: &MonitorOptions, load: LoadOptions) -> Result<()> {
+    let mut config = Config::load_or_default_readonly()?;
+    apply_overrides(&mut config)?;
+    if config.target_files.is_empty() {
+        println!("{}", t("msg_no_targets_configured").themed(Role::Warn));
+        return Ok(());
+    }
+    add_session_watch_paths(&mut config, options)?;
+
+    let options = MonitorOptions {
+        keep_going: true,
+        no_auto_create: options.no_auto_create || !load.auto_create,
+        ..options.clone()
+    };
+    let roots = WatchRoots::plan(&config, &options)?;
+    let recursive_mode = if config.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+    let (tx, _rx) = channel();
+    let mut watcher = RecommendedWatcher::new(tx, NotifyConfig::default())?;
+    let mut live_roots = Vec::new();
+    for (dir, mode, _) in roots.watches(recursive_mode) {
+        let path = dir.display().to_string();
+        match watcher.watch(&dir, mode) {
+            Ok(()) => live_roots.push((path, mode == RecursiveMode::Recursive)),
+            Err(e) => println!(
+                "{}",
+                tf("msg_coverage_watch_failed", &[&path, &e.to_string()]).themed(Role::Error)
+            ),
+        }
+    }
+
+    // Every path the target files list, not only those inside the watch
+    // paths, which are all a sync manager would track
+    let mut tracked: Vec<String> = load_target_files_skipping_errors(&config)
+        .into_iter()
+        .flat_map(|target_file| target_file.paths)
+        .map(|entry| entry.path)
+        .collect();
+    tracked.sort();
+    tracked.dedup();
+    let uncovered = uncovered_paths(&tracked, &live_roots);
+    if uncovered.is_empty() {
+        println!(
+            "{}",
+            tf(
+                "msg_coverage_complete",
+                &[&format_number(tracked.len() as u64)]
+            )
+            .themed(Role::Success)
+        );
+    } else {
+        println!(
+            "{}",
+            tf(
+                "msg_coverage_uncovered",
+                &[&format_number(uncovered.len() as u64)]
+            )
+            .themed(Role::Warn)
+        );
+        for path in &uncovered {
+            println!("  - {}", path.themed(Role::Path));
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,2272 @@
+use crate::config::{Config, RenameDisplay};
+use crate::i18n::{format_list, format_number, t, tf};
+use crate::log_sink::{EventLevel, EventSink, LogSink, TerminalSink};
+use crate::path_sync::{
+    LoadOptions, PathSyncManager, event_path_key, load_sync_manager, save_state,
+};
+use crate::theme::{Role, Themed};
+use anyhow::{Result, bail};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long an identical line stays suppressed under `--dedupe-output`
+pub const DEDUPE_OUTPUT_WINDOW: Duration = Duration::from_millis(500);
+
+/// Directory printed paths are shown relative to, set by `--relative-to`
+static DISPLAY_BASE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Show printed paths relative to `base` for the whole process
+pub fn set_display_base(base: Option<PathBuf>) {
+    let base = base.map(|base| std::path::absolute(&base).unwrap_or(base));
+    *DISPLAY_BASE.write().unwrap() = base;
+}
+
+/// `path` as printed: relative to the `--relative-to` base when beneath it
+pub fn display_path(path: &Path) -> String {
+    match DISPLAY_BASE.read().unwrap().as_deref() {
+        Some(base) => relative_display(path, base),
+        None => path.display().to_string(),
+    }
+}
+
+/// `path` relative to `base`, or unchanged when it is not beneath `base`
+pub fn relative_display(path: &Path, base: &Path) -> String {
+    match path.strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+        Ok(relative) => relative.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+/// How long an identical event delivered by another watch root counts as a repeat
+pub const EVENT_DEDUP_WINDOW: Duration = Duration::from_millis(50);
+
+/// How long a rename `From` waits for its `To` under combined rename display
+pub const RENAME_PAIR_WINDOW: Duration = Duration::from_millis(200);
+
+/// Options that only apply to the file monitor (the bare `chaser` invocation)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonitorOptions {
+    /// Suppress lines identical to the previously printed one
+    pub dedupe_output: bool,
+    /// Watch the current directory for this session
+    pub here: bool,
+    /// Persist the `--here` directory to the config
+    pub save: bool,
+    /// Stop watching after this long without a non-ignored event
+    pub watch_timeout: Option<Duration>,
+    /// Stop watching after this long regardless of activity
+    pub max_runtime: Option<Duration>,
+    /// Print event counters this often
+    pub stats_interval: Option<Duration>,
+    /// Print one grouped summary per window of this length instead of each event
+    pub aggregate: Option<Duration>,
+    /// Extra ignore pattern files merged into the config patterns
+    pub ignore_from: Vec<PathBuf>,
+    /// Hidden paths to watch even when `ignore_hidden` is on
+    pub watch_hidden: Vec<String>,
+    /// Print which rule showed or ignored each event
+    pub explain: bool,
+    /// Only handle events with a path matching this regular expression
+    pub grep: Option<String>,
+    /// Print every raw event to stderr before any filtering
+    pub trace: bool,
+    /// Skip target files that fail to load instead of aborting the sync
+    pub keep_going: bool,
+    /// Fail on missing target files instead of creating them
+    pub no_auto_create: bool,
+    /// Report only the first event of each path
+    pub once_per_path: bool,
+    /// List tracked paths that are already missing before watching starts
+    pub report_missing_on_start: bool,
+    /// Reload edited target files and warn about newly listed missing paths
+    pub watch_targets: bool,
+    /// Also watch the directories covering the tracked paths, without saving them
+    pub watch_roots_from_targets: bool,
+    /// Prefix event lines with the time since monitoring started
+    pub relative_times: bool,
+    /// Print no event lines, only the statistics when monitoring ends
+    pub summary_only: bool,
+    /// Match tracked symlinks by the file they point to and watch that file
+    pub watch_symlink_targets: bool,
+    /// Where event lines go; empty means the terminal
+    pub log_sinks: Vec<LogSink>,
+    /// File written by the file log sink
+    pub log_file: Option<PathBuf>,
+    /// Serve live status over HTTP on this address
+    pub serve: Option<SocketAddr>,
+}
+
+impl MonitorOptions {
+    /// How the monitor loads target files
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            keep_going: self.keep_going,
+            auto_create: !self.no_auto_create,
+            quiet: false,
+        }
+    }
+}
+
+/// Parse a duration such as `30s`, `5m` or `2h`; a bare number means seconds
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let (number, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => input.split_at(index),
+        None => (input, "s"),
+    };
+
+    let Ok(value) = number.parse::<u64>() else {
+        bail!("Invalid duration: '{}' (expected e.g. 30s, 5m, 2h)", input);
+    };
+    let seconds = match unit {
+        "s" => value,
+        "m" => value.saturating_mul(60),
+        "h" => value.saturating_mul(3600),
+        _ => bail!("Invalid duration unit in '{}' (use s, m or h)", input),
+    };
+    if seconds == 0 {
+        bail!("Invalid duration: '{}' (must be longer than zero)", input);
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Why the watch loop stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchExit {
+    /// The event source went away
+    Disconnected,
+    /// No activity arrived within the idle timeout
+    IdleTimeout,
+    /// The maximum runtime elapsed
+    MaxRuntime,
+}
+
+/// Timers driving the watch loop besides incoming events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WatchTiming {
+    /// Stop after this long without activity
+    pub idle_timeout: Option<Duration>,
+    /// Stop this long after starting, active or not
+    pub max_runtime: Option<Duration>,
+    /// Ask for a stats report this often
+    pub stats_interval: Option<Duration>,
+    /// Ask for an aggregated report this often
+    pub aggregate_interval: Option<Duration>,
+    /// Ask for held-back rename phases to be flushed this often
+    pub rename_flush_interval: Option<Duration>,
+}
+
+/// What the watch loop hands to its handler
+#[derive(Debug)]
+pub enum WatchInput<T> {
+    /// An item received from the channel
+    Item(T),
+    /// The stats interval elapsed
+    StatsDue,
+    /// The aggregation window elapsed
+    AggregateDue,
+    /// Held-back rename phases may have expired
+    RenamesDue,
+}
+
+/// Fires once per elapsed interval
+///
+/// A zero interval, or one reaching past what [`Instant`] can represent, never fires.
+#[derive(Debug, Clone, Copy)]
+pub struct IntervalTimer {
+    interval: Duration,
+    next_due: Option<Instant>,
+}
+
+impl IntervalTimer {
+    pub fn new(interval: Duration, start: Instant) -> Self {
+        Self {
+            interval,
+            next_due: (!interval.is_zero())
+                .then(|| start.checked_add(interval))
+                .flatten(),
+        }
+    }
+
+    pub fn next_due(&self) -> Option<Instant> {
+        self.next_due
+    }
+
+    /// Return whether the interval has elapsed at `now`, re-arming it if so
+    pub fn poll(&mut self, now: Instant) -> bool {
+        if self.next_due.is_none_or(|due| now < due) {
+            return false;
+        }
+        // Skip missed intervals rather than firing for each of them
+        while let Some(due) = self.next_due
+            && due <= now
+        {
+            self.next_due = due.checked_add(self.interval);
+        }
+        true
+    }
+}
+
+/// Renders instants relative to a monotonic start, so wall clock adjustments
+/// never make times jump or go negative
+#[derive(Debug, Clone, Copy)]
+pub struct RelativeClock {
+    start: Instant,
+}
+
+impl RelativeClock {
+    /// A clock started now
+    pub fn start() -> Self {
+        Self::started_at(Instant::now())
+    }
+
+    pub fn started_at(start: Instant) -> Self {
+        Self { start }
+    }
+
+    /// `at` as an offset from the start, such as `+1m05s`
+    pub fn since_start(&self, at: Instant) -> String {
+        format!(
+            "+{}",
+            format_relative(at.saturating_duration_since(self.start))
+        )
+    }
+
+    /// How long before `now` the instant `at` was, such as `2s ago`
+    pub fn ago(&self, at: Instant, now: Instant) -> String {
+        tf(
+            "msg_time_ago",
+            &[&format_relative(now.saturating_duration_since(at))],
+        )
+    }
+}
+
+/// A short duration such as `250ms`, `42s`, `3m07s` or `2h05m`
+pub fn format_relative(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0 => format!("{}ms", duration.as_millis()),
+        1..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m{:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// Where the watch loop reads the time and waits for items
+pub trait WatchClock {
+    fn now(&mut self) -> Instant;
+
+    /// The next item from `rx`, waiting at most until `until` if given
+    fn recv_until<T>(
+        &mut self,
+        rx: &Receiver<T>,
+        until: Option<Instant>,
+    ) -> Result<T, RecvTimeoutError>;
+}
+
+/// The real time, blocking on the channel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl WatchClock for SystemClock {
+    fn now(&mut self) -> Instant {
+        Instant::now()
+    }
+
+    fn recv_until<T>(
+        &mut self,
+        rx: &Receiver<T>,
+        until: Option<Instant>,
+    ) -> Result<T, RecvTimeoutError> {
+        match until {
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(until) => rx.recv_timeout(until.saturating_duration_since(Instant::now())),
+        }
+    }
+}
+
+/// Feed items from `rx` to `handle` until the channel closes, the loop idles
+/// out or the maximum runtime is reached
+///
+/// `handle` returns whether the input counts as activity; only activity resets
+/// the idle timeout countdown.
+pub fn run_watch_loop<T>(
+    rx: &Receiver<T>,
+    timing: WatchTiming,
+    handle: impl FnMut(WatchInput<T>) -> bool,
+) -> WatchExit {
+    run_watch_loop_with(rx, timing, &mut SystemClock, handle)
+}
+
+/// [`run_watch_loop`] driven by `clock`
+pub fn run_watch_loop_with<T>(
+    rx: &Receiver<T>,
+    timing: WatchTiming,
+    clock: &mut impl WatchClock,
+    mut handle: impl FnMut(WatchInput<T>) -> bool,
+) -> WatchExit {
+    let start = clock.now();
+    let mut deadline = timing
+        .idle_timeout
+        .and_then(|timeout| start.checked_add(timeout));
+    let end = timing
+        .max_runtime
+        .and_then(|runtime| start.checked_add(runtime));
+    let mut stats = timing
+        .stats_interval
+        .map(|interval| IntervalTimer::new(interval, start));
+    let mut aggregate = timing
+        .aggregate_interval
+        .map(|interval| IntervalTimer::new(interval, start));
+    let mut rename_flush = timing
+        .rename_flush_interval
+        .map(|interval| IntervalTimer::new(interval, start));
+
+    loop {
+        let timers = [stats, aggregate, rename_flush].map(|timer| timer.and_then(|t| t.next_due()));
+        let wake_at = [deadline, end].into_iter().chain(timers).flatten().min();
+
+        let item = match clock.recv_until(rx, wake_at) {
+            Ok(item) => Some(item),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => return WatchExit::Disconnected,
+        };
+
+        let now = clock.now();
+        if let Some(item) = item
+            && handle(WatchInput::Item(item))
+            && let Some(timeout) = timing.idle_timeout
+        {
+            deadline = now.checked_add(timeout);
+        }
+        if let Some(timer) = &mut stats
+            && timer.poll(now)
+        {
+            handle(WatchInput::StatsDue);
+        }
+        if let Some(timer) = &mut aggregate
+            && timer.poll(now)
+        {
+            handle(WatchInput::AggregateDue);
+        }
+        if let Some(timer) = &mut rename_flush
+            && timer.poll(now)
+        {
+            handle(WatchInput::RenamesDue);
+        }
+        if end.is_some_and(|end| now >= end) {
+            return WatchExit::MaxRuntime;
+        }
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            return WatchExit::IdleTimeout;
+        }
+    }
+}
+
+/// Running totals of the events seen by the monitor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventCounters {
+    /// Events received, including ignored ones
+    pub seen: u64,
+    /// Events dropped by ignore patterns
+    pub ignored: u64,
+    /// Handled events per kind description
+    pub by_kind: BTreeMap<String, u64>,
+}
+
+impl EventCounters {
+    pub fn record_ignored(&mut self) {
+        self.seen += 1;
+        self.ignored += 1;
+    }
+
+    pub fn record(&mut self, kind: &str) {
+        self.seen += 1;
+        *self.by_kind.entry(kind.to_string()).or_insert(0) += 1;
+    }
+
+    /// Per-kind counts such as `Created 2, Modified 5`, or `-` if there are none
+    pub fn kind_summary(&self) -> String {
+        if self.by_kind.is_empty() {
+            return "-".to_string();
+        }
+        self.by_kind
+            .iter()
+            .map(|(kind, count)| format!("{} {}", kind, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// How an event is counted in an aggregated report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AggregateKind {
+    Created,
+    Modified,
+    Renamed,
+    Deleted,
+    Other,
+}
+
+impl AggregateKind {
+    pub fn of(kind: &notify::EventKind) -> Self {
+        use notify::EventKind;
+        use notify::event::ModifyKind;
+        match kind {
+            EventKind::Create(_) => Self::Created,
+            EventKind::Modify(ModifyKind::Name(_)) => Self::Renamed,
+            EventKind::Modify(_) => Self::Modified,
+            EventKind::Remove(_) => Self::Deleted,
+            _ => Self::Other,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Created => "msg_aggregate_created",
+            Self::Modified => "msg_aggregate_modified",
+            Self::Renamed => "msg_aggregate_renamed",
+            Self::Deleted => "msg_aggregate_deleted",
+            Self::Other => "msg_aggregate_other",
+        }
+    }
+}
+
+/// Counts of one kind of event under one root
+pub type AggregateGroup = (String, Vec<(AggregateKind, u64)>);
+
+/// Collects events over one `--aggregate` window, grouped by the watch root
+/// they happened under
+#[derive(Debug, Default)]
+pub struct EventAggregator {
+    roots: Vec<String>,
+    window: BTreeMap<String, BTreeMap<AggregateKind, u64>>,
+    /// The tracker, source path and completion of the last counted rename
+    last_rename: Option<(Option<usize>, PathBuf, bool)>,
+}
+
+impl EventAggregator {
+    pub fn new(roots: &[String]) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            window: BTreeMap::new(),
+            last_rename: None,
+        }
+    }
+
+    /// Count `event` under the innermost root holding its first path, or
+    /// under the path's directory if no root holds it
+    ///
+    /// The `From`, `To` and `Both` phases of one rename count once.
+    pub fn record(&mut self, event: &notify::Event) {
+        let Some(path) = event.paths.first() else {
+            return;
+        };
+        if let Some(phase) = RenamePhase::of(event)
+            && self.is_counted_rename(phase, event.attrs.tracker())
+        {
+            return;
+        }
+        let root = self
+            .roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.len())
+            .cloned()
+            .unwrap_or_else(|| path.parent().unwrap_or(path).to_string_lossy().to_string());
+        *self
+            .window
+            .entry(root)
+            .or_default()
+            .entry(AggregateKind::of(&event.kind))
+            .or_insert(0) += 1;
+    }
+
+    /// Whether `phase` belongs to the rename counted last, remembering it otherwise
+    fn is_counted_rename(&mut self, phase: RenamePhase, tracker: Option<usize>) -> bool {
+        // Backends without trackers pair phases by their order and paths
+        let same = |last: &(Option<usize>, PathBuf, bool), from: Option<&Path>| match last.0 {
+            Some(last_tracker) if tracker.is_some() => tracker == Some(last_tracker),
+            _ => from.is_none_or(|from| from == last.1),
+        };
+        match phase {
+            RenamePhase::From(from) => {
+                self.last_rename = Some((tracker, from.to_path_buf(), false));
+                false
+            }
+            RenamePhase::To(_) => match &mut self.last_rename {
+                Some(last) if !last.2 && same(last, None) => {
+                    last.2 = true;
+                    true
+                }
+                _ => {
+                    self.last_rename = None;
+                    false
+                }
+            },
+            RenamePhase::Both(from, _) => self
+                .last_rename
+                .take()
+                .is_some_and(|last| same(&last, Some(from))),
+        }
+    }
+
+    /// The counts of the window so far, per root, starting a new window
+    pub fn take_groups(&mut self) -> Vec<AggregateGroup> {
+        std::mem::take(&mut self.window)
+            .into_iter()
+            .map(|(root, counts)| (root, counts.into_iter().collect()))
+            .collect()
+    }
+
+    /// One line per root such as `5 created, 2 modified under /proj`,
+    /// starting a new window
+    pub fn take_report(&mut self) -> Vec<String> {
+        self.take_groups()
+            .into_iter()
+            .map(|(root, counts)| {
+                let parts: Vec<String> = counts
+                    .iter()
+                    .map(|(kind, count)| tf(kind.key(), &[&format_number(*count)]))
+                    .collect();
+                tf(
+                    "msg_aggregate_line",
+                    &[&format_list(&parts), &display_path(Path::new(&root))],
+                )
+            })
+            .collect()
+    }
+}
+
+/// Remembers the last emitted line to drop immediate byte-identical repeats
+#[derive(Debug)]
+pub struct OutputDeduper {
+    window: Duration,
+    last: Option<(String, Instant)>,
+}
+
+impl OutputDeduper {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last: None }
+    }
+
+    /// Decide whether `line` should be printed at `now`, recording it if so
+    pub fn should_emit(&mut self, line: &str, now: Instant) -> bool {
+        if let Some((last_line, last_time)) = &self.last
+            && last_line == line
+            && now.saturating_duration_since(*last_time) <= self.window
+        {
+            return false;
+        }
+
+        self.last = Some((line.to_string(), now));
+        true
+    }
+}
+
+/// Drops events equal in kind and paths to one seen shortly before, as
+/// delivered once per overlapping watch root
+#[derive(Debug)]
+pub struct EventDeduper {
+    window: Duration,
+    recent: Vec<(notify::EventKind, Vec<PathBuf>, Instant)>,
+}
+
+impl EventDeduper {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            recent: Vec::new(),
+        }
+    }
+
+    /// Whether `event` repeats one seen within the window before `now`,
+    /// recording it if not
+    pub fn is_repeat(&mut self, event: &notify::Event, now: Instant) -> bool {
+        self.recent
+            .retain(|(_, _, seen)| now.saturating_duration_since(*seen) <= self.window);
+        let repeat = self
+            .recent
+            .iter()
+            .any(|(kind, paths, _)| *kind == event.kind && *paths == event.paths);
+        if !repeat {
+            self.recent.push((event.kind, event.paths.clone(), now));
+        }
+        repeat
+    }
+}
+
+/// Paths already reported by `--once-per-path`
+#[derive(Debug, Default)]
+pub struct SeenPaths {
+    seen: HashSet<PathBuf>,
+}
+
+impl SeenPaths {
+    /// Record the paths of `event`; true if at least one was not seen before
+    pub fn first_seen(&mut self, event: &notify::Event) -> bool {
+        let mut any_new = false;
+        for path in &event.paths {
+            any_new |= self.seen.insert(path.clone());
+        }
+        any_new
+    }
+}
+
+/// Destination for the monitor's per-event lines
+pub struct EventOutput {
+    silent: bool,
+    deduper: Option<OutputDeduper>,
+    clock: Option<RelativeClock>,
+    sinks: Vec<Box<dyn EventSink>>,
+}
+
+impl EventOutput {
+    /// Output to the terminal
+    pub fn new(options: &MonitorOptions) -> Self {
+        Self {
+            silent: options.summary_only,
+            deduper: options
+                .dedupe_output
+                .then(|| OutputDeduper::new(DEDUPE_OUTPUT_WINDOW)),
+            clock: options.relative_times.then(RelativeClock::start),
+            sinks: vec![Box::new(TerminalSink)],
+        }
+    }
+
+    /// Replace the terminal with `sinks`
+    pub fn with_sinks(mut self, sinks: Vec<Box<dyn EventSink>>) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// Write one informational event line
+    pub fn emit(&mut self, line: impl Display) {
+        self.emit_at(EventLevel::Info, line);
+    }
+
+    /// Write one event line to every sink, subject to the configured output filters
+    pub fn emit_at(&mut self, level: EventLevel, line: impl Display) {
+        // Errors stay visible even when only the summary is wanted
+        if self.silent && level != EventLevel::Error {
+            return;
+        }
+        let mut line = line.to_string();
+        if let Some(deduper) = &mut self.deduper
+            && !deduper.should_emit(&line, Instant::now())
+        {
+            return;
+        }
+        if let Some(clock) = &self.clock {
+            line = format!("[{}] {}", clock.since_start(Instant::now()), line);
+        }
+        for sink in &mut self.sinks {
+            if let Err(e) = sink.write(level, &line) {
+                eprintln!("{}", tf("msg_log_sink_failed", &[&e.to_string()]));
+            }
+        }
+    }
+}
+
+/// One phase of a rename as reported by the watcher
+#[derive(Debug, Clone, Copy)]
+pub enum RenamePhase<'a> {
+    From(&'a Path),
+    To(&'a Path),
+    Both(&'a Path, &'a Path),
+}
+
+impl<'a> RenamePhase<'a> {
+    /// The rename phase `event` carries, if it is a rename
+    pub fn of(event: &'a notify::Event) -> Option<Self> {
+        use notify::event::{ModifyKind, RenameMode};
+        let notify::EventKind::Modify(ModifyKind::Name(mode)) = event.kind else {
+            return None;
+        };
+        match (mode, event.paths.as_slice()) {
+            (RenameMode::Both, [from, to, ..]) => Some(Self::Both(from, to)),
+            (RenameMode::From, [from, ..]) => Some(Self::From(from)),
+            (RenameMode::To, [to, ..]) => Some(Self::To(to)),
+            _ => None,
+        }
+    }
+}
+
+/// What the monitor reports for a rename
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameReport {
+    Started(PathBuf),
+    Completed(PathBuf),
+    /// A `From` no `To` followed within the window, so the path left the
+    /// watched tree
+    MovedOut(PathBuf),
+    Renamed {
+        from: PathBuf,
+        to: PathBuf,
+    },
+}
+
+/// Turns rename phases into reports according to the configured [`RenameDisplay`]
+///
+/// In combined mode a `From` is held back until the next rename phase: a `To`
+/// arriving within the window completes it into a single `Renamed`, another
+/// `From` reports it on its own as `Started`, and once the window passes
+/// without either it is reported as `MovedOut`. A `Both` repeating the pair
+/// just completed, as inotify sends after its `From` and `To`, is dropped.
+#[derive(Debug)]
+pub struct RenameTracker {
+    display: RenameDisplay,
+    window: Duration,
+    pending: Option<(PathBuf, Instant)>,
+    /// The `from` and `to` of the rename the last `To` completed
+    completed: Option<(PathBuf, PathBuf)>,
+}
+
+impl RenameTracker {
+    pub fn new(display: RenameDisplay, window: Duration) -> Self {
+        Self {
+            display,
+            window,
+            pending: None,
+            completed: None,
+        }
+    }
+
+    /// A tracker reporting each move once as `Renamed`, whatever the
+    /// configured display, for consumers such as path sync
+    pub fn pairing(window: Duration) -> Self {
+        Self::new(RenameDisplay::Combined, window)
+    }
+
+    pub fn display(&self) -> RenameDisplay {
+        self.display
+    }
+
+    /// Report the held-back `From` as moved out once its window has passed
+    pub fn expire(&mut self, now: Instant) -> Vec<RenameReport> {
+        match self.pending.take() {
+            Some((from, started)) if now.saturating_duration_since(started) > self.window => {
+                vec![RenameReport::MovedOut(from)]
+            }
+            pending => {
+                self.pending = pending;
+                Vec::new()
+            }
+        }
+    }
+
+    /// Classify a rename phase observed at `now`
+    pub fn classify(&mut self, phase: RenamePhase, now: Instant) -> Vec<RenameReport> {
+        if self.display == RenameDisplay::Verbose {
+            return vec![match phase {
+                RenamePhase::From(path) => RenameReport::Started(path.to_path_buf()),
+                RenamePhase::To(path) => RenameReport::Completed(path.to_path_buf()),
+                RenamePhase::Both(from, to) => RenameReport::Renamed {
+                    from: from.to_path_buf(),
+                    to: to.to_path_buf(),
+                },
+            }];
+        }
+
+        let mut reports = self.expire(now);
+        let pending = self.pending.take();
+        let completed = self.completed.take();
+
+        match phase {
+            RenamePhase::To(to) => match pending {
+                Some((from, _)) => {
+                    self.completed = Some((from.clone(), to.to_path_buf()));
+                    reports.push(RenameReport::Renamed {
+                        from,
+                        to: to.to_path_buf(),
+                    });
+                }
+                None => reports.push(RenameReport::Completed(to.to_path_buf())),
+            },
+            RenamePhase::From(from) => {
+                reports.extend(pending.map(|(from, _)| RenameReport::Started(from)));
+                self.pending = Some((from.to_path_buf(), now));
+            }
+            RenamePhase::Both(from, to) => {
+                reports.extend(pending.map(|(from, _)| RenameReport::Started(from)));
+                if completed.is_some_and(|(done_from, done_to)| done_from == from && done_to == to)
+                {
+                    return reports;
+                }
+                reports.push(RenameReport::Renamed {
+                    from: from.to_path_buf(),
+                    to: to.to_path_buf(),
+                });
+            }
+        }
+
+        reports
+    }
+}
+
+/// One subscriber to the renames paired up from the watcher's events, such as
+/// the event display or path sync
+pub trait RenameConsumer {
+    fn on_rename(&mut self, report: &RenameReport, output: &mut EventOutput);
+}
+
+/// Hand the renames `renames` gave up pairing by `now` to every consumer
+pub fn flush_renames(
+    renames: &mut RenameTracker,
+    now: Instant,
+    output: &mut EventOutput,
+    consumers: &mut [&mut dyn RenameConsumer],
+) {
+    for report in renames.expire(now) {
+        for consumer in consumers.iter_mut() {
+            consumer.on_rename(&report, output);
+        }
+    }
+}
+
+/// Pair the rename `event` carries and hand each report to every consumer in
+/// turn; returns whether `event` was a rename
+pub fn dispatch_rename(
+    event: &notify::Event,
+    renames: &mut RenameTracker,
+    now: Instant,
+    output: &mut EventOutput,
+    consumers: &mut [&mut dyn RenameConsumer],
+) -> bool {
+    let Some(phase) = RenamePhase::of(event) else {
+        return false;
+    };
+    for report in renames.classify(phase, now) {
+        for consumer in consumers.iter_mut() {
+            consumer.on_rename(&report, output);
+        }
+    }
+    true
+}
+
+/// Syncs completed renames to the target files of the monitoring session
+pub struct RenameSync<'a> {
+    /// The session config, including watch roots that were never saved
+    pub config: &'a Config,
+    pub options: &'a MonitorOptions,
+}
+
+impl RenameConsumer for RenameSync<'_> {
+    fn on_rename(&mut self, report: &RenameReport, output: &mut EventOutput) {
+        if let RenameReport::Renamed { from, to } = report {
+            sync_rename(from, to, output, self.config, self.options);
+        }
+    }
+}
+
+/// Try to sync a completed rename to the configured target files
+fn sync_rename(
+    old_path: &Path,
+    new_path: &Path,
+    output: &mut EventOutput,
+    config: &Config,
+    options: &MonitorOptions,
+) {
+    if config.target_files.is_empty() {
+        return;
+    }
+
+    // Convert absolute paths to relative paths for better matching
+    let current_dir = std::env::current_dir().unwrap_or_default();
+
+    let keys = event_path_key(old_path, &current_dir)
+        .and_then(|old| Ok((old, event_path_key(new_path, &current_dir)?)));
+    let (old_path_str, new_path_str) = match keys {
+        Ok(keys) => keys,
+        Err(e) => {
+            output.emit(tf("msg_path_sync_skipped", &[&e.to_string()]).themed(Role::Warn));
+            return;
+        }
+    };
+
+    if !PathSyncManager::is_in_watch_dirs(&new_path_str, &config.watch_paths) {
+        output.emit(tf("msg_sync_outside_watch_paths", &[&new_path_str]).themed(Role::Warn));
+    }
+
+    // The sync is reported through `output` below, so it honours
+    // --summary-only and the log sinks
+    let load = LoadOptions {
+        quiet: true,
+        ..options.load_options()
+    };
+    let manager = load_sync_manager(config, load)
+        .map(|manager| manager.with_symlink_targets(options.watch_symlink_targets));
+    match manager {
+        Ok(mut manager) => match manager.sync_path_change(&old_path_str, &new_path_str) {
+            Ok(summary) => {
+                // Without a rewritten entry there is nothing to report or record
+                if !summary.targets.is_empty() {
+                    output.emit(
+                        tf("msg_target_files_updated", &[&old_path_str, &new_path_str])
+                            .themed(Role::Success),
+                    );
+                    // Record the synced paths so status doesn't report them as drift
+                    save_state(&manager);
+                }
+                for line in summary.to_string().lines() {
+                    output.emit(format!("  {}", line).themed(Role::Text));
+                }
+            }
+            Err(e) => {
+                output.emit_at(
+                    EventLevel::Error,
+                    tf("msg_failed_to_update_target_files", &[&e.to_string()]).themed(Role::Error),
+                );
+            }
+        },
+        Err(e) => {
+            output.emit_at(
+                EventLevel::Error,
+                tf("msg_could_not_initialize_path_sync", &[&e.to_string()]).themed(Role::Error),
+            );
+        }
+    }
+}
+
+/// Prints renames as event lines in the configured [`RenameDisplay`]
+pub struct RenamePrinter {
+    display: RenameDisplay,
+}
+
+impl RenamePrinter {
+    pub fn new(display: RenameDisplay) -> Self {
+        Self { display }
+    }
+}
+
+impl RenameConsumer for RenamePrinter {
+    fn on_rename(&mut self, report: &RenameReport, output: &mut EventOutput) {
+        let path_arg = |path: &Path| display_path(path).themed(Role::Path).to_string();
+        match report {
+            RenameReport::Started(path) => {
+                output.emit(tf("msg_rename_started", &[&path_arg(path)]).themed(Role::Warn))
+            }
+            RenameReport::Completed(path) => {
+                output.emit(tf("msg_rename_completed", &[&path_arg(path)]).themed(Role::Warn))
+            }
+            RenameReport::MovedOut(path) => {
+                output.emit(tf("msg_moved_out", &[&path_arg(path)]).themed(Role::Warn))
+            }
+            RenameReport::Renamed { from, to } => match self.display {
+                RenameDisplay::Verbose => {
+                    output.emit(t("msg_file_renamed").themed(Role::Warn));
+                    output.emit(tf("msg_rename_from", &[&path_arg(from)]));
+                    output.emit(tf("msg_rename_to", &[&path_arg(to)]));
+                }
+                RenameDisplay::Combined => output.emit(
+                    tf(
+                        "msg_file_renamed_combined",
+                        &[&path_arg(from), &path_arg(to)],
+                    )
+                    .themed(Role::Warn),
+                ),
+            },
+        }
+    }
+}
+
+/// Resolve the watch paths for a monitoring session
+///
+/// With `here`, only `current_dir` is watched; otherwise the configured paths are used.
+pub fn effective_watch_paths(configured: &[String], here: bool, current_dir: &Path) -> Vec<String> {
+    if here {
+        vec![current_dir.display().to_string()]
+    } else {
+        configured.to_vec()
+    }
+}
+
+/// Result of collapsing watch paths that point at the same location
+#[derive(Debug, Default, PartialEq)]
+pub struct DedupedWatchPaths {
+    /// Paths to register, in their original order
+    pub paths: Vec<String>,
+    /// Dropped duplicates as `(duplicate, kept)` pairs
+    pub collapsed: Vec<(String, String)>,
+}
+
+/// Collapse watch paths that resolve to the same canonical location
+///
+/// Exact duplicates, different spellings (`./a` vs `a/../a`) and symlinks to an
+/// already-listed path are all watched once, keeping the first occurrence.
+/// Paths that cannot be canonicalized are compared as written.
+pub fn dedupe_watch_paths(paths: &[String]) -> DedupedWatchPaths {
+    let mut result = DedupedWatchPaths::default();
+    let mut seen: Vec<(PathBuf, String)> = Vec::new();
+
+    for path in paths {
+        let canonical = Path::new(path)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(path));
+
+        match seen.iter().find(|(existing, _)| *existing == canonical) {
+            Some((_, kept)) => result.collapsed.push((path.clone(), kept.clone())),
+            None => {
+                seen.push((canonical, path.clone()));
+                result.paths.push(path.clone());
+            }
+        }
+    }
+
+    result
+}
+
+/// Whether this platform reports renames of a watched file only to a watch
+/// on its parent directory
+pub fn renames_need_parent_watch() -> bool {
+    cfg!(any(target_os = "linux", target_os = "android"))
+}
+
+/// Whether watch path `path` needs its parent watched to see it being renamed
+///
+/// Only plain files do; directories already see renames of their entries.
+pub fn should_watch_parent(path: &Path, enabled: bool) -> bool {
+    enabled && path.is_file()
+}
+
+/// A watch change asked for by [`FileRearm`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RearmAction {
+    /// A watched file was deleted; watch this directory to see it come back
+    WatchParent(PathBuf),
+    /// A deleted watched file was recreated; watch it again, and stop
+    /// watching `release_parent` if given
+    Rearm {
+        file: PathBuf,
+        release_parent: Option<PathBuf>,
+    },
+}
+
+/// Tracks watched files through deletion and recreation, so a file replaced
+/// by an editor keeps being watched
+#[derive(Debug, Default)]
+pub struct FileRearm {
+    files: HashSet<PathBuf>,
+    /// Deleted watched files and the parent directory watched meanwhile
+    missing: HashMap<PathBuf, PathBuf>,
+}
+
+impl FileRearm {
+    pub fn new(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            files: files.into_iter().collect(),
+            missing: HashMap::new(),
+        }
+    }
+
+    /// The watch changes `event` calls for
+    ///
+    /// Renames are classified by whether the path exists afterwards.
+    pub fn on_event(&mut self, event: &notify::Event) -> Vec<RearmAction> {
+        use notify::EventKind;
+        use notify::event::{ModifyKind, RenameMode};
+
+        let mut actions = Vec::new();
+        for path in event.paths.iter().filter(|path| self.files.contains(*path)) {
+            let appeared = match event.kind {
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => true,
+                EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                    false
+                }
+                EventKind::Modify(ModifyKind::Name(_)) => path.exists(),
+                _ => continue,
+            };
+
+            if appeared {
+                if let Some(parent) = self.missing.remove(path) {
+                    let shared = self.missing.values().any(|other| *other == parent);
+                    actions.push(RearmAction::Rearm {
+                        file: path.clone(),
+                        release_parent: (!shared).then_some(parent),
+                    });
+                }
+            } else if !self.missing.contains_key(path) {
+                let parent = match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                    _ => PathBuf::from("."),
+                };
+                if !self.missing.values().any(|other| *other == parent) {
+                    actions.push(RearmAction::WatchParent(parent.clone()));
+                }
+                self.missing.insert(path.clone(), parent);
+            }
+        }
+        actions
+    }
+}
+
+/// Parent directories to watch non-recursively so renames of watched files show up
+///
+/// Parents already covered by a watched directory (itself, or an ancestor when
+/// `recursive`) are skipped, and each parent is listed once.
+pub fn parent_rename_watches(paths: &[String], enabled: bool, recursive: bool) -> Vec<PathBuf> {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let watched_dirs: Vec<PathBuf> = paths
+        .iter()
+        .map(Path::new)
+        .filter(|path| path.is_dir())
+        .map(canonical)
+        .collect();
+
+    let mut parents: Vec<PathBuf> = Vec::new();
+    for path in paths.iter().map(Path::new) {
+        if !should_watch_parent(path, enabled) {
+            continue;
+        }
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let resolved = canonical(&parent);
+        let covered = watched_dirs
+            .iter()
+            .any(|dir| *dir == resolved || (recursive && resolved.starts_with(dir)));
+        if !covered && !parents.iter().any(|p| canonical(p) == resolved) {
+            parents.push(parent);
+        }
+    }
+    parents
+}
+
+/// Directories to watch so edits of `target_files` are seen
+///
+/// The parent directory is watched rather than the file, since editors often
+/// save by replacing the file. Directories the watch paths already cover are
+/// skipped.
+pub fn target_file_watch_dirs(
+    target_files: &[String],
+    watch_paths: &[String],
+    recursive: bool,
+) -> Vec<PathBuf> {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let watched: Vec<PathBuf> = watch_paths
+        .iter()
+        .map(|p| canonical(Path::new(p)))
+        .collect();
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for target in target_files {
+        let dir = match Path::new(target).parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => canonical(parent),
+            _ => canonical(Path::new(".")),
+        };
+        let covered = watched
+            .iter()
+            .any(|w| *w == dir || (recursive && dir.starts_with(w)));
+        if !covered && !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Directories created or moved in by `event` that need a watch of their own
+///
+/// Only non-recursive watches with `watch_new_subdirs` miss the contents of
+/// new subdirectories. Backends that don't say whether a file or folder was
+/// created are answered by checking the path.
+pub fn new_subdirs_to_watch(
+    event: &notify::Event,
+    recursive: bool,
+    watch_new_subdirs: bool,
+) -> Vec<PathBuf> {
+    use notify::EventKind;
+    use notify::event::{CreateKind, ModifyKind, RenameMode};
+
+    if recursive || !watch_new_subdirs {
+        return Vec::new();
+    }
+    let dirs = |paths: &[PathBuf]| paths.iter().filter(|p| p.is_dir()).cloned().collect();
+    match event.kind {
+        EventKind::Create(CreateKind::Folder) => event.paths.clone(),
+        EventKind::Create(CreateKind::File) => Vec::new(),
+        EventKind::Create(_) => dirs(&event.paths),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Any)) => dirs(&event.paths),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            dirs(event.paths.get(1..).unwrap_or_default())
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// One output line per path of an event, capped at `max` paths
+///
+/// When paths are left out, a final "... and N more" line says how many.
+pub fn event_path_lines(
+    paths: &[PathBuf],
+    max: Option<usize>,
+    line: impl Fn(&Path) -> String,
+) -> Vec<String> {
+    let shown = max.map_or(paths.len(), |max| max.min(paths.len()));
+    let mut lines: Vec<String> = paths[..shown].iter().map(|path| line(path)).collect();
+    if shown < paths.len() {
+        lines.push(tf(
+            "msg_more_paths",
+            &[&format_number((paths.len() - shown) as u64)],
+        ));
+    }
+    lines
+}
+
+/// The raw event as printed by `--trace`: kind, every path and attributes
+pub fn format_trace(event: &notify::Event) -> String {
+    format!("[trace] {:#?}", event)
+}
+
+/// Whether `event` passes the `--grep` filter: any of its paths matches
+/// `regex`, or there is no filter
+pub fn matches_grep(event: &notify::Event, regex: Option<&Regex>) -> bool {
+    regex.is_none_or(|regex| {
+        event
+            .paths
+            .iter()
+            .any(|path| regex.is_match(&path.to_string_lossy()))
+    })
+}
+
+/// Whether any path of `event` is a watch path or lies beneath one
+///
+/// Events seen only through a parent rename watch that concern sibling files
+/// fail this check.
+pub fn touches_watch_paths(event: &notify::Event, paths: &[String]) -> bool {
+    event
+        .paths
+        .iter()
+        .any(|event_path| paths.iter().any(|path| event_path.starts_with(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_seen_paths_reports_each_path_once() {
+        let event = |paths: &[&str]| {
+            paths
+                .iter()
+                .fold(notify::Event::new(notify::EventKind::Any), |event, path| {
+                    event.add_path(PathBuf::from(path))
+                })
+        };
+        let mut seen = SeenPaths::default();
+
+        let reported: Vec<bool> = [
+            event(&["/w/a.txt"]),
+            event(&["/w/b.txt"]),
+            event(&["/w/a.txt"]),
+            event(&["/w/a.txt", "/w/c.txt"]),
+            event(&["/w/b.txt", "/w/c.txt"]),
+            event(&["/w/b.txt"]),
+        ]
+        .iter()
+        .map(|event| seen.first_seen(event))
+        .collect();
+
+        assert_eq!(reported, vec![true, true, false, true, false, false]);
+    }
+
+    #[test]
+    fn test_output_deduper_suppresses_repeat_within_window() {
+        let mut deduper = OutputDeduper::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        assert!(deduper.should_emit("File created: a", start));
+        assert!(!deduper.should_emit("File created: a", start + Duration::from_millis(100)));
+        assert!(deduper.should_emit("File created: b", start + Duration::from_millis(200)));
+        // Only the immediately preceding line counts
+        assert!(deduper.should_emit("File created: a", start + Duration::from_millis(300)));
+    }
+
+    /// Records every line it is given
+    struct MemorySink(std::sync::Arc<std::sync::Mutex<Vec<(EventLevel, String)>>>);
+
+    impl EventSink for MemorySink {
+        fn write(&mut self, level: EventLevel, line: &str) -> std::io::Result<()> {
+            self.0.lock().unwrap().push((level, line.to_string()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_event_output_dispatches_to_every_sink() {
+        let first = std::sync::Arc::default();
+        let second = std::sync::Arc::default();
+        let options = MonitorOptions {
+            dedupe_output: true,
+            ..Default::default()
+        };
+        let mut output = EventOutput::new(&options).with_sinks(vec![
+            Box::new(MemorySink(std::sync::Arc::clone(&first))),
+            Box::new(MemorySink(std::sync::Arc::clone(&second))),
+        ]);
+
+        output.emit("Created: /w/a");
+        output.emit("Created: /w/a");
+        output.emit_at(EventLevel::Warn, "Deleted: /w/a");
+
+        let expected = vec![
+            (EventLevel::Info, "Created: /w/a".to_string()),
+            (EventLevel::Warn, "Deleted: /w/a".to_string()),
+        ];
+        assert_eq!(*first.lock().unwrap(), expected);
+        assert_eq!(*second.lock().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_event_output_summary_only_emits_nothing() {
+        let lines = std::sync::Arc::default();
+        let options = MonitorOptions {
+            summary_only: true,
+            ..Default::default()
+        };
+        let mut output = EventOutput::new(&options)
+            .with_sinks(vec![Box::new(MemorySink(std::sync::Arc::clone(&lines)))]);
+
+        output.emit("Created: /w/a");
+        output.emit_at(EventLevel::Warn, "Deleted: /w/a");
+        output.emit_at(EventLevel::Error, "Failed to update target files");
+
+        assert_eq!(
+            *lines.lock().unwrap(),
+            vec![(
+                EventLevel::Error,
+                "Failed to update target files".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_file_rearm_watches_parent_until_recreated() {
+        use notify::event::{CreateKind, RemoveKind};
+        let event = |kind, path: &str| notify::Event::new(kind).add_path(PathBuf::from(path));
+        let removed = |path| event(notify::EventKind::Remove(RemoveKind::File), path);
+        let created = |path| event(notify::EventKind::Create(CreateKind::File), path);
+        let mut rearm = FileRearm::new([PathBuf::from("/w/a.txt"), PathBuf::from("/w/b.txt")]);
+
+        // Unwatched files are none of its business
+        assert!(rearm.on_event(&removed("/w/other.txt")).is_empty());
+
+        assert_eq!(
+            rearm.on_event(&removed("/w/a.txt")),
+            vec![RearmAction::WatchParent(PathBuf::from("/w"))]
+        );
+        // The parent is already watched for the second file
+        assert!(rearm.on_event(&removed("/w/b.txt")).is_empty());
+        assert!(rearm.on_event(&removed("/w/a.txt")).is_empty());
+
+        assert_eq!(
+            rearm.on_event(&created("/w/a.txt")),
+            vec![RearmAction::Rearm {
+                file: PathBuf::from("/w/a.txt"),
+                release_parent: None,
+            }]
+        );
+        assert_eq!(
+            rearm.on_event(&created("/w/b.txt")),
+            vec![RearmAction::Rearm {
+                file: PathBuf::from("/w/b.txt"),
+                release_parent: Some(PathBuf::from("/w")),
+            }]
+        );
+        // Creating a file that was never deleted changes nothing
+        assert!(rearm.on_event(&created("/w/a.txt")).is_empty());
+    }
+
+    #[test]
+    fn test_format_relative_ranges() {
+        assert_eq!(format_relative(Duration::from_millis(250)), "250ms");
+        assert_eq!(format_relative(Duration::from_millis(42_900)), "42s");
+        assert_eq!(format_relative(Duration::from_secs(187)), "3m07s");
+        assert_eq!(
+            format_relative(Duration::from_secs(2 * 3600 + 300)),
+            "2h05m"
+        );
+    }
+
+    #[test]
+    fn test_relative_clock_never_negative() {
+        let start = Instant::now();
+        let clock = RelativeClock::started_at(start + Duration::from_secs(5));
+        // Instants before the start clamp to zero instead of going negative
+        assert_eq!(clock.since_start(start), "+0ms");
+        assert_eq!(clock.since_start(start + Duration::from_secs(65)), "+1m00s");
+        assert_eq!(
+            clock.ago(start + Duration::from_secs(10), start),
+            tf("msg_time_ago", &["0ms"])
+        );
+        assert_eq!(
+            clock.ago(start, start + Duration::from_secs(2)),
+            tf("msg_time_ago", &["2s"])
+        );
+    }
+
+    #[test]
+    fn test_relative_display() {
+        let base = Path::new("/home/me/project");
+        assert_eq!(
+            relative_display(Path::new("/home/me/project/src/main.rs"), base),
+            Path::new("src").join("main.rs").display().to_string()
+        );
+        assert_eq!(relative_display(base, base), ".");
+        // Paths outside the base stay absolute
+        assert_eq!(
+            relative_display(Path::new("/home/me/projects/x"), base),
+            "/home/me/projects/x"
+        );
+        assert_eq!(
+            relative_display(Path::new("/etc/hosts"), base),
+            "/etc/hosts"
+        );
+    }
+
+    #[test]
+    fn test_event_deduper_drops_identical_event_within_window() {
+        use notify::event::{CreateKind, ModifyKind, RenameMode};
+        use notify::{Event, EventKind};
+
+        let event = |kind, paths: &[&str]| {
+            paths.iter().fold(Event::new(kind), |event, path| {
+                event.add_path(PathBuf::from(path))
+            })
+        };
+        let created = event(EventKind::Create(CreateKind::File), &["/w/a.txt"]);
+        let mut deduper = EventDeduper::new(Duration::from_millis(50));
+        let start = Instant::now();
+
+        assert!(!deduper.is_repeat(&created, start));
+        assert!(deduper.is_repeat(&created, start + Duration::from_millis(10)));
+        // Other kinds and paths are distinct events
+        let modified = event(EventKind::Modify(ModifyKind::Any), &["/w/a.txt"]);
+        assert!(!deduper.is_repeat(&modified, start + Duration::from_millis(10)));
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+        assert!(!deduper.is_repeat(&event(rename, &["/w/a", "/w/b"]), start));
+        assert!(!deduper.is_repeat(&event(rename, &["/w/b", "/w/a"]), start));
+        // Outside the window it is a new event again
+        assert!(!deduper.is_repeat(&created, start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_output_deduper_allows_repeat_after_window() {
+        let mut deduper = OutputDeduper::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        assert!(deduper.should_emit("File deleted: a", start));
+        assert!(deduper.should_emit("File deleted: a", start + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("15").unwrap(), Duration::from_secs(15));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5d").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("0s").is_err());
+        // Saturates instead of overflowing
+        assert!(parse_duration(&format!("{}h", u64::MAX)).is_ok());
+    }
+
+    fn idle_after(timeout: Duration) -> WatchTiming {
+        WatchTiming {
+            idle_timeout: Some(timeout),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_watch_loop_idle_timeout() {
+        let (_tx, rx) = std::sync::mpsc::channel::<u32>();
+        let exit = run_watch_loop(&rx, idle_after(Duration::from_millis(20)), |_| true);
+        assert_eq!(exit, WatchExit::IdleTimeout);
+    }
+
+    #[test]
+    fn test_run_watch_loop_ignored_items_do_not_reset_timeout() {
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+        let sender = std::thread::spawn(move || {
+            for i in 0..100 {
+                if tx.send(i).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let mut seen = 0;
+        let exit = run_watch_loop(&rx, idle_after(Duration::from_millis(50)), |_| {
+            seen += 1;
+            false
+        });
+        assert_eq!(exit, WatchExit::IdleTimeout);
+        assert!(seen < 100);
+        drop(rx);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_watch_loop_max_runtime_despite_activity() {
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+        let sender = std::thread::spawn(move || {
+            for i in 0..200 {
+                if tx.send(i).is_err() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        // Every item is activity, so only the runtime limit can end the loop
+        let timing = WatchTiming {
+            idle_timeout: Some(Duration::from_millis(50)),
+            max_runtime: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let exit = run_watch_loop(&rx, timing, |_| true);
+        assert_eq!(exit, WatchExit::MaxRuntime);
+        assert!(started.elapsed() < Duration::from_millis(900));
+        drop(rx);
+        sender.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_watch_loop_disconnected() {
+        let (tx, rx) = std::sync::mpsc::channel::<u32>();
+        tx.send(1).unwrap();
+        drop(tx);
+
+        let mut received = Vec::new();
+        let exit = run_watch_loop(&rx, WatchTiming::default(), |input| {
+            if let WatchInput::Item(item) = input {
+                received.push(item);
+            }
+            true
+        });
+        assert_eq!(exit, WatchExit::Disconnected);
+        assert_eq!(received, vec![1]);
+    }
+
+    #[test]
+    fn test_run_watch_loop_reports_stats() {
+        let (_tx, rx) = std::sync::mpsc::channel::<u32>();
+        let timing = WatchTiming {
+            idle_timeout: Some(Duration::from_millis(100)),
+            stats_interval: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let mut reports = 0;
+        run_watch_loop(&rx, timing, |input| {
+            if let WatchInput::StatsDue = input {
+                reports += 1;
+            }
+            false
+        });
+        assert!(reports >= 2, "expected periodic stats, got {}", reports);
+    }
+
+    #[test]
+    fn test_run_watch_loop_aggregate_windows() {
+        let (_tx, rx) = std::sync::mpsc::channel::<u32>();
+        let timing = WatchTiming {
+            idle_timeout: Some(Duration::from_millis(100)),
+            aggregate_interval: Some(Duration::from_millis(20)),
+            ..Default::default()
+        };
+
+        let mut windows = 0;
+        let exit = run_watch_loop_with(&rx, timing, &mut ManualClock(Instant::now()), |input| {
+            if let WatchInput::AggregateDue = input {
+                windows += 1;
+            }
+            false
+        });
+        assert_eq!(exit, WatchExit::IdleTimeout);
+        // One window every 20ms up to the idle timeout at 100ms
+        assert_eq!(windows, 5);
+    }
+
+    /// Virtual time that jumps to the next wake-up whenever the channel is empty
+    struct ManualClock(Instant);
+
+    impl WatchClock for ManualClock {
+        fn now(&mut self) -> Instant {
+            self.0
+        }
+
+        fn recv_until<T>(
+            &mut self,
+            rx: &Receiver<T>,
+            until: Option<Instant>,
+        ) -> Result<T, RecvTimeoutError> {
+            match rx.try_recv() {
+                Ok(item) => Ok(item),
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    Err(RecvTimeoutError::Disconnected)
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => {
+                    // Nothing can arrive without a wake-up, so the clock would stop
+                    let until = until.ok_or(RecvTimeoutError::Disconnected)?;
+                    self.0 = self.0.max(until);
+                    Err(RecvTimeoutError::Timeout)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_event_aggregator_groups_by_root_and_kind() {
+        use notify::EventKind;
+        use notify::event::{CreateKind, DataChange, ModifyKind, RemoveKind, RenameMode};
+        let event = |kind, path: &str| notify::Event::new(kind).add_path(PathBuf::from(path));
+        let created = EventKind::Create(CreateKind::File);
+        let modified = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+
+        let mut aggregator = EventAggregator::new(&["/proj".to_string(), "/proj/docs".to_string()]);
+        for name in ["a", "b", "c", "d", "e"] {
+            aggregator.record(&event(created, &format!("/proj/src/{}.rs", name)));
+        }
+        aggregator.record(&event(modified, "/proj/src/a.rs"));
+        aggregator.record(&event(modified, "/proj/src/b.rs"));
+        aggregator.record(&event(EventKind::Remove(RemoveKind::File), "/proj/old.rs"));
+        // The innermost root wins
+        aggregator.record(&event(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            "/proj/docs/x.md",
+        ));
+        // Outside every root, grouped by directory
+        aggregator.record(&event(created, "/elsewhere/f.txt"));
+
+        assert_eq!(
+            aggregator.take_groups(),
+            vec![
+                ("/elsewhere".to_string(), vec![(AggregateKind::Created, 1)]),
+                (
+                    "/proj".to_string(),
+                    vec![
+                        (AggregateKind::Created, 5),
+                        (AggregateKind::Modified, 2),
+                        (AggregateKind::Deleted, 1),
+                    ]
+                ),
+                ("/proj/docs".to_string(), vec![(AggregateKind::Renamed, 1)]),
+            ]
+        );
+        // Taking the groups starts a new window
+        assert!(aggregator.take_groups().is_empty());
+        assert!(aggregator.take_report().is_empty());
+
+        aggregator.record(&event(created, "/proj/n.rs"));
+        assert_eq!(
+            aggregator.take_report(),
+            vec![tf(
+                "msg_aggregate_line",
+                &[
+                    &format_list(&[tf("msg_aggregate_created", &["1"])]),
+                    "/proj"
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_event_aggregator_counts_a_paired_rename_once() {
+        use notify::EventKind;
+        use notify::event::{ModifyKind, RenameMode};
+        let rename = |mode, paths: &[&str], tracker: Option<usize>| {
+            let event = paths.iter().fold(
+                notify::Event::new(EventKind::Modify(ModifyKind::Name(mode))),
+                |event, path| event.add_path(PathBuf::from(path)),
+            );
+            match tracker {
+                Some(tracker) => event.set_tracker(tracker),
+                None => event,
+            }
+        };
+
+        let mut aggregator = EventAggregator::new(&["/proj".to_string()]);
+        // inotify reports From, To and Both sharing one tracker
+        aggregator.record(&rename(RenameMode::From, &["/proj/a"], Some(1)));
+        aggregator.record(&rename(RenameMode::To, &["/proj/b"], Some(1)));
+        aggregator.record(&rename(RenameMode::Both, &["/proj/a", "/proj/b"], Some(1)));
+        // Without trackers a To completes the From before it
+        aggregator.record(&rename(RenameMode::From, &["/proj/c"], None));
+        aggregator.record(&rename(RenameMode::To, &["/proj/d"], None));
+        // A file moved in and one moved out each count on their own
+        aggregator.record(&rename(RenameMode::To, &["/proj/e"], Some(2)));
+        aggregator.record(&rename(RenameMode::From, &["/proj/f"], Some(3)));
+
+        assert_eq!(
+            aggregator.take_groups(),
+            vec![("/proj".to_string(), vec![(AggregateKind::Renamed, 4)])]
+        );
+    }
+
+    #[test]
+    fn test_interval_timer_poll() {
+        let start = Instant::now();
+        let mut timer = IntervalTimer::new(Duration::from_secs(10), start);
+
+        assert!(!timer.poll(start + Duration::from_secs(5)));
+        assert!(timer.poll(start + Duration::from_secs(10)));
+        assert!(!timer.poll(start + Duration::from_secs(15)));
+        // A long stall fires once and re-arms past `now`
+        assert!(timer.poll(start + Duration::from_secs(45)));
+        assert_eq!(timer.next_due(), Some(start + Duration::from_secs(50)));
+    }
+
+    #[test]
+    fn test_interval_timer_out_of_range_never_fires() {
+        let start = Instant::now();
+        for interval in [Duration::ZERO, Duration::MAX] {
+            let mut timer = IntervalTimer::new(interval, start);
+            assert_eq!(timer.next_due(), None);
+            assert!(!timer.poll(start + Duration::from_secs(3600)));
+        }
+
+        let mut timer = IntervalTimer::new(Duration::from_secs(u64::MAX / 2), start);
+        assert!(!timer.poll(start));
+    }
+
+    #[test]
+    fn test_run_watch_loop_with_huge_timeouts() {
+        let (tx, rx) = std::sync::mpsc::channel::<()>();
+        drop(tx);
+        let timing = WatchTiming {
+            idle_timeout: Some(Duration::MAX),
+            stats_interval: Some(Duration::MAX),
+            max_runtime: Some(Duration::MAX),
+            ..Default::default()
+        };
+        assert_eq!(
+            run_watch_loop(&rx, timing, |_| true),
+            WatchExit::Disconnected
+        );
+    }
+
+    #[test]
+    fn test_event_counters_summary() {
+        let mut counters = EventCounters::default();
+        assert_eq!(counters.kind_summary(), "-");
+
+        counters.record("Modified");
+        counters.record("Created");
+        counters.record("Modified");
+        counters.record_ignored();
+
+        assert_eq!(counters.seen, 4);
+        assert_eq!(counters.ignored, 1);
+        assert_eq!(counters.kind_summary(), "Created 1, Modified 2");
+    }
+
+    #[test]
+    fn test_rename_tracker_combines_from_and_to() {
+        let mut tracker = RenameTracker::new(RenameDisplay::Combined, RENAME_PAIR_WINDOW);
+        let now = Instant::now();
+
+        let first = tracker.classify(RenamePhase::From(Path::new("a.txt")), now);
+        assert!(first.is_empty());
+
+        let second = tracker.classify(
+            RenamePhase::To(Path::new("b.txt")),
+            now + Duration::from_millis(10),
+        );
+        assert_eq!(
+            second,
+            vec![RenameReport::Renamed {
+                from: PathBuf::from("a.txt"),
+                to: PathBuf::from("b.txt"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rename_tracker_drops_both_repeating_a_completed_pair() {
+        let mut tracker = RenameTracker::new(RenameDisplay::Combined, RENAME_PAIR_WINDOW);
+        let now = Instant::now();
+        let (a, b) = (Path::new("a.txt"), Path::new("b.txt"));
+
+        let mut reports = tracker.classify(RenamePhase::From(a), now);
+        reports.extend(tracker.classify(RenamePhase::To(b), now));
+        reports.extend(tracker.classify(RenamePhase::Both(a, b), now));
+        assert_eq!(
+            reports,
+            vec![RenameReport::Renamed {
+                from: PathBuf::from("a.txt"),
+                to: PathBuf::from("b.txt"),
+            }]
+        );
+
+        // A later `Both` for the same paths is a new move
+        assert_eq!(tracker.classify(RenamePhase::Both(a, b), now).len(), 1);
+    }
+
+    #[test]
+    fn test_rename_tracker_combined_unmatched_from() {
+        let mut tracker = RenameTracker::new(RenameDisplay::Combined, RENAME_PAIR_WINDOW);
+        let now = Instant::now();
+
+        tracker.classify(RenamePhase::From(Path::new("a.txt")), now);
+        let reports = tracker.classify(
+            RenamePhase::To(Path::new("b.txt")),
+            now + Duration::from_secs(1),
+        );
+        assert_eq!(
+            reports,
+            vec![
+                RenameReport::MovedOut(PathBuf::from("a.txt")),
+                RenameReport::Completed(PathBuf::from("b.txt")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_tracker_expires_from_without_to() {
+        let mut tracker = RenameTracker::new(RenameDisplay::Combined, RENAME_PAIR_WINDOW);
+        let now = Instant::now();
+
+        tracker.classify(RenamePhase::From(Path::new("a.txt")), now);
+        assert!(tracker.expire(now + RENAME_PAIR_WINDOW).is_empty());
+        assert_eq!(
+            tracker.expire(now + RENAME_PAIR_WINDOW * 2),
+            vec![RenameReport::MovedOut(PathBuf::from("a.txt"))]
+        );
+
+        // The expired entry is gone, so a later `To` does not pair with it
+        assert!(tracker.expire(now + RENAME_PAIR_WINDOW * 3).is_empty());
+        assert_eq!(
+            tracker.classify(
+                RenamePhase::To(Path::new("b.txt")),
+                now + RENAME_PAIR_WINDOW * 3
+            ),
+            vec![RenameReport::Completed(PathBuf::from("b.txt"))]
+        );
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_rename_event_reaches_display_and_sync() {
+        let temp_dir = TempDir::new().unwrap();
+        let watch_dir = temp_dir.path().join("watch");
+        fs::create_dir_all(&watch_dir).unwrap();
+        let old = watch_dir.join("a.txt");
+        let new = watch_dir.join("b.txt");
+        fs::write(&new, "").unwrap();
+        let target = temp_dir.path().join("targets.json");
+        fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+        let config = Config {
+            watch_paths: vec![watch_dir.display().to_string()],
+            target_files: vec![target.display().to_string()],
+            ..Default::default()
+        };
+        let options = MonitorOptions::default();
+
+        let lines = std::sync::Arc::default();
+        let mut output = EventOutput::new(&options)
+            .with_sinks(vec![Box::new(MemorySink(std::sync::Arc::clone(&lines)))]);
+        let mut tracker = RenameTracker::new(RenameDisplay::Combined, RENAME_PAIR_WINDOW);
+        let mut printer = RenamePrinter::new(RenameDisplay::Combined);
+        let mut sync_tracker = RenameTracker::pairing(RENAME_PAIR_WINDOW);
+        /// Counts the moves handed to the real sync
+        struct CountingSync<'a>(RenameSync<'a>, usize);
+        impl RenameConsumer for CountingSync<'_> {
+            fn on_rename(&mut self, report: &RenameReport, output: &mut EventOutput) {
+                if matches!(report, RenameReport::Renamed { .. }) {
+                    self.1 += 1;
+                }
+                self.0.on_rename(report, output);
+            }
+        }
+        let mut sync = CountingSync(
+            RenameSync {
+                config: &config,
+                options: &options,
+            },
+            0,
+        );
+        let rename = |mode, paths: &[&PathBuf]| {
+            paths.iter().fold(
+                notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Name(
+                    mode,
+                ))),
+                |event, path| event.add_path(path.to_path_buf()),
+            )
+        };
+        // What inotify sends for one move
+        let events = [
+            rename(notify::event::RenameMode::From, &[&old]),
+            rename(notify::event::RenameMode::To, &[&new]),
+            rename(notify::event::RenameMode::Both, &[&old, &new]),
+        ];
+
+        // The journal and state file of the sync go to a scratch config directory
+        unsafe { std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config")) };
+        let now = Instant::now();
+        for event in &events {
+            let shown = dispatch_rename(event, &mut tracker, now, &mut output, &mut [&mut printer]);
+            let synced =
+                dispatch_rename(event, &mut sync_tracker, now, &mut output, &mut [&mut sync]);
+            assert!(shown && synced);
+        }
+        unsafe { std::env::remove_var("XDG_CONFIG_HOME") };
+
+        // One move is synced once
+        assert_eq!(sync.1, 1);
+        let content = fs::read_to_string(&target).unwrap();
+        assert!(content.contains("b.txt"), "{}", content);
+        assert!(!content.contains("a.txt"), "{}", content);
+        // The rename line from the display, then the sync's update line
+        let logged = |lines: &std::sync::Arc<std::sync::Mutex<Vec<(EventLevel, String)>>>| {
+            lines
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, line)| line.clone())
+                .collect::<Vec<_>>()
+        };
+        let first = logged(&lines);
+        // Followed by the summary: the target file, then its rewritten entry
+        assert_eq!(first.len(), 4, "{:?}", first);
+        let updated = tf(
+            "msg_target_files_updated",
+            &[&old.display().to_string(), &new.display().to_string()],
+        );
+        assert!(first[1].contains(&updated), "{:?}", first);
+        assert!(
+            first[2].contains(&target.display().to_string()),
+            "{:?}",
+            first
+        );
+        assert!(first[3].contains(" -> "), "{:?}", first);
+        let state = temp_dir
+            .path()
+            .join("config")
+            .join("chaser")
+            .join("state.yaml");
+        assert!(state.exists());
+
+        // A rename of an untracked path rewrites nothing, so it is neither
+        // reported as an update nor recorded
+        fs::remove_file(&state).unwrap();
+        let untracked = notify::Event::new(notify::EventKind::Modify(
+            notify::event::ModifyKind::Name(notify::event::RenameMode::Both),
+        ))
+        .add_path(watch_dir.join("c.txt"))
+        .add_path(watch_dir.join("d.txt"));
+        unsafe { std::env::set_var("XDG_CONFIG_HOME", temp_dir.path().join("config")) };
+        dispatch_rename(
+            &untracked,
+            &mut sync_tracker,
+            Instant::now(),
+            &mut output,
+            &mut [&mut sync],
+        );
+        unsafe { std::env::remove_var("XDG_CONFIG_HOME") };
+        assert_eq!(logged(&lines), first);
+        assert!(!state.exists());
+
+        // Events that are not renames reach neither consumer
+        let created =
+            notify::Event::new(notify::EventKind::Create(notify::event::CreateKind::File))
+                .add_path(watch_dir.join("c.txt"));
+        assert!(!dispatch_rename(
+            &created,
+            &mut tracker,
+            Instant::now(),
+            &mut output,
+            &mut [&mut printer, &mut sync],
+        ));
+        assert_eq!(logged(&lines), first);
+    }
+
+    #[test]
+    fn test_rename_tracker_verbose_keeps_phases() {
+        let mut tracker = RenameTracker::new(RenameDisplay::Verbose, RENAME_PAIR_WINDOW);
+        let now = Instant::now();
+
+        assert_eq!(
+            tracker.classify(RenamePhase::From(Path::new("a.txt")), now),
+            vec![RenameReport::Started(PathBuf::from("a.txt"))]
+        );
+        assert_eq!(
+            tracker.classify(RenamePhase::To(Path::new("b.txt")), now),
+            vec![RenameReport::Completed(PathBuf::from("b.txt"))]
+        );
+    }
+
+    #[test]
+    fn test_effective_watch_paths() {
+        let current_dir = Path::new("/work/project");
+
+        // Empty config without --here has nothing to watch
+        assert!(effective_watch_paths(&[], false, current_dir).is_empty());
+
+        // Empty config with --here watches the current directory
+        assert_eq!(
+            effective_watch_paths(&[], true, current_dir),
+            vec!["/work/project".to_string()]
+        );
+
+        // --here takes precedence over configured paths for the session
+        let configured = vec!["/other".to_string()];
+        assert_eq!(
+            effective_watch_paths(&configured, true, current_dir),
+            vec!["/work/project".to_string()]
+        );
+        assert_eq!(
+            effective_watch_paths(&configured, false, current_dir),
+            configured
+        );
+    }
+
+    #[test]
+    fn test_dedupe_watch_paths_different_spellings() {
+        let temp_dir = TempDir::new().unwrap();
+        let watch_dir = temp_dir.path().join("watch");
+        fs::create_dir_all(watch_dir.join("sub")).unwrap();
+
+        let plain = watch_dir.to_string_lossy().to_string();
+        let roundabout = watch_dir
+            .join("sub")
+            .join("..")
+            .to_string_lossy()
+            .to_string();
+
+        let deduped = dedupe_watch_paths(&[plain.clone(), roundabout.clone()]);
+        assert_eq!(deduped.paths, vec![plain.clone()]);
+        assert_eq!(deduped.collapsed, vec![(roundabout, plain)]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dedupe_watch_paths_symlink() {
+        let temp_dir = TempDir::new().unwrap();
+        let real_dir = temp_dir.path().join("real");
+        let link_dir = temp_dir.path().join("link");
+        fs::create_dir_all(&real_dir).unwrap();
+        std::os::unix::fs::symlink(&real_dir, &link_dir).unwrap();
+
+        let deduped = dedupe_watch_paths(&[
+            real_dir.to_string_lossy().to_string(),
+            link_dir.to_string_lossy().to_string(),
+        ]);
+        assert_eq!(deduped.paths.len(), 1);
+        assert_eq!(deduped.collapsed.len(), 1);
+    }
+
+    #[test]
+    fn test_dedupe_watch_paths_keeps_distinct() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a");
+        let b = temp_dir.path().join("b");
+        fs::create_dir_all(&a).unwrap();
+        fs::create_dir_all(&b).unwrap();
+
+        let paths = vec![
+            a.to_string_lossy().to_string(),
+            b.to_string_lossy().to_string(),
+            a.to_string_lossy().to_string(),
+            "/does/not/exist".to_string(),
+        ];
+        let deduped = dedupe_watch_paths(&paths);
+        assert_eq!(deduped.paths.len(), 3);
+        assert_eq!(deduped.collapsed.len(), 1);
+    }
+
+    #[test]
+    fn test_should_watch_parent_per_path_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "a").unwrap();
+
+        assert!(should_watch_parent(&file, true));
+        assert!(!should_watch_parent(&file, false));
+        assert!(!should_watch_parent(temp_dir.path(), true));
+        assert!(!should_watch_parent(&temp_dir.path().join("missing"), true));
+    }
+
+    #[test]
+    fn test_target_file_watch_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let watched = temp_dir.path().join("watched");
+        let nested = watched.join("nested");
+        let other = temp_dir.path().join("other");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let target = |dir: &Path, name: &str| dir.join(name).to_string_lossy().to_string();
+        let targets = vec![
+            target(&watched, "a.json"),
+            target(&nested, "b.json"),
+            target(&other, "c.json"),
+            target(&other, "d.yaml"),
+        ];
+        let watch_paths = vec![watched.to_string_lossy().to_string()];
+
+        let other = other.canonicalize().unwrap();
+        assert_eq!(
+            target_file_watch_dirs(&targets, &watch_paths, true),
+            vec![other.clone()]
+        );
+        assert_eq!(
+            target_file_watch_dirs(&targets, &watch_paths, false),
+            vec![nested.canonicalize().unwrap(), other]
+        );
+    }
+
+    #[test]
+    fn test_new_subdirs_to_watch() {
+        use notify::event::{CreateKind, ModifyKind, RenameMode};
+        use notify::{Event, EventKind};
+
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("sub");
+        let file = temp_dir.path().join("file.txt");
+        fs::create_dir(&dir).unwrap();
+        fs::write(&file, "").unwrap();
+        let event = |kind, path: &Path| Event::new(kind).add_path(path.to_path_buf());
+
+        let folder = event(EventKind::Create(CreateKind::Folder), &dir);
+        assert_eq!(
+            new_subdirs_to_watch(&folder, false, true),
+            vec![dir.clone()]
+        );
+        // Only non-recursive watches with the option enabled need it
+        assert!(new_subdirs_to_watch(&folder, true, true).is_empty());
+        assert!(new_subdirs_to_watch(&folder, false, false).is_empty());
+
+        let untyped = event(EventKind::Create(CreateKind::Any), &dir);
+        assert_eq!(
+            new_subdirs_to_watch(&untyped, false, true),
+            vec![dir.clone()]
+        );
+        let untyped_file = event(EventKind::Create(CreateKind::Any), &file);
+        assert!(new_subdirs_to_watch(&untyped_file, false, true).is_empty());
+        let modified = event(EventKind::Modify(ModifyKind::Any), &dir);
+        assert!(new_subdirs_to_watch(&modified, false, true).is_empty());
+
+        // Directories moved in need a watch as well
+        let moved_in = event(EventKind::Modify(ModifyKind::Name(RenameMode::To)), &dir);
+        assert_eq!(
+            new_subdirs_to_watch(&moved_in, false, true),
+            vec![dir.clone()]
+        );
+        let renamed = Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+            .add_path(temp_dir.path().join("old"))
+            .add_path(dir.clone());
+        assert_eq!(
+            new_subdirs_to_watch(&renamed, false, true),
+            vec![dir.clone()]
+        );
+        let moved_file = event(EventKind::Modify(ModifyKind::Name(RenameMode::To)), &file);
+        assert!(new_subdirs_to_watch(&moved_file, false, true).is_empty());
+    }
+
+    #[test]
+    fn test_parent_rename_watches_dedupes_with_watched_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let covered_dir = temp_dir.path().join("covered");
+        let nested_dir = covered_dir.join("nested");
+        let lone_dir = temp_dir.path().join("lone");
+        fs::create_dir_all(&nested_dir).unwrap();
+        fs::create_dir_all(&lone_dir).unwrap();
+        for file in [
+            "covered/a.txt",
+            "covered/nested/b.txt",
+            "lone/c.txt",
+            "lone/d.txt",
+        ] {
+            fs::write(temp_dir.path().join(file), "x").unwrap();
+        }
+
+        let path = |rel: &str| temp_dir.path().join(rel).to_string_lossy().to_string();
+        let paths = vec![
+            covered_dir.to_string_lossy().to_string(),
+            path("covered/a.txt"),
+            path("covered/nested/b.txt"),
+            path("lone/c.txt"),
+            path("lone/d.txt"),
+        ];
+
+        assert_eq!(
+            parent_rename_watches(&paths, true, true),
+            vec![lone_dir.clone()]
+        );
+        assert_eq!(
+            parent_rename_watches(&paths, true, false),
+            vec![nested_dir, lone_dir]
+        );
+        assert!(parent_rename_watches(&paths, false, true).is_empty());
+    }
+
+    #[test]
+    fn test_event_path_lines_truncates() {
+        let paths: Vec<PathBuf> = (0..10).map(|i| PathBuf::from(format!("/w/{i}"))).collect();
+        let line = |path: &Path| path.display().to_string();
+
+        let lines = event_path_lines(&paths, Some(3), line);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[..3], ["/w/0", "/w/1", "/w/2"]);
+        assert!(lines[3].contains('7'));
+
+        assert_eq!(event_path_lines(&paths, None, line).len(), 10);
+        assert_eq!(event_path_lines(&paths[..2], Some(3), line).len(), 2);
+    }
+
+    #[test]
+    fn test_matches_grep() {
+        let event =
+            |path: &str| notify::Event::new(notify::EventKind::Any).add_path(PathBuf::from(path));
+        let regex = Regex::new(r"\.rs$").unwrap();
+
+        let shown: Vec<&str> = ["/w/main.rs", "/w/notes.md", "/w/rs/data.json", "/w/lib.rs"]
+            .into_iter()
+            .filter(|path| matches_grep(&event(path), Some(&regex)))
+            .collect();
+        assert_eq!(shown, vec!["/w/main.rs", "/w/lib.rs"]);
+        assert!(matches_grep(&event("/w/notes.md"), None));
+    }
+
+    #[test]
+    fn test_format_trace_shows_kind_and_paths() {
+        let event = notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Name(
+            notify::event::RenameMode::Both,
+        )))
+        .add_path(PathBuf::from("/w/old.txt"))
+        .add_path(PathBuf::from("/w/new.txt"));
+
+        let trace = format_trace(&event);
+        assert!(trace.starts_with("[trace]"));
+        assert!(trace.contains("Modify") && trace.contains("Both"));
+        assert!(trace.contains("/w/old.txt") && trace.contains("/w/new.txt"));
+    }
+
+    #[test]
+    fn test_touches_watch_paths() {
+        let event =
+            |path: &str| notify::Event::new(notify::EventKind::Any).add_path(PathBuf::from(path));
+        let paths = vec!["/w/file.txt".to_string(), "/dir".to_string()];
+
+        assert!(touches_watch_paths(&event("/w/file.txt"), &paths));
+        assert!(touches_watch_paths(&event("/dir/inner/x"), &paths));
+        assert!(!touches_watch_paths(&event("/w/sibling.txt"), &paths));
+        assert!(!touches_watch_paths(&event("/w/file.txt.bak"), &paths));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::path_sync::{PathStatus, PathSyncManager};
+use anyhow::{Context, Result, anyhow};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// How long a client may take to send its request line
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The `/status` body: every tracked path, sorted by path
+pub fn status_json(mut statuses: Vec<PathStatus>) -> Result<String> {
+    statuses.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(serde_json::to_string_pretty(&statuses)?)
+}
+
+/// HTTP status code, content type and body answering `method` and `path`
+///
+/// `status` builds the `/status` body and is only called for that route. A
+/// query string does not change the route.
+pub fn route(
+    method: &str,
+    path: &str,
+    status: impl FnOnce() -> Result<String>,
+) -> (u16, &'static str, String) {
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    match (method, path) {
+        ("GET", "/healthz") => (200, "text/plain", "ok\n".to_string()),
+        ("GET", "/status") => match status() {
+            Ok(body) => (200, "application/json", body),
+            Err(e) => (500, "text/plain", format!("{:#}\n", e)),
+        },
+        (_, "/healthz" | "/status") => (405, "text/plain", "method not allowed\n".to_string()),
+        _ => (404, "text/plain", "not found\n".to_string()),
+    }
+}
+
+/// Serve `/status` and `/healthz` on `addr` from a background thread,
+/// returning the address actually bound
+///
+/// Target files are reloaded for every `/status` request, since the monitor
+/// rewrites them through managers of its own.
+pub fn spawn_status_server(
+    addr: SocketAddr,
+    manager: Arc<Mutex<PathSyncManager>>,
+) -> Result<SocketAddr> {
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+    let bound = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // A slow client must not hold up the others
+            let manager = Arc::clone(&manager);
+            thread::spawn(move || {
+                // A client that hangs up early is not the server's problem
+                let _ = handle_connection(stream, &manager);
+            });
+        }
+    });
+    Ok(bound)
+}
+
+fn handle_connection(stream: TcpStream, manager: &Mutex<PathSyncManager>) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (code, content_type, body) = route(method, path, || {
+        let mut manager = manager
+            .lock()
+            .map_err(|_| anyhow!("status is unavailable after a failed request"))?;
+        manager.refresh_with_report();
+        status_json(manager.get_path_status(true))
+    });
+    let reason = match code {
+        200 => "OK",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        &stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(path: &str, exists: bool) -> PathStatus {
+        PathStatus {
+            path: path.to_string(),
+            exists,
+            target_files: vec!["targets.json".to_string()],
+            last_known_path: None,
+        }
+    }
+
+    #[test]
+    fn test_status_json_is_valid_and_sorted() {
+        let json = status_json(vec![status("./b \"quoted\"", false), status("./a", true)]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["path"], "./a");
+        assert_eq!(value[0]["exists"], true);
+        assert_eq!(value[1]["path"], "./b \"quoted\"");
+        assert_eq!(value[1]["target_files"][0], "targets.json");
+        assert!(value[1]["last_known_path"].is_null());
+
+        assert_eq!(status_json(Vec::new()).unwrap(), "[]");
+    }
+
+    #[test]
+    fn test_route() {
+        let unused = || -> Result<String> { panic!("status built for another route") };
+        assert_eq!(route("GET", "/healthz", unused).0, 200);
+        assert_eq!(route("POST", "/healthz", unused).0, 405);
+        assert_eq!(route("GET", "/", unused).0, 404);
+        assert_eq!(route("GET", "/healthz?probe=1", unused).0, 200);
+
+        let (code, content_type, body) = route("GET", "/status", || Ok("[]".to_string()));
+        assert_eq!(
+            (code, content_type, body.as_str()),
+            (200, "application/json", "[]")
+        );
+        let failed = route("GET", "/status", || Err(anyhow::anyhow!("boom")));
+        assert_eq!(failed.0, 500);
+        assert_eq!(route("GET", "/status?x", || Ok("[]".to_string())).0, 200);
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_server_answers_despite_idle_client_and_poisoned_lock() {
+        let manager = Arc::new(Mutex::new(
+            PathSyncManager::new(Vec::new(), Vec::new()).unwrap(),
+        ));
+        let addr =
+            spawn_status_server("127.0.0.1:0".parse().unwrap(), Arc::clone(&manager)).unwrap();
+
+        // A connection that never sends its request does not block the next one
+        let _idle = TcpStream::connect(addr).unwrap();
+        assert!(get(addr, "/healthz").starts_with("HTTP/1.1 200"));
+        assert!(get(addr, "/status?pretty").starts_with("HTTP/1.1 200"));
+
+        let poisoner = Arc::clone(&manager);
+        let _ = thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(get(addr, "/status").starts_with("HTTP/1.1 500"));
+    }
+}
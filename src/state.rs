@@ -0,0 +1,340 @@
+use crate::config::{Config, active_profile};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Last observed state of one tracked path
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PathState {
+    pub exists: bool,
+    /// Modification time in milliseconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime_ms: Option<u64>,
+}
+
+impl PathState {
+    /// Stat `path` on disk
+    pub fn capture(path: &str) -> Self {
+        let metadata = fs::metadata(path).ok();
+        let mtime_ms = metadata
+            .as_ref()
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64);
+
+        Self {
+            exists: metadata.is_some(),
+            mtime_ms,
+        }
+    }
+}
+
+/// Tracked paths of one target file, keyed by path as written in the file
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct TargetFileState {
+    #[serde(default)]
+    pub paths: BTreeMap<String, PathState>,
+}
+
+impl TargetFileState {
+    /// Stat every path currently tracked by a target file
+    pub fn capture<'a>(paths: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            paths: paths
+                .into_iter()
+                .map(|path| (path.to_string(), PathState::capture(path)))
+                .collect(),
+        }
+    }
+}
+
+/// A difference between the stored state and what is found now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathDrift {
+    /// The target file lists a path it did not list before
+    Added(String),
+    /// The target file no longer lists a path it listed before
+    Removed(String),
+    /// A tracked path exists now but was missing before
+    Appeared(String),
+    /// A tracked path was present before but is missing now
+    Disappeared(String),
+    /// A tracked path's modification time changed
+    Modified(String),
+}
+
+/// `name` next to the config file, prefixed with the active profile if any
+fn sidecar_file_path(name: &str) -> Result<PathBuf> {
+    // Resolving the config path also creates the config directory
+    Config::config_file_path()?;
+    let file_name = match active_profile() {
+        Some(profile) => format!("{}.{}", profile, name),
+        None => name.to_string(),
+    };
+    Ok(Config::app_config_dir()?.join(file_name))
+}
+
+/// Sidecar file remembering tracked paths between runs, keyed by target file
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct StateStore {
+    #[serde(default)]
+    pub targets: BTreeMap<String, TargetFileState>,
+}
+
+impl StateStore {
+    /// Location of the state file, next to the config file of the active profile
+    pub fn state_file_path() -> Result<PathBuf> {
+        sidecar_file_path("state.yaml")
+    }
+
+    /// Load the state file, or an empty store if there is none yet
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::state_file_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path).context("Failed to read state file")?;
+        serde_yaml_ng::from_str(&content).context("Failed to parse state file")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::state_file_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let content = serde_yaml_ng::to_string(self).context("Failed to serialize state")?;
+        crate::write_mode::write_file(path, content).context("Failed to write state file")
+    }
+
+    /// Replace the stored state for `target`
+    pub fn record(&mut self, target: &str, state: TargetFileState) {
+        self.targets.insert(target.to_string(), state);
+    }
+
+    /// Compare the stored state for `target` against `current`
+    ///
+    /// A target file with no stored state has nothing to compare against and
+    /// reports no drift.
+    pub fn drift(&self, target: &str, current: &TargetFileState) -> Vec<PathDrift> {
+        let Some(previous) = self.targets.get(target) else {
+            return Vec::new();
+        };
+
+        let mut drift = Vec::new();
+        for (path, now) in &current.paths {
+            match previous.paths.get(path) {
+                None => drift.push(PathDrift::Added(path.clone())),
+                Some(before) if before.exists && !now.exists => {
+                    drift.push(PathDrift::Disappeared(path.clone()))
+                }
+                Some(before) if !before.exists && now.exists => {
+                    drift.push(PathDrift::Appeared(path.clone()))
+                }
+                Some(before) if before.mtime_ms != now.mtime_ms => {
+                    drift.push(PathDrift::Modified(path.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for path in previous.paths.keys() {
+            if !current.paths.contains_key(path) {
+                drift.push(PathDrift::Removed(path.clone()));
+            }
+        }
+
+        drift
+    }
+}
+
+/// One path rewrite of a pending transaction
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    pub from: String,
+    pub to: String,
+}
+
+/// The target file rewrites of one sync, recorded before they are applied so
+/// a sync interrupted partway can be completed on the next run
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Transaction {
+    pub old_path: String,
+    pub new_path: String,
+    /// Rewrites not applied yet, keyed by target file path
+    #[serde(default)]
+    pub pending: BTreeMap<String, Vec<Rewrite>>,
+}
+
+impl Transaction {
+    /// Location of the transaction journal, next to the state file
+    pub fn journal_file_path() -> Result<PathBuf> {
+        sidecar_file_path("journal.yaml")
+    }
+
+    /// Load the unfinished transaction, if there is one
+    pub fn load_from(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path).context("Failed to read transaction journal")?;
+        serde_yaml_ng::from_str(&content)
+            .map(Some)
+            .context("Failed to parse transaction journal")
+    }
+
+    /// Record the transaction, or remove the journal once nothing is pending
+    ///
+    /// The journal is left alone in no-write mode.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if crate::write_mode::is_no_write() {
+            return Ok(());
+        }
+        if self.pending.is_empty() {
+            if path.exists() {
+                fs::remove_file(path).context("Failed to remove transaction journal")?;
+            }
+            return Ok(());
+        }
+        let content = serde_yaml_ng::to_string(self).context("Failed to serialize transaction")?;
+        crate::write_mode::write_file(path, content).context("Failed to write transaction journal")
+    }
+
+    /// Mark the rewrites of `target` as applied
+    pub fn complete(&mut self, target: &str) {
+        self.pending.remove(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn path_str(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let tracked = temp_dir.path().join("a.txt");
+        fs::write(&tracked, "a").unwrap();
+        let tracked = path_str(&tracked);
+
+        let mut store = StateStore::default();
+        store.record("targets.json", TargetFileState::capture([tracked.as_str()]));
+
+        let state_file = temp_dir.path().join("state.yaml");
+        store.save_to(&state_file).unwrap();
+
+        let loaded = StateStore::load_from(&state_file).unwrap();
+        assert_eq!(loaded, store);
+        assert!(loaded.targets["targets.json"].paths[&tracked].exists);
+    }
+
+    #[test]
+    fn test_transaction_journal_is_removed_when_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = temp_dir.path().join("journal.yaml");
+        let rewrite = || {
+            vec![Rewrite {
+                from: "./old".to_string(),
+                to: "./new".to_string(),
+            }]
+        };
+        let mut transaction = Transaction {
+            old_path: "./old".to_string(),
+            new_path: "./new".to_string(),
+            pending: BTreeMap::from([
+                ("a.json".to_string(), rewrite()),
+                ("b.json".to_string(), rewrite()),
+            ]),
+        };
+        transaction.save_to(&journal).unwrap();
+
+        transaction.complete("a.json");
+        transaction.save_to(&journal).unwrap();
+        let loaded = Transaction::load_from(&journal).unwrap().unwrap();
+        assert_eq!(loaded.pending.keys().collect::<Vec<_>>(), vec!["b.json"]);
+
+        transaction.complete("b.json");
+        transaction.save_to(&journal).unwrap();
+        assert!(!journal.exists());
+        assert!(Transaction::load_from(&journal).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_load_missing_state_is_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let loaded = StateStore::load_from(&temp_dir.path().join("state.yaml")).unwrap();
+        assert!(loaded.targets.is_empty());
+    }
+
+    #[test]
+    fn test_drift_detects_out_of_band_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let modified = temp_dir.path().join("modified.txt");
+        let deleted = temp_dir.path().join("deleted.txt");
+        let created = temp_dir.path().join("created.txt");
+        fs::write(&modified, "a").unwrap();
+        fs::write(&deleted, "b").unwrap();
+
+        let (modified, deleted, created) =
+            (path_str(&modified), path_str(&deleted), path_str(&created));
+        let tracked = [modified.as_str(), deleted.as_str(), created.as_str()];
+
+        let mut store = StateStore::default();
+        store.record("targets.json", TargetFileState::capture(tracked));
+        let state_file = temp_dir.path().join("state.yaml");
+        store.save_to(&state_file).unwrap();
+
+        // Change things behind chaser's back
+        File::options()
+            .write(true)
+            .open(&modified)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        fs::remove_file(&deleted).unwrap();
+        fs::write(&created, "c").unwrap();
+
+        let loaded = StateStore::load_from(&state_file).unwrap();
+        let drift = loaded.drift("targets.json", &TargetFileState::capture(tracked));
+
+        assert!(drift.contains(&PathDrift::Modified(modified)));
+        assert!(drift.contains(&PathDrift::Disappeared(deleted)));
+        assert!(drift.contains(&PathDrift::Appeared(created)));
+        assert_eq!(drift.len(), 3);
+    }
+
+    #[test]
+    fn test_drift_detects_target_file_edits() {
+        let mut store = StateStore::default();
+        store.record("targets.json", TargetFileState::capture(["./old"]));
+
+        let drift = store.drift("targets.json", &TargetFileState::capture(["./new"]));
+        assert_eq!(
+            drift,
+            vec![
+                PathDrift::Added("./new".to_string()),
+                PathDrift::Removed("./old".to_string()),
+            ]
+        );
+
+        // Unknown target files have no baseline
+        assert!(
+            store
+                .drift("other.json", &TargetFileState::capture(["./new"]))
+                .is_empty()
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
 use serde_json::Value as JsonValue;
 use serde_yaml_ng::Value as YamlValue;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use toml::Value as TomlValue;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -14,6 +17,18 @@ pub enum TargetFileFormat {
 }
 
 impl TargetFileFormat {
+    /// Every supported format
+    pub const ALL: [Self; 4] = [Self::Json, Self::Yaml, Self::Toml, Self::Csv];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Toml => "toml",
+            Self::Csv => "csv",
+        }
+    }
+
     pub fn from_path(path: &Path) -> Result<Self> {
         match path.extension().and_then(|s| s.to_str()) {
             Some("json") => Ok(Self::Json),
@@ -23,6 +38,135 @@ impl TargetFileFormat {
             _ => anyhow::bail!("Unsupported file format for: {:?}", path),
         }
     }
+
+    /// A list-style document in this format holding `paths`, shaped like the
+    /// files created for missing target files
+    pub fn render_paths(&self, paths: &[String]) -> Result<String> {
+        let document = BTreeMap::from([("paths", paths)]);
+        Ok(match self {
+            Self::Json => serde_json::to_string_pretty(paths)? + "\n",
+            Self::Yaml => serde_yaml_ng::to_string(&document)?,
+            Self::Toml => toml::to_string_pretty(&document)?,
+            Self::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                writer.write_record(["path", "type"])?;
+                for path in paths {
+                    let kind = if Path::new(path).is_dir() {
+                        "directory"
+                    } else {
+                        "file"
+                    };
+                    writer.write_record([path.as_str(), kind])?;
+                }
+                String::from_utf8(writer.into_inner()?)?
+            }
+        })
+    }
+}
+
+/// Write the paths recognized in `src` to a new target file `dst`, in the
+/// format of `dst`'s extension; returns the number of paths written
+pub fn convert_target_file(src: &Path, dst: &Path) -> Result<usize> {
+    if !src.exists() {
+        anyhow::bail!("Target file not found: {}", src.display());
+    }
+    if dst.exists() {
+        anyhow::bail!("Refusing to overwrite existing file: {}", dst.display());
+    }
+
+    let format = TargetFileFormat::from_path(dst)?;
+    let paths: Vec<String> = TargetFile::new(src.to_path_buf())?
+        .paths
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect();
+    let content = format.render_paths(&paths)?;
+    crate::write_mode::write_file(dst, crate::write_mode::line_ending().apply(&content))?;
+    Ok(paths.len())
+}
+
+/// Nesting levels read below the document root when none is configured
+pub const DEFAULT_MAX_EXTRACT_DEPTH: usize = 64;
+
+/// Process-wide nesting limit for path extraction
+static MAX_EXTRACT_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_EXTRACT_DEPTH);
+
+/// Stop extracting paths nested deeper than `depth` levels, protecting
+/// against pathologically nested target files
+pub fn set_max_extract_depth(depth: usize) {
+    MAX_EXTRACT_DEPTH.store(depth, Ordering::SeqCst);
+}
+
+pub fn max_extract_depth() -> usize {
+    MAX_EXTRACT_DEPTH.load(Ordering::SeqCst)
+}
+
+/// File extensions recognized as target files
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["json", "yaml", "yml", "toml", "csv"];
+
+/// A same-stem file with another supported extension next to a missing target
+/// file, which suggests the target file was renamed to a different format
+pub fn find_renamed_target(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return None;
+    }
+    let current = path.extension().and_then(|ext| ext.to_str());
+    SUPPORTED_EXTENSIONS
+        .iter()
+        .filter(|ext| Some(**ext) != current)
+        .map(|ext| path.with_extension(ext))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Entries [`scan_target_candidates`] visits before giving up by default
+pub const DEFAULT_MAX_SCAN_ENTRIES: usize = 100_000;
+
+/// Files under `dir` in a supported format that list at least one path,
+/// sorted by path
+///
+/// Hidden directories and symlinked directories are not entered, and files
+/// that fail to parse are skipped. Fails when `dir` can't be read, or once
+/// more than `max_entries` entries were visited, rather than walking an
+/// unexpectedly large tree.
+pub fn scan_target_candidates(dir: &Path, max_entries: usize) -> Result<Vec<TargetFile>> {
+    let mut candidates = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    let mut visited = 0;
+    while let Some(current) = pending.pop() {
+        let entries = match fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if current == dir => anyhow::bail!(crate::i18n::tf(
+                "msg_scan_unreadable",
+                &[&dir.display().to_string(), &e.to_string()]
+            )),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > max_entries {
+                anyhow::bail!(crate::i18n::tf(
+                    "msg_scan_too_large",
+                    &[&dir.display().to_string(), &max_entries.to_string()]
+                ));
+            }
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !entry.file_name().to_string_lossy().starts_with('.') {
+                    pending.push(path);
+                }
+            } else if TargetFileFormat::from_path(&path).is_ok()
+                && let Ok(target_file) = TargetFile::new(path)
+                && !target_file.paths.is_empty()
+            {
+                candidates.push(target_file);
+            }
+        }
+    }
+    candidates.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(candidates)
 }
 
 #[derive(Debug, Clone)]
@@ -32,149 +176,414 @@ pub struct PathEntry {
     pub last_known_path: Option<String>,
 }
 
+/// Line-level unified diff of a change to the file at `path`
+pub fn unified_diff(path: &Path, old: &str, new: &str) -> String {
+    let name = path.display().to_string();
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&name, &name)
+        .to_string()
+}
+
+/// Byte order mark some Windows editors put at the start of UTF-8 files
+const UTF8_BOM: char = '\u{feff}';
+
+/// Read a text file without its byte order mark, returning whether it had one
+fn read_without_bom(path: &Path) -> Result<(String, bool)> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read file: {:?}", path))?;
+    Ok(match content.strip_prefix(UTF8_BOM) {
+        Some(rest) => (rest.to_string(), true),
+        None => (content, false),
+    })
+}
+
+/// Whether a YAML or TOML document has a `#` comment
+///
+/// Works line by line: a `#` at the start of a line or after whitespace,
+/// outside a quoted string, starts a comment. Quotes only open a string where
+/// a value can start, so an apostrophe inside a plain YAML scalar does not hide
+/// a comment. Multi-line strings may give a
+/// false positive, which only makes formatting refuse the file.
+fn has_hash_comment(content: &str) -> bool {
+    content.lines().any(|line| {
+        let mut quote = None;
+        let mut escaped = false;
+        let mut previous = ' ';
+        for c in line.chars() {
+            match quote {
+                Some('"') if escaped => escaped = false,
+                Some('"') if c == '\\' => escaped = true,
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if (c == '"' || c == '\'')
+                    && (previous.is_whitespace() || "=:[{,-".contains(previous)) =>
+                {
+                    quote = Some(c)
+                }
+                None if c == '#' && previous.is_whitespace() => return true,
+                None => {}
+            }
+            previous = c;
+        }
+        false
+    })
+}
+
+/// Key holding the current path in an object-form entry
+pub const HISTORY_PATH_KEY: &str = "path";
+/// Key holding the previous path in an object-form entry
+pub const HISTORY_LAST_KNOWN_KEY: &str = "last_known_path";
+
+impl PathEntry {
+    fn found(path: &str, last_known_path: Option<&str>) -> Self {
+        Self {
+            path: path.to_string(),
+            exists: Path::new(path).exists(),
+            last_known_path: last_known_path.map(str::to_string),
+        }
+    }
+
+    /// Read an object-form entry `{ path, last_known_path }`
+    ///
+    /// `len` is the number of keys in the object and `field` looks up a string
+    /// value by key. Objects with any other keys are not history entries.
+    fn from_history_fields<'v>(
+        len: usize,
+        field: impl Fn(&str) -> Option<&'v str>,
+    ) -> Option<Self> {
+        let path = field(HISTORY_PATH_KEY).filter(|p| TargetFile::looks_like_path(p))?;
+        let last_known = field(HISTORY_LAST_KNOWN_KEY);
+        let expected_len = 1 + usize::from(last_known.is_some());
+        (len == expected_len).then(|| Self::found(path, last_known))
+    }
+}
+
+/// Outcome of [`TargetFile::update_path`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathUpdate {
+    /// The file was rewritten
+    Updated,
+    /// In no-write mode: the unified diff of the rewrite that was skipped
+    Previewed(String),
+    /// Nothing needed rewriting
+    Unchanged,
+    /// The path is still tracked in memory but the file on disk no longer lists it
+    MissingOnDisk,
+}
+
 #[derive(Debug, Clone)]
 pub struct TargetFile {
     pub path: PathBuf,
     pub format: TargetFileFormat,
     pub paths: Vec<PathEntry>,
+    /// Match tracked paths regardless of letter case
+    pub case_insensitive: bool,
+    /// CSV lines starting with this byte are comments
+    pub csv_comment: Option<u8>,
+    /// Rewrite moved paths as `{ path, last_known_path }` objects where the
+    /// format allows it
+    pub track_history: bool,
+}
+
+/// `path` without trailing slashes, except for a bare root
+pub fn trim_trailing_slash(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" if path.starts_with('/') => "/",
+        trimmed => trimmed,
+    }
+}
+
+/// Compare two path spellings, optionally ignoring letter case
+///
+/// A trailing slash does not make a difference: `src/` equals `src`.
+pub fn path_eq(a: &str, b: &str, case_insensitive: bool) -> bool {
+    let (a, b) = (trim_trailing_slash(a), trim_trailing_slash(b));
+    if case_insensitive {
+        a.to_lowercase() == b.to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// Rewrite `path` for a move of `old` to `new`
+///
+/// Returns `None` unless `path` is `old` itself or lies beneath it. The part
+/// matching `old` is replaced by `new` as spelled, so with `case_insensitive`
+/// a case-only rename is picked up too.
+pub fn rebase_path(path: &str, old: &str, new: &str, case_insensitive: bool) -> Option<String> {
+    let path_segments: Vec<&str> = path.split('/').collect();
+    let old_segments: Vec<&str> = old.trim_end_matches('/').split('/').collect();
+    if path_segments.len() < old_segments.len()
+        || !path_segments
+            .iter()
+            .zip(&old_segments)
+            .all(|(a, b)| path_eq(a, b, case_insensitive))
+    {
+        return None;
+    }
+
+    if path_segments.len() == old_segments.len() {
+        return Some(new.to_string());
+    }
+
+    let mut rebased: Vec<&str> = new.trim_end_matches('/').split('/').collect();
+    rebased.extend_from_slice(&path_segments[old_segments.len()..]);
+
+    Some(rebased.join("/"))
+}
+
+/// An exact-path replacement applied to string values in a target file
+struct PathReplacement<'a> {
+    old: &'a str,
+    new: &'a str,
+    case_insensitive: bool,
+    /// Turn replaced bare strings into `{ path, last_known_path }` objects
+    track_history: bool,
+}
+
+impl PathReplacement<'_> {
+    /// The replacement for `value`, if it is the old path
+    ///
+    /// Keeps whether `value` was written with a trailing slash.
+    fn apply(&self, value: &str) -> Option<String> {
+        if !path_eq(value, self.old, self.case_insensitive) {
+            return None;
+        }
+        let trimmed = trim_trailing_slash(value);
+        let replaced = if self.case_insensitive {
+            rebase_path(trimmed, self.old, self.new, true)?
+        } else {
+            trim_trailing_slash(self.new).to_string()
+        };
+        if trimmed.len() < value.len() && !replaced.ends_with('/') {
+            Some(replaced + "/")
+        } else {
+            Some(replaced)
+        }
+    }
+
+    /// Split `line` into the replaced leading path and the rest of the line
+    fn apply_prefix<'l>(&self, line: &'l str) -> Option<(String, &'l str)> {
+        let prefix = line.get(..self.old.len())?;
+        let remaining = &line[self.old.len()..];
+        Some((self.apply(prefix)?, remaining))
+    }
 }
 
 impl TargetFile {
     pub fn new(path: PathBuf) -> Result<Self> {
+        Self::with_csv_comment(path, None)
+    }
+
+    /// Load a target file, treating CSV lines that start with `comment` as comments
+    pub fn with_csv_comment(path: PathBuf, comment: Option<u8>) -> Result<Self> {
         let format = TargetFileFormat::from_path(&path)?;
-        let paths = Self::extract_paths(&path, &format)?;
+        let paths = Self::extract_paths(&path, &format, comment)?;
 
         Ok(Self {
             path,
             format,
             paths,
+            case_insensitive: false,
+            csv_comment: comment,
+            track_history: false,
         })
     }
 
     /// Extract all paths from the target file
-    fn extract_paths(file_path: &Path, format: &TargetFileFormat) -> Result<Vec<PathEntry>> {
+    fn extract_paths(
+        file_path: &Path,
+        format: &TargetFileFormat,
+        csv_comment: Option<u8>,
+    ) -> Result<Vec<PathEntry>> {
         if !file_path.exists() {
             return Ok(Vec::new());
         }
 
-        let content = fs::read_to_string(file_path)
-            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
+        let (content, _) = read_without_bom(file_path)?;
+        Self::extract_paths_from_content(&content, format, csv_comment)
+    }
 
+    fn extract_paths_from_content(
+        content: &str,
+        format: &TargetFileFormat,
+        csv_comment: Option<u8>,
+    ) -> Result<Vec<PathEntry>> {
         match format {
-            TargetFileFormat::Json => Self::extract_paths_from_json(&content),
-            TargetFileFormat::Yaml => Self::extract_paths_from_yaml(&content),
-            TargetFileFormat::Toml => Self::extract_paths_from_toml(&content),
-            TargetFileFormat::Csv => Self::extract_paths_from_csv(&content),
+            TargetFileFormat::Json => Self::extract_paths_from_json(content),
+            TargetFileFormat::Yaml => Self::extract_paths_from_yaml(content),
+            TargetFileFormat::Toml => Self::extract_paths_from_toml(content),
+            TargetFileFormat::Csv => Self::extract_paths_from_csv(content, csv_comment),
         }
     }
 
     fn extract_paths_from_json(content: &str) -> Result<Vec<PathEntry>> {
+        if let Some(items) = Self::parse_string_array(content) {
+            return Ok(items
+                .iter()
+                .filter(|item| Self::looks_like_path(item))
+                .map(|item| PathEntry::found(item, None))
+                .collect());
+        }
+
         let value: JsonValue = serde_json::from_str(content)?;
         let mut paths = Vec::new();
-        Self::collect_paths_from_json_value(&value, &mut paths);
-        Ok(paths
-            .into_iter()
-            .map(|p| PathEntry {
-                path: p.clone(),
-                exists: Path::new(&p).exists(),
-                last_known_path: None,
-            })
-            .collect())
+        let max_depth = max_extract_depth();
+        if Self::collect_paths_from_json_value(&value, max_depth, &mut paths) {
+            Self::warn_depth_exceeded(max_depth);
+        }
+        Ok(paths)
     }
 
-    fn collect_paths_from_json_value(value: &JsonValue, paths: &mut Vec<String>) {
+    fn warn_depth_exceeded(max_depth: usize) {
+        eprintln!(
+            "  {}",
+            crate::i18n::tf("msg_extract_depth_exceeded", &[&max_depth.to_string()]).yellow()
+        );
+    }
+
+    /// Push the paths found in `value` and below, descending at most
+    /// `depth_left` levels of nesting; returns whether deeper content was skipped
+    fn collect_paths_from_json_value(
+        value: &JsonValue,
+        depth_left: usize,
+        paths: &mut Vec<PathEntry>,
+    ) -> bool {
         match value {
-            JsonValue::String(s) => {
-                if Self::looks_like_path(s) {
-                    paths.push(s.clone());
-                }
+            JsonValue::String(s) if Self::looks_like_path(s) => {
+                paths.push(PathEntry::found(s, None));
+                false
             }
-            JsonValue::Array(arr) => {
-                for item in arr {
-                    Self::collect_paths_from_json_value(item, paths);
+            JsonValue::Array(items) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
+                for item in items {
+                    truncated |= Self::collect_paths_from_json_value(item, depth_left, paths);
                 }
+                truncated
             }
-            JsonValue::Object(obj) => {
-                for (_, v) in obj {
-                    Self::collect_paths_from_json_value(v, paths);
+            JsonValue::Object(map) => {
+                let field = |key: &str| map.get(key).and_then(JsonValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(map.len(), field) {
+                    paths.push(entry);
+                    return false;
                 }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
+                for (_, v) in map {
+                    truncated |= Self::collect_paths_from_json_value(v, depth_left, paths);
+                }
+                truncated
             }
-            _ => {}
+            _ => false,
         }
     }
 
     fn extract_paths_from_yaml(content: &str) -> Result<Vec<PathEntry>> {
         let value: YamlValue = serde_yaml_ng::from_str(content)?;
         let mut paths = Vec::new();
-        Self::collect_paths_from_yaml_value(&value, &mut paths);
-        Ok(paths
-            .into_iter()
-            .map(|p| PathEntry {
-                path: p.clone(),
-                exists: Path::new(&p).exists(),
-                last_known_path: None,
-            })
-            .collect())
+        let max_depth = max_extract_depth();
+        if Self::collect_paths_from_yaml_value(&value, max_depth, &mut paths) {
+            Self::warn_depth_exceeded(max_depth);
+        }
+        Ok(paths)
     }
 
-    fn collect_paths_from_yaml_value(value: &YamlValue, paths: &mut Vec<String>) {
+    fn collect_paths_from_yaml_value(
+        value: &YamlValue,
+        depth_left: usize,
+        paths: &mut Vec<PathEntry>,
+    ) -> bool {
         match value {
-            YamlValue::String(s) => {
-                if Self::looks_like_path(s) {
-                    paths.push(s.clone());
-                }
+            YamlValue::String(s) if Self::looks_like_path(s) => {
+                paths.push(PathEntry::found(s, None));
+                false
             }
-            YamlValue::Sequence(seq) => {
-                for item in seq {
-                    Self::collect_paths_from_yaml_value(item, paths);
+            YamlValue::Sequence(items) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
+                for item in items {
+                    truncated |= Self::collect_paths_from_yaml_value(item, depth_left, paths);
                 }
+                truncated
             }
             YamlValue::Mapping(map) => {
+                let field = |key: &str| map.get(key).and_then(YamlValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(map.len(), field) {
+                    paths.push(entry);
+                    return false;
+                }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
                 for (_, v) in map {
-                    Self::collect_paths_from_yaml_value(v, paths);
+                    truncated |= Self::collect_paths_from_yaml_value(v, depth_left, paths);
                 }
+                truncated
             }
-            _ => {}
+            _ => false,
         }
     }
 
     fn extract_paths_from_toml(content: &str) -> Result<Vec<PathEntry>> {
         let value: TomlValue = toml::from_str(content)?;
         let mut paths = Vec::new();
-        Self::collect_paths_from_toml_value(&value, &mut paths);
-        Ok(paths
-            .into_iter()
-            .map(|p| PathEntry {
-                path: p.clone(),
-                exists: Path::new(&p).exists(),
-                last_known_path: None,
-            })
-            .collect())
+        let max_depth = max_extract_depth();
+        if Self::collect_paths_from_toml_value(&value, max_depth, &mut paths) {
+            Self::warn_depth_exceeded(max_depth);
+        }
+        Ok(paths)
     }
 
-    fn collect_paths_from_toml_value(value: &TomlValue, paths: &mut Vec<String>) {
+    fn collect_paths_from_toml_value(
+        value: &TomlValue,
+        depth_left: usize,
+        paths: &mut Vec<PathEntry>,
+    ) -> bool {
         match value {
-            TomlValue::String(s) => {
-                if Self::looks_like_path(s) {
-                    paths.push(s.clone());
-                }
+            TomlValue::String(s) if Self::looks_like_path(s) => {
+                paths.push(PathEntry::found(s, None));
+                false
             }
-            TomlValue::Array(arr) => {
-                for item in arr {
-                    Self::collect_paths_from_toml_value(item, paths);
+            TomlValue::Array(items) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
+                for item in items {
+                    truncated |= Self::collect_paths_from_toml_value(item, depth_left, paths);
                 }
+                truncated
             }
-            TomlValue::Table(table) => {
-                for (_, v) in table {
-                    Self::collect_paths_from_toml_value(v, paths);
+            TomlValue::Table(map) => {
+                let field = |key: &str| map.get(key).and_then(TomlValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(map.len(), field) {
+                    paths.push(entry);
+                    return false;
+                }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return true;
+                };
+                let mut truncated = false;
+                for (_, v) in map {
+                    truncated |= Self::collect_paths_from_toml_value(v, depth_left, paths);
                 }
+                truncated
             }
-            _ => {}
+            _ => false,
         }
     }
 
-    fn extract_paths_from_csv(content: &str) -> Result<Vec<PathEntry>> {
-        let mut reader = csv::Reader::from_reader(content.as_bytes());
+    fn extract_paths_from_csv(content: &str, comment: Option<u8>) -> Result<Vec<PathEntry>> {
+        let mut reader = Self::csv_reader(content, comment);
         let mut paths = Vec::new();
 
         for result in reader.records() {
@@ -188,14 +597,16 @@ impl TargetFile {
 
         Ok(paths
             .into_iter()
-            .map(|p| PathEntry {
-                path: p.clone(),
-                exists: Path::new(&p).exists(),
-                last_known_path: None,
-            })
+            .map(|p| PathEntry::found(&p, None))
             .collect())
     }
 
+    fn csv_reader(content: &str, comment: Option<u8>) -> csv::Reader<&[u8]> {
+        csv::ReaderBuilder::new()
+            .comment(comment)
+            .from_reader(content.as_bytes())
+    }
+
     /// Check if a string looks like a file/directory path
     fn looks_like_path(s: &str) -> bool {
         if s.is_empty() {
@@ -212,121 +623,461 @@ impl TargetFile {
             || (cfg!(windows) && s.len() > 2 && s.chars().nth(1) == Some(':'))
     }
 
+    /// Whether the file on disk currently lists `path`
+    pub fn contains_path(&self, path: &str) -> bool {
+        Self::extract_paths(&self.path, &self.format, self.csv_comment).is_ok_and(|paths| {
+            paths
+                .iter()
+                .any(|entry| path_eq(&entry.path, path, self.case_insensitive))
+        })
+    }
+
     /// Update a path in the target file
-    pub fn update_path(&mut self, old_path: &str, new_path: &str) -> Result<()> {
+    ///
+    /// Renaming a path to itself or a path the file doesn't list leaves the
+    /// file untouched. A path still tracked in memory but gone from the file
+    /// on disk is skipped and reported as [`PathUpdate::MissingOnDisk`].
+    pub fn update_path(&mut self, old_path: &str, new_path: &str) -> Result<PathUpdate> {
+        if old_path == new_path {
+            return Ok(PathUpdate::Unchanged);
+        }
         // Update internal path tracking
-        for entry in &mut self.paths {
-            if entry.path == old_path {
+        let replacement = PathReplacement {
+            old: old_path,
+            new: new_path,
+            case_insensitive: self.case_insensitive,
+            track_history: self.track_history,
+        };
+
+        let mut paths = self.paths.clone();
+        let mut listed = false;
+        for entry in &mut paths {
+            if let Some(updated) = replacement.apply(&entry.path) {
                 entry.last_known_path = Some(entry.path.clone());
-                entry.path = new_path.to_string();
-                entry.exists = Path::new(new_path).exists();
+                entry.exists = Path::new(&updated).exists();
+                entry.path = updated;
+                listed = true;
             }
         }
+        if !listed {
+            return Ok(PathUpdate::Unchanged);
+        }
+
+        // The content checked for the path is the content rewritten below
+        let on_disk = if self.path.exists() {
+            Some(read_without_bom(&self.path)?)
+        } else {
+            None
+        };
+        // A file that no longer parses is left to fail the rewrite below
+        if let Some((content, _)) = &on_disk
+            && let Ok(listed) =
+                Self::extract_paths_from_content(content, &self.format, self.csv_comment)
+            && !listed
+                .iter()
+                .any(|entry| replacement.apply(&entry.path).is_some())
+        {
+            return Ok(PathUpdate::MissingOnDisk);
+        }
+
+        // Track the new paths only once the file itself was rewritten
+        let update = match on_disk {
+            Some((content, had_bom)) => {
+                let updated = self.updated_content(&content, &replacement)?;
+                self.write_rewritten(&content, had_bom, updated)?
+            }
+            None => PathUpdate::Unchanged,
+        };
+        self.paths = paths;
+        Ok(update)
+    }
 
-        // Update the actual file content
-        self.update_file_content(old_path, new_path)
+    fn updated_content(&self, content: &str, replacement: &PathReplacement) -> Result<String> {
+        match self.format {
+            TargetFileFormat::Json => self.update_json_content(content, replacement),
+            TargetFileFormat::Yaml => self.update_yaml_content(content, replacement),
+            TargetFileFormat::Toml => self.update_toml_content(content, replacement),
+            TargetFileFormat::Csv => self.update_csv_content(content, replacement),
+        }
     }
 
-    fn update_file_content(&self, old_path: &str, new_path: &str) -> Result<()> {
+    /// Read the file, apply `transform`, and write the result back only if it
+    /// still parses in the file's format, so a broken file is never written
+    ///
+    /// Identical content is not rewritten. With `--no-write` the edit is
+    /// returned as a diff instead.
+    fn rewrite_content(
+        &self,
+        transform: impl FnOnce(&str) -> Result<String>,
+    ) -> Result<PathUpdate> {
         if !self.path.exists() {
-            return Ok(());
+            return Ok(PathUpdate::Unchanged);
         }
 
-        let content = fs::read_to_string(&self.path)?;
+        let (content, had_bom) = read_without_bom(&self.path)?;
+        let updated_content = transform(&content)?;
+        self.write_rewritten(&content, had_bom, updated_content)
+    }
 
-        let updated_content = match self.format {
-            TargetFileFormat::Json => self.update_json_content(&content, old_path, new_path)?,
-            TargetFileFormat::Yaml => self.update_yaml_content(&content, old_path, new_path)?,
-            TargetFileFormat::Toml => self.update_toml_content(&content, old_path, new_path)?,
-            TargetFileFormat::Csv => self.update_csv_content(&content, old_path, new_path)?,
+    /// Write `updated_content` over `content` read with [`read_without_bom`]
+    fn write_rewritten(
+        &self,
+        content: &str,
+        had_bom: bool,
+        updated_content: String,
+    ) -> Result<PathUpdate> {
+        Self::validate_content(&self.format, &updated_content, self.csv_comment).with_context(
+            || {
+                format!(
+                    "Refusing to write {:?}: updated content is not valid {:?}",
+                    self.path, self.format
+                )
+            },
+        )?;
+
+        let mut updated_content = crate::write_mode::line_ending().apply(&updated_content);
+        if updated_content == content {
+            return Ok(PathUpdate::Unchanged);
+        }
+        let update = if crate::write_mode::is_no_write() {
+            PathUpdate::Previewed(unified_diff(&self.path, content, &updated_content))
+        } else {
+            PathUpdate::Updated
         };
+        if had_bom {
+            updated_content.insert(0, UTF8_BOM);
+        }
+        crate::write_mode::write_file(&self.path, updated_content)?;
+        Ok(update)
+    }
 
-        fs::write(&self.path, updated_content)?;
+    /// Check that content parses with the loader for `format`
+    fn validate_content(
+        format: &TargetFileFormat,
+        content: &str,
+        csv_comment: Option<u8>,
+    ) -> Result<()> {
+        match format {
+            TargetFileFormat::Json => {
+                serde_json::from_str::<JsonValue>(content)?;
+            }
+            TargetFileFormat::Yaml => {
+                serde_yaml_ng::from_str::<YamlValue>(content)?;
+            }
+            TargetFileFormat::Toml => {
+                toml::from_str::<TomlValue>(content)?;
+            }
+            TargetFileFormat::Csv => {
+                let mut reader = Self::csv_reader(content, csv_comment);
+                for record in reader.records() {
+                    record?;
+                }
+            }
+        }
         Ok(())
     }
 
-    fn update_json_content(&self, content: &str, old_path: &str, new_path: &str) -> Result<String> {
+    /// Parse a document that is a top-level array of strings, the common shape
+    /// of large target files, without building a `JsonValue` tree
+    fn parse_string_array(content: &str) -> Option<Vec<String>> {
+        if !content.trim_start().starts_with('[') {
+            return None;
+        }
+        serde_json::from_str(content).ok()
+    }
+
+    /// Replace exact string elements of a top-level string array in place,
+    /// leaving the rest of the text (and its formatting) untouched
+    fn replace_in_string_array(content: &str, replacement: &PathReplacement) -> String {
+        // The old path may be written with or without a trailing slash
+        let old = trim_trailing_slash(replacement.old);
+        let mut updated = content.to_string();
+        for spelling in [old.to_string(), format!("{}/", old)] {
+            let Some(new) = replacement.apply(&spelling) else {
+                continue;
+            };
+            if let (Ok(needle), Ok(substitute)) = (
+                serde_json::to_string(&spelling),
+                serde_json::to_string(&new),
+            ) {
+                updated = Self::replace_string_elements(&updated, &needle, &substitute);
+            }
+        }
+        updated
+    }
+
+    /// Replace the JSON string literal `needle` wherever it is a whole array element
+    fn replace_string_elements(content: &str, needle: &str, substitute: &str) -> String {
+        let mut updated = String::with_capacity(content.len());
+        let mut rest = content;
+        while let Some(found) = rest.find(needle) {
+            let (before, after) = rest.split_at(found);
+            let after = &after[needle.len()..];
+            // Only whole elements: preceded by `[` or `,` and followed by `,` or `]`,
+            // which rules out matches inside other strings
+            let is_element = updated
+                .chars()
+                .chain(before.chars())
+                .rev()
+                .find(|c| !c.is_whitespace())
+                .is_some_and(|c| c == '[' || c == ',')
+                && after.trim_start().starts_with([',', ']']);
+
+            updated.push_str(before);
+            updated.push_str(if is_element { substitute } else { needle });
+            rest = after;
+        }
+        updated.push_str(rest);
+        updated
+    }
+
+    fn update_json_content(&self, content: &str, replacement: &PathReplacement) -> Result<String> {
+        // Exact replacements in a plain string array can skip the value tree
+        if !replacement.case_insensitive
+            && !replacement.track_history
+            && Self::parse_string_array(content).is_some()
+        {
+            return Ok(Self::replace_in_string_array(content, replacement));
+        }
+
         let mut value: JsonValue = serde_json::from_str(content)?;
-        Self::update_json_value(&mut value, old_path, new_path);
-        Ok(serde_json::to_string_pretty(&value)?)
+        let mut paths = Vec::new();
+        // Only what loading recognized as paths is updated
+        let max_depth = max_extract_depth();
+        Self::collect_paths_from_json_value(&value, max_depth, &mut paths);
+        Self::update_json_value(&mut value, replacement, max_depth);
+
+        let parse = |text: &str| serde_json::from_str::<JsonValue>(text).ok();
+        match Self::edit_scalars_in_place(content, &paths, replacement, parse, &value) {
+            Some(edited) => Ok(edited),
+            None => Ok(serde_json::to_string_pretty(&value)?),
+        }
+    }
+
+    /// Rewrite only the spans of the scalars `replacement` changes, which keeps
+    /// the quoting and escaping of every entry and the rest of the layout
+    ///
+    /// `None` when the edited text does not parse to `expected`, e.g. because
+    /// a path was written with escapes, so the caller re-serializes instead.
+    /// Re-serializing keeps sequence elements in their original order in
+    /// every format.
+    fn edit_scalars_in_place<V: PartialEq>(
+        content: &str,
+        paths: &[PathEntry],
+        replacement: &PathReplacement,
+        parse: impl Fn(&str) -> Option<V>,
+        expected: &V,
+    ) -> Option<String> {
+        let mut edited = content.to_string();
+        let mut replaced = Vec::new();
+        for entry in paths {
+            if replaced.contains(&entry.path.as_str()) {
+                continue;
+            }
+            if let Some(new) = replacement.apply(&entry.path) {
+                edited = Self::replace_scalar_spans(&edited, &entry.path, &new);
+                replaced.push(entry.path.as_str());
+            }
+        }
+        (parse(&edited).as_ref() == Some(expected)).then_some(edited)
     }
 
-    fn update_json_value(value: &mut JsonValue, old_path: &str, new_path: &str) {
+    /// Replace `old` wherever it is a whole scalar on a line: between matching
+    /// quotes, or unquoted after `- `, `: `, `[` or `,` up to the end of the
+    /// value
+    fn replace_scalar_spans(content: &str, old: &str, new: &str) -> String {
+        let mut updated = String::with_capacity(content.len());
+        for line in content.split_inclusive('\n') {
+            let mut start = 0;
+            while let Some(offset) = line[start..].find(old) {
+                let at = start + offset;
+                let end = at + old.len();
+                let (before, after) = (&line[..at], &line[end..]);
+                let whole = match before.chars().next_back() {
+                    Some(quote @ ('"' | '\'')) => after.starts_with(quote),
+                    _ => {
+                        matches!(
+                            before.trim_end().chars().next_back(),
+                            None | Some('-' | ':' | '[' | ',')
+                        ) && after
+                            .trim_start()
+                            .chars()
+                            .next()
+                            .is_none_or(|c| matches!(c, '#' | ',' | ']' | '}'))
+                    }
+                };
+                updated.push_str(&line[start..at]);
+                updated.push_str(if whole { new } else { old });
+                start = end;
+            }
+            updated.push_str(&line[start..]);
+        }
+        updated
+    }
+
+    /// Apply `replacement` to the path strings [`Self::collect_paths_from_json_value`]
+    /// would find within `depth_left` levels, leaving every other string untouched
+    fn update_json_value(value: &mut JsonValue, replacement: &PathReplacement, depth_left: usize) {
         match value {
-            JsonValue::String(s) => {
-                if s == old_path {
-                    *s = new_path.to_string();
+            JsonValue::String(s) if Self::looks_like_path(s) => {
+                if let Some(updated) = replacement.apply(s) {
+                    *value = if replacement.track_history {
+                        let mut entry = serde_json::Map::new();
+                        entry.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        entry.insert(HISTORY_LAST_KNOWN_KEY.into(), s.as_str().into());
+                        JsonValue::Object(entry)
+                    } else {
+                        JsonValue::String(updated)
+                    };
                 }
             }
             JsonValue::Array(arr) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for item in arr {
-                    Self::update_json_value(item, old_path, new_path);
+                    Self::update_json_value(item, replacement, depth_left);
                 }
             }
             JsonValue::Object(obj) => {
+                let field = |key: &str| obj.get(key).and_then(JsonValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(obj.len(), field) {
+                    if let Some(updated) = replacement.apply(&entry.path) {
+                        obj.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        obj.insert(HISTORY_LAST_KNOWN_KEY.into(), entry.path.into());
+                    }
+                    return;
+                }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for (_, v) in obj {
-                    Self::update_json_value(v, old_path, new_path);
+                    Self::update_json_value(v, replacement, depth_left);
                 }
             }
             _ => {}
         }
     }
 
-    fn update_yaml_content(&self, content: &str, old_path: &str, new_path: &str) -> Result<String> {
+    fn update_yaml_content(&self, content: &str, replacement: &PathReplacement) -> Result<String> {
         let mut value: YamlValue = serde_yaml_ng::from_str(content)?;
-        Self::update_yaml_value(&mut value, old_path, new_path);
-        Ok(serde_yaml_ng::to_string(&value)?)
+        let mut paths = Vec::new();
+        // Only what loading recognized as paths is updated
+        let max_depth = max_extract_depth();
+        Self::collect_paths_from_yaml_value(&value, max_depth, &mut paths);
+        Self::update_yaml_value(&mut value, replacement, max_depth);
+
+        let parse = |text: &str| serde_yaml_ng::from_str::<YamlValue>(text).ok();
+        match Self::edit_scalars_in_place(content, &paths, replacement, parse, &value) {
+            Some(edited) => Ok(edited),
+            None => Ok(serde_yaml_ng::to_string(&value)?),
+        }
     }
 
-    fn update_yaml_value(value: &mut YamlValue, old_path: &str, new_path: &str) {
+    /// Apply `replacement` to the path strings [`Self::collect_paths_from_yaml_value`]
+    /// would find within `depth_left` levels, leaving every other string untouched
+    fn update_yaml_value(value: &mut YamlValue, replacement: &PathReplacement, depth_left: usize) {
         match value {
-            YamlValue::String(s) => {
-                if s == old_path {
-                    *s = new_path.to_string();
+            YamlValue::String(s) if Self::looks_like_path(s) => {
+                if let Some(updated) = replacement.apply(s) {
+                    *value = if replacement.track_history {
+                        let mut entry = serde_yaml_ng::Mapping::new();
+                        entry.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        entry.insert(HISTORY_LAST_KNOWN_KEY.into(), s.as_str().into());
+                        YamlValue::Mapping(entry)
+                    } else {
+                        YamlValue::String(updated)
+                    };
                 }
             }
             YamlValue::Sequence(seq) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for item in seq {
-                    Self::update_yaml_value(item, old_path, new_path);
+                    Self::update_yaml_value(item, replacement, depth_left);
                 }
             }
             YamlValue::Mapping(map) => {
+                let field = |key: &str| map.get(key).and_then(YamlValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(map.len(), field) {
+                    if let Some(updated) = replacement.apply(&entry.path) {
+                        map.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        map.insert(HISTORY_LAST_KNOWN_KEY.into(), entry.path.into());
+                    }
+                    return;
+                }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for (_, v) in map {
-                    Self::update_yaml_value(v, old_path, new_path);
+                    Self::update_yaml_value(v, replacement, depth_left);
                 }
             }
             _ => {}
         }
     }
 
-    fn update_toml_content(&self, content: &str, old_path: &str, new_path: &str) -> Result<String> {
+    fn update_toml_content(&self, content: &str, replacement: &PathReplacement) -> Result<String> {
         let mut value: TomlValue = toml::from_str(content)?;
-        Self::update_toml_value(&mut value, old_path, new_path);
-        Ok(toml::to_string_pretty(&value)?)
+        let mut paths = Vec::new();
+        // Only what loading recognized as paths is updated
+        let max_depth = max_extract_depth();
+        Self::collect_paths_from_toml_value(&value, max_depth, &mut paths);
+        Self::update_toml_value(&mut value, replacement, max_depth);
+
+        let parse = |text: &str| toml::from_str::<TomlValue>(text).ok();
+        match Self::edit_scalars_in_place(content, &paths, replacement, parse, &value) {
+            Some(edited) => Ok(edited),
+            None => Ok(toml::to_string_pretty(&value)?),
+        }
     }
 
-    fn update_toml_value(value: &mut TomlValue, old_path: &str, new_path: &str) {
+    /// Apply `replacement` to the path strings [`Self::collect_paths_from_toml_value`]
+    /// would find within `depth_left` levels, leaving every other string untouched
+    fn update_toml_value(value: &mut TomlValue, replacement: &PathReplacement, depth_left: usize) {
         match value {
-            TomlValue::String(s) => {
-                if s == old_path {
-                    *s = new_path.to_string();
+            TomlValue::String(s) if Self::looks_like_path(s) => {
+                if let Some(updated) = replacement.apply(s) {
+                    *value = if replacement.track_history {
+                        let mut entry = toml::map::Map::new();
+                        entry.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        entry.insert(HISTORY_LAST_KNOWN_KEY.into(), s.as_str().into());
+                        TomlValue::Table(entry)
+                    } else {
+                        TomlValue::String(updated)
+                    };
                 }
             }
             TomlValue::Array(arr) => {
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for item in arr {
-                    Self::update_toml_value(item, old_path, new_path);
+                    Self::update_toml_value(item, replacement, depth_left);
                 }
             }
             TomlValue::Table(table) => {
+                let field = |key: &str| table.get(key).and_then(TomlValue::as_str);
+                if let Some(entry) = PathEntry::from_history_fields(table.len(), field) {
+                    if let Some(updated) = replacement.apply(&entry.path) {
+                        table.insert(HISTORY_PATH_KEY.into(), updated.into());
+                        table.insert(HISTORY_LAST_KNOWN_KEY.into(), entry.path.into());
+                    }
+                    return;
+                }
+                let Some(depth_left) = depth_left.checked_sub(1) else {
+                    return;
+                };
                 for (_, v) in table {
-                    Self::update_toml_value(v, old_path, new_path);
+                    Self::update_toml_value(v, replacement, depth_left);
                 }
             }
             _ => {}
         }
     }
 
-    fn update_csv_content(&self, content: &str, old_path: &str, new_path: &str) -> Result<String> {
+    fn update_csv_content(&self, content: &str, replacement: &PathReplacement) -> Result<String> {
         let lines: Vec<&str> = content.lines().collect();
         if lines.is_empty() {
             return Ok(content.to_string());
@@ -336,10 +1087,9 @@ impl TargetFile {
         updated_lines.push(lines[0].to_string()); // Keep header
 
         for line in &lines[1..] {
-            if line.starts_with(old_path) {
+            if let Some((updated, remaining)) = replacement.apply_prefix(line) {
                 // Replace the path at the beginning of the line
-                let remaining = &line[old_path.len()..];
-                updated_lines.push(format!("{}{}", new_path, remaining));
+                updated_lines.push(format!("{}{}", updated, remaining));
             } else {
                 updated_lines.push(line.to_string());
             }
@@ -348,10 +1098,168 @@ impl TargetFile {
         Ok(updated_lines.join("\n") + "\n")
     }
 
+    /// The file content in canonical layout: recognized path entries sorted
+    /// and the document re-indented, everything else left as it is
+    ///
+    /// Key order is kept. YAML and TOML files with comments are refused, since
+    /// re-serializing them would drop the comments.
+    pub fn formatted_content(&self) -> Result<String> {
+        let (content, _) = read_without_bom(&self.path)?;
+        if matches!(self.format, TargetFileFormat::Yaml | TargetFileFormat::Toml)
+            && has_hash_comment(&content)
+        {
+            anyhow::bail!(crate::i18n::tf(
+                "msg_format_would_drop_comments",
+                &[&self.path.display().to_string()]
+            ));
+        }
+        match self.format {
+            TargetFileFormat::Json => {
+                let mut value: JsonValue = serde_json::from_str(&content)?;
+                Self::sort_json_value(&mut value);
+                Ok(serde_json::to_string_pretty(&value)? + "\n")
+            }
+            TargetFileFormat::Yaml => {
+                let mut value: YamlValue = serde_yaml_ng::from_str(&content)?;
+                Self::sort_yaml_value(&mut value);
+                Ok(serde_yaml_ng::to_string(&value)?)
+            }
+            TargetFileFormat::Toml => {
+                let mut value: TomlValue = toml::from_str(&content)?;
+                Self::sort_toml_value(&mut value);
+                Ok(toml::to_string_pretty(&value)?)
+            }
+            TargetFileFormat::Csv => Ok(self.format_csv_content(&content)),
+        }
+    }
+
+    /// Rewrite the file in canonical layout
+    pub fn format_in_place(&self) -> Result<PathUpdate> {
+        let formatted = self.formatted_content()?;
+        self.rewrite_content(|_| Ok(formatted))
+    }
+
+    /// Sort the elements `key` recognizes among their own positions, leaving
+    /// every other element where it is
+    fn sort_recognized<T: Clone>(items: &mut [T], key: impl Fn(&T) -> Option<String>) {
+        let slots: Vec<(usize, String)> = items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| key(item).map(|k| (i, k)))
+            .collect();
+        let mut sorted = slots.clone();
+        sorted.sort_by(|a, b| a.1.cmp(&b.1));
+        let reordered: Vec<T> = sorted.iter().map(|(i, _)| items[*i].clone()).collect();
+        for ((slot, _), item) in slots.iter().zip(reordered) {
+            items[*slot] = item;
+        }
+    }
+
+    fn json_entry_path(value: &JsonValue) -> Option<String> {
+        match value {
+            JsonValue::String(s) if Self::looks_like_path(s) => Some(s.clone()),
+            JsonValue::Object(obj) => {
+                let field = |key: &str| obj.get(key).and_then(JsonValue::as_str);
+                PathEntry::from_history_fields(obj.len(), field).map(|entry| entry.path)
+            }
+            _ => None,
+        }
+    }
+
+    fn sort_json_value(value: &mut JsonValue) {
+        match value {
+            JsonValue::Array(arr) => {
+                Self::sort_recognized(arr, Self::json_entry_path);
+                for item in arr {
+                    Self::sort_json_value(item);
+                }
+            }
+            JsonValue::Object(obj) => {
+                for (_, v) in obj {
+                    Self::sort_json_value(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn yaml_entry_path(value: &YamlValue) -> Option<String> {
+        match value {
+            YamlValue::String(s) if Self::looks_like_path(s) => Some(s.clone()),
+            YamlValue::Mapping(map) => {
+                let field = |key: &str| map.get(key).and_then(YamlValue::as_str);
+                PathEntry::from_history_fields(map.len(), field).map(|entry| entry.path)
+            }
+            _ => None,
+        }
+    }
+
+    fn sort_yaml_value(value: &mut YamlValue) {
+        match value {
+            YamlValue::Sequence(seq) => {
+                Self::sort_recognized(seq, Self::yaml_entry_path);
+                for item in seq {
+                    Self::sort_yaml_value(item);
+                }
+            }
+            YamlValue::Mapping(map) => {
+                for (_, v) in map {
+                    Self::sort_yaml_value(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn toml_entry_path(value: &TomlValue) -> Option<String> {
+        match value {
+            TomlValue::String(s) if Self::looks_like_path(s) => Some(s.clone()),
+            TomlValue::Table(table) => {
+                let field = |key: &str| table.get(key).and_then(TomlValue::as_str);
+                PathEntry::from_history_fields(table.len(), field).map(|entry| entry.path)
+            }
+            _ => None,
+        }
+    }
+
+    fn sort_toml_value(value: &mut TomlValue) {
+        match value {
+            TomlValue::Array(arr) => {
+                Self::sort_recognized(arr, Self::toml_entry_path);
+                for item in arr {
+                    Self::sort_toml_value(item);
+                }
+            }
+            TomlValue::Table(table) => {
+                for (_, v) in table {
+                    Self::sort_toml_value(v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Keep the header and comment lines in place and sort the rows that
+    /// start with a path among themselves
+    fn format_csv_content(&self, content: &str) -> String {
+        let mut lines: Vec<&str> = content.lines().map(str::trim_end).collect();
+        if lines.len() > 1 {
+            let comment = self.csv_comment;
+            Self::sort_recognized(&mut lines[1..], |line| {
+                if comment.is_some_and(|c| line.as_bytes().first() == Some(&c)) {
+                    return None;
+                }
+                let first = line.split(',').next().unwrap_or_default();
+                Self::looks_like_path(first).then(|| line.to_string())
+            });
+        }
+        lines.join("\n") + "\n"
+    }
+
     /// Mark a path as deleted (but keep tracking it)
     pub fn mark_path_deleted(&mut self, path: &str) -> Result<()> {
         for entry in &mut self.paths {
-            if entry.path == path {
+            if path_eq(&entry.path, path, self.case_insensitive) {
                 entry.exists = false;
             }
         }
@@ -361,7 +1269,7 @@ impl TargetFile {
     /// Mark a path as restored
     pub fn mark_path_restored(&mut self, path: &str) -> Result<()> {
         for entry in &mut self.paths {
-            if entry.path == path {
+            if path_eq(&entry.path, path, self.case_insensitive) {
                 entry.exists = true;
             }
         }
@@ -429,18 +1337,119 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_paths_from_yaml() {
-        let yaml_content = r#"
-paths:
-  - "./test_files/file1.txt"
-  - "./test_files/dir"
-  - "/absolute/path"
-other_field: "value"
-"#;
+    fn test_extraction_stops_at_depth_limit() {
+        // 100 levels, each with a path, stays below serde_json's own limit
+        let mut json = String::new();
+        for level in 0..100 {
+            json.push_str(&format!(r#"{{"p": "./level/{}", "next": "#, level));
+        }
+        json.push_str("null");
+        json.push_str(&"}".repeat(100));
+        let value: JsonValue = serde_json::from_str(&json).unwrap();
 
-        let paths = TargetFile::extract_paths_from_yaml(yaml_content).unwrap();
-        assert_eq!(paths.len(), 3);
-        assert!(paths.iter().any(|p| p.path == "./test_files/file1.txt"));
+        let mut paths = Vec::new();
+        assert!(TargetFile::collect_paths_from_json_value(
+            &value, 10, &mut paths
+        ));
+        assert_eq!(paths.len(), 10);
+        assert!(paths.iter().any(|entry| entry.path == "./level/9"));
+        assert!(!paths.iter().any(|entry| entry.path == "./level/10"));
+
+        let mut paths = Vec::new();
+        assert!(!TargetFile::collect_paths_from_json_value(
+            &value, 100, &mut paths
+        ));
+        assert_eq!(paths.len(), 100);
+
+        // The default limit applies when loading, without panicking
+        let paths = TargetFile::extract_paths_from_json(&json).unwrap();
+        assert_eq!(paths.len(), DEFAULT_MAX_EXTRACT_DEPTH);
+    }
+
+    #[test]
+    fn test_update_leaves_non_path_strings_equal_to_old_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let files = [
+            (
+                "t.json",
+                r#"{"paths": ["old/"], "description": "old"}"#,
+                r#"{"paths": ["new/"], "description": "old"}"#,
+            ),
+            (
+                "t.yaml",
+                "paths:\n  - old/\ndescription: old\n",
+                "paths:\n  - new/\ndescription: old\n",
+            ),
+            (
+                "t.toml",
+                "description = \"old\"\npaths = [\"old/\"]\n",
+                "description = \"old\"\npaths = [\"new/\"]\n",
+            ),
+        ];
+        for (name, content, expected) in files {
+            let file = temp_dir.path().join(name);
+            fs::write(&file, content).unwrap();
+            let mut target_file = TargetFile::new(file.clone()).unwrap();
+
+            // The description equals the directory up to its trailing slash
+            assert_eq!(
+                target_file.update_path("old/", "new/").unwrap(),
+                PathUpdate::Updated
+            );
+            assert_eq!(fs::read_to_string(&file).unwrap(), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_update_leaves_strings_beyond_depth_limit() {
+        let replacement = PathReplacement {
+            old: "./old",
+            new: "./new",
+            case_insensitive: false,
+            track_history: false,
+        };
+        // The note equals the old path but is nested deeper than extraction looks
+        let json = r#"{"paths": ["./old"], "meta": {"deep": {"note": "./old"}}}"#;
+        let yaml = "paths: [./old]\nmeta:\n  deep:\n    note: ./old\n";
+        let toml = "paths = [\"./old\"]\n[meta.deep]\nnote = \"./old\"\n";
+
+        let mut value: JsonValue = serde_json::from_str(json).unwrap();
+        let mut paths = Vec::new();
+        TargetFile::collect_paths_from_json_value(&value, 2, &mut paths);
+        assert_eq!(paths.len(), 1);
+        TargetFile::update_json_value(&mut value, &replacement, 2);
+        assert_eq!(value["paths"][0], "./new");
+        assert_eq!(value["meta"]["deep"]["note"], "./old");
+
+        let mut value: YamlValue = serde_yaml_ng::from_str(yaml).unwrap();
+        TargetFile::update_yaml_value(&mut value, &replacement, 2);
+        assert_eq!(value["paths"][0], "./new");
+        assert_eq!(value["meta"]["deep"]["note"], "./old");
+
+        let mut value: TomlValue = toml::from_str(toml).unwrap();
+        TargetFile::update_toml_value(&mut value, &replacement, 2);
+        assert_eq!(value["paths"][0].as_str(), Some("./new"));
+        assert_eq!(value["meta"]["deep"]["note"].as_str(), Some("./old"));
+
+        // Within the limit, both are recognized and both are updated
+        let mut value: JsonValue = serde_json::from_str(json).unwrap();
+        TargetFile::update_json_value(&mut value, &replacement, 4);
+        assert_eq!(value["meta"]["deep"]["note"], "./new");
+    }
+
+    #[test]
+    fn test_extract_paths_from_yaml() {
+        let yaml_content = r#"
+paths:
+  - "./test_files/file1.txt"
+  - "./test_files/dir"
+  - "/absolute/path"
+other_field: "value"
+"#;
+
+        let paths = TargetFile::extract_paths_from_yaml(yaml_content).unwrap();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().any(|p| p.path == "./test_files/file1.txt"));
         assert!(paths.iter().any(|p| p.path == "./test_files/dir"));
         assert!(paths.iter().any(|p| p.path == "/absolute/path"));
     }
@@ -467,13 +1476,69 @@ other_field = "value"
 /absolute/path,file,Absolute path
 "#;
 
-        let paths = TargetFile::extract_paths_from_csv(csv_content).unwrap();
+        let paths = TargetFile::extract_paths_from_csv(csv_content, None).unwrap();
         assert_eq!(paths.len(), 3);
         assert!(paths.iter().any(|p| p.path == "./test_files/file1.txt"));
         assert!(paths.iter().any(|p| p.path == "./test_files/dir"));
         assert!(paths.iter().any(|p| p.path == "/absolute/path"));
     }
 
+    #[test]
+    fn test_update_path_skips_no_op_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("test.json");
+        let initial_content = "[ \"./test_files/old_path\" ]";
+        fs::write(&json_file, initial_content).unwrap();
+        let modified = fs::metadata(&json_file).unwrap().modified().unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        let same = target_file
+            .update_path("./test_files/old_path", "./test_files/old_path")
+            .unwrap();
+        let unlisted = target_file
+            .update_path("./test_files/other", "./test_files/new_path")
+            .unwrap();
+
+        assert_eq!(same, PathUpdate::Unchanged);
+        assert_eq!(unlisted, PathUpdate::Unchanged);
+        assert_eq!(fs::read_to_string(&json_file).unwrap(), initial_content);
+        assert_eq!(
+            fs::metadata(&json_file).unwrap().modified().unwrap(),
+            modified
+        );
+        assert_eq!(
+            target_file
+                .update_path("./test_files/old_path", "./test_files/new_path")
+                .unwrap(),
+            PathUpdate::Updated
+        );
+    }
+
+    #[test]
+    fn test_update_path_skips_path_removed_from_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("test.json");
+        fs::write(&json_file, r#"["./a/old", "./a/keep"]"#).unwrap();
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+
+        // Edited by hand while nothing was watching the file
+        fs::write(&json_file, r#"["./a/keep"]"#).unwrap();
+        assert!(!target_file.contains_path("./a/old"));
+        assert!(target_file.contains_path("./a/keep"));
+
+        assert_eq!(
+            target_file.update_path("./a/old", "./a/new").unwrap(),
+            PathUpdate::MissingOnDisk
+        );
+        assert_eq!(fs::read_to_string(&json_file).unwrap(), r#"["./a/keep"]"#);
+        assert!(
+            target_file
+                .paths
+                .iter()
+                .any(|entry| entry.path == "./a/old")
+        );
+    }
+
     #[test]
     fn test_json_file_path_update() {
         let temp_dir = TempDir::new().unwrap();
@@ -493,6 +1558,353 @@ other_field = "value"
         assert!(!updated_content.contains("./test_files/old_path"));
     }
 
+    #[test]
+    fn test_history_entries_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let cases = [
+            (
+                "targets.json",
+                r#"{"assets": [{"path": "./a/one.png", "last_known_path": "./old/one.png"}, "./a/two.png"]}"#,
+            ),
+            (
+                "targets.yaml",
+                "assets:\n  - path: ./a/one.png\n    last_known_path: ./old/one.png\n  - ./a/two.png\n",
+            ),
+            (
+                "targets.toml",
+                "assets = [{ path = \"./a/one.png\", last_known_path = \"./old/one.png\" }, \"./a/two.png\"]\n",
+            ),
+        ];
+
+        for (name, content) in cases {
+            let file = temp_dir.path().join(name);
+            fs::write(&file, content).unwrap();
+
+            let mut target_file = TargetFile::new(file.clone()).unwrap();
+            assert_eq!(target_file.paths.len(), 2, "{name}");
+            assert_eq!(target_file.paths[0].path, "./a/one.png");
+            assert_eq!(
+                target_file.paths[0].last_known_path.as_deref(),
+                Some("./old/one.png")
+            );
+            assert_eq!(target_file.paths[1].last_known_path, None);
+
+            target_file.track_history = true;
+            target_file
+                .update_path("./a/one.png", "./b/one.png")
+                .unwrap();
+            target_file
+                .update_path("./a/two.png", "./b/two.png")
+                .unwrap();
+
+            let reloaded = TargetFile::new(file).unwrap();
+            let entries: Vec<(&str, Option<&str>)> = reloaded
+                .paths
+                .iter()
+                .map(|e| (e.path.as_str(), e.last_known_path.as_deref()))
+                .collect();
+            assert_eq!(
+                entries,
+                vec![
+                    ("./b/one.png", Some("./a/one.png")),
+                    ("./b/two.png", Some("./a/two.png")),
+                ],
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_string_array_extract_and_update() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("large.json");
+
+        let paths: Vec<String> = (0..20_000)
+            .map(|i| format!("./assets/file_{i}.png"))
+            .collect();
+        let mut content = serde_json::to_string_pretty(&paths).unwrap();
+        // An equal string inside an escaped quote must not be touched
+        content = content.replacen('[', r#"["note: \"./assets/file_7.png\"", "#, 1);
+        fs::write(&json_file, &content).unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        assert_eq!(target_file.paths.len(), 20_001);
+
+        target_file
+            .update_path("./assets/file_7.png", "./moved/file_7.png")
+            .unwrap();
+
+        let updated_content = fs::read_to_string(&json_file).unwrap();
+        let updated: Vec<String> = serde_json::from_str(&updated_content).unwrap();
+        assert_eq!(updated.len(), 20_001);
+        assert_eq!(updated[0], r#"note: "./assets/file_7.png""#);
+        assert_eq!(updated[8], "./moved/file_7.png");
+        assert_eq!(updated[9], "./assets/file_8.png");
+        assert_eq!(
+            updated_content.replace("./moved/file_7.png", "./assets/file_7.png"),
+            content,
+            "formatting is preserved"
+        );
+    }
+
+    #[test]
+    fn test_find_renamed_target() {
+        let temp_dir = TempDir::new().unwrap();
+        let configured = temp_dir.path().join("targets.json");
+        assert_eq!(find_renamed_target(&configured), None);
+
+        let renamed = temp_dir.path().join("targets.yaml");
+        fs::write(&renamed, "paths: []").unwrap();
+        assert_eq!(find_renamed_target(&configured), Some(renamed));
+
+        // An existing target file is never reported as renamed
+        fs::write(&configured, "[]").unwrap();
+        assert_eq!(find_renamed_target(&configured), None);
+    }
+
+    #[test]
+    fn test_scan_target_candidates_keeps_files_listing_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("config/nested")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        let files = [
+            ("assets.json", r#"["./img/logo.png"]"#),
+            ("config/nested/paths.yaml", "paths:\n  - ./docs/guide.md\n"),
+            ("config/files.csv", "path,type\n./src/main.rs,file\n"),
+            // No path-like strings
+            ("package.json", r#"{"name": "demo", "version": "1.0.0"}"#),
+            ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            // Unparsable, unsupported or hidden
+            ("broken.json", "[\"./a\""),
+            ("notes.txt", "./docs/guide.md"),
+            (".git/config.json", r#"["./hidden/path"]"#),
+        ];
+        for (name, content) in files {
+            fs::write(root.join(name), content).unwrap();
+        }
+
+        let found: Vec<PathBuf> = scan_target_candidates(root, DEFAULT_MAX_SCAN_ENTRIES)
+            .unwrap()
+            .into_iter()
+            .map(|target| target.path)
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                root.join("assets.json"),
+                root.join("config/files.csv"),
+                root.join("config/nested/paths.yaml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_target_candidates_stops_at_entry_cap() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for dir in ["a", "b"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            for i in 0..4 {
+                fs::write(root.join(dir).join(format!("{}.json", i)), r#"["./x/y"]"#).unwrap();
+            }
+        }
+
+        // Two directories holding four files each are ten entries
+        assert_eq!(scan_target_candidates(root, 10).unwrap().len(), 8);
+        assert!(scan_target_candidates(root, 9).is_err());
+        assert!(scan_target_candidates(root, 1).is_err());
+    }
+
+    #[test]
+    fn test_scan_target_candidates_rejects_unreadable_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("targets.json");
+        fs::write(&file, r#"["./x/y"]"#).unwrap();
+
+        assert!(scan_target_candidates(&temp_dir.path().join("missing"), 10).is_err());
+        assert!(scan_target_candidates(&file, 10).is_err());
+    }
+
+    #[test]
+    fn test_trailing_slash_entries_match_and_keep_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("dirs.json");
+        fs::write(&json_file, r#"["./src/", "./src/main.rs", "./docs"]"#).unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file.update_path("./src", "./lib").unwrap();
+        target_file.update_path("./docs/", "./guide/").unwrap();
+
+        let value: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&json_file).unwrap()).unwrap();
+        // Only exact entries are rewritten here; `./src/main.rs` is handled by
+        // the manager as a path below the moved directory
+        assert_eq!(
+            value,
+            serde_json::json!(["./lib/", "./src/main.rs", "./guide"])
+        );
+        assert_eq!(target_file.paths[0].path, "./lib/");
+        assert_eq!(trim_trailing_slash("/"), "/");
+        assert_eq!(trim_trailing_slash("src//"), "src");
+    }
+
+    #[test]
+    fn test_bare_strings_stay_bare_without_track_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("test.json");
+        fs::write(&json_file, r#"["./a/one.png"]"#).unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file
+            .update_path("./a/one.png", "./b/one.png")
+            .unwrap();
+
+        let value: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&json_file).unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!(["./b/one.png"]));
+    }
+
+    #[test]
+    fn test_format_sorts_paths_and_is_idempotent() {
+        let temp_dir = TempDir::new().unwrap();
+        let cases = [
+            (
+                "t.json",
+                r#"{"name": "demo", "paths": ["./b/x", "plain", "./a/y"]}"#,
+            ),
+            ("t.yaml", "name: demo\npaths:\n- ./b/x\n- plain\n- ./a/y\n"),
+            (
+                "t.toml",
+                "name = \"demo\"\npaths = [\"./b/x\", \"plain\", \"./a/y\"]\n",
+            ),
+            ("t.csv", "path,note\n./b/x,second\n./a/y,first\n"),
+        ];
+
+        for (name, content) in cases {
+            let file_path = temp_dir.path().join(name);
+            fs::write(&file_path, content).unwrap();
+            let target = TargetFile::new(file_path.clone()).unwrap();
+
+            assert_eq!(
+                target.format_in_place().unwrap(),
+                PathUpdate::Updated,
+                "{name} was not reformatted"
+            );
+            let once = fs::read_to_string(&file_path).unwrap();
+            assert_eq!(
+                target.format_in_place().unwrap(),
+                PathUpdate::Unchanged,
+                "{name} is not idempotent"
+            );
+            assert_eq!(fs::read_to_string(&file_path).unwrap(), once);
+
+            // Paths are sorted, other content is kept
+            assert!(once.find("./a/y").unwrap() < once.find("./b/x").unwrap());
+            if name != "t.csv" {
+                assert!(once.contains("demo") && once.contains("plain"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_keeps_key_order_and_refuses_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        for (name, content) in [
+            ("t.json", r#"{"zeta": 1, "alpha": ["./b", "./a"]}"#),
+            ("t.toml", "zeta = 1\nalpha = [\"./b\", \"./a\"]\n"),
+            ("t.yaml", "zeta: 1\nalpha:\n- ./b\n- ./a\n"),
+        ] {
+            let file_path = temp_dir.path().join(name);
+            fs::write(&file_path, content).unwrap();
+            let formatted = TargetFile::new(file_path)
+                .unwrap()
+                .formatted_content()
+                .unwrap();
+            assert!(
+                formatted.find("zeta").unwrap() < formatted.find("alpha").unwrap(),
+                "{name} keys were reordered"
+            );
+            assert!(formatted.find("./a").unwrap() < formatted.find("./b").unwrap());
+        }
+
+        for (name, content) in [
+            (
+                "c.toml",
+                "# assets\nzeta = 1\nalpha = [\"./b\", \"./a\"] # sorted\n",
+            ),
+            ("c.yaml", "zeta: 1 # first\nalpha:\n- ./b\n- ./a\n"),
+        ] {
+            let file_path = temp_dir.path().join(name);
+            fs::write(&file_path, content).unwrap();
+            let target = TargetFile::new(file_path.clone()).unwrap();
+            assert!(target.format_in_place().is_err(), "{name} was formatted");
+            assert_eq!(fs::read_to_string(&file_path).unwrap(), content);
+        }
+    }
+
+    #[test]
+    fn test_has_hash_comment() {
+        assert!(has_hash_comment("# top\na = 1\n"));
+        assert!(has_hash_comment("a: 1  # trailing\n"));
+        assert!(!has_hash_comment("a = \"./x#y\"\nb = 'c # d'\n"));
+        assert!(!has_hash_comment("a: \"say \\\" # not a comment\"\n"));
+        assert!(!has_hash_comment("a: ./x#y\n"));
+        assert!(has_hash_comment("a: don't # note\n"));
+    }
+
+    #[test]
+    fn test_format_leaves_unrecognized_elements_in_place() {
+        let mut items = vec!["./b", "keep", "./a"];
+        TargetFile::sort_recognized(&mut items, |item| {
+            TargetFile::looks_like_path(item).then(|| item.to_string())
+        });
+        assert_eq!(items, vec!["./a", "keep", "./b"]);
+    }
+
+    #[test]
+    fn test_convert_between_every_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = vec![
+            "./assets/a.png".to_string(),
+            "./src/main.rs".to_string(),
+            "/opt/data/".to_string(),
+        ];
+
+        for from in TargetFileFormat::ALL {
+            let src = temp_dir.path().join(format!("src.{}", from.name()));
+            fs::write(&src, from.render_paths(&paths).unwrap()).unwrap();
+
+            for to in TargetFileFormat::ALL {
+                let dst = temp_dir
+                    .path()
+                    .join(format!("{}-to.{}", from.name(), to.name()));
+                assert_eq!(convert_target_file(&src, &dst).unwrap(), paths.len());
+
+                let mut converted: Vec<String> = TargetFile::new(dst)
+                    .unwrap()
+                    .paths
+                    .into_iter()
+                    .map(|entry| entry.path)
+                    .collect();
+                converted.sort();
+                assert_eq!(converted, paths, "{} -> {}", from.name(), to.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_refuses_to_overwrite() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("targets.json");
+        let dst = temp_dir.path().join("targets.yaml");
+        fs::write(&src, r#"["./a/b"]"#).unwrap();
+        fs::write(&dst, "keep: me\n").unwrap();
+
+        assert!(convert_target_file(&src, &dst).is_err());
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "keep: me\n");
+    }
+
     #[test]
     fn test_yaml_file_path_update() {
         let temp_dir = TempDir::new().unwrap();
@@ -518,6 +1930,181 @@ other_field: "value"
         assert!(updated_content.contains("other_field")); // YAML formatting might change quotes
     }
 
+    #[test]
+    fn test_bom_prefixed_target_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("bom.json");
+        fs::write(
+            &json_file,
+            "\u{feff}[\"./assets/old.png\", \"./assets/other.png\"]",
+        )
+        .unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        let paths: Vec<&str> = target_file.paths.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["./assets/old.png", "./assets/other.png"]);
+
+        target_file
+            .update_path("./assets/old.png", "./assets/new.png")
+            .unwrap();
+        let written = fs::read(&json_file).unwrap();
+        assert!(written.starts_with(&[0xEF, 0xBB, 0xBF]));
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "\u{feff}[\"./assets/new.png\", \"./assets/other.png\"]"
+        );
+
+        // Files without a BOM don't gain one
+        let plain = temp_dir.path().join("plain.yaml");
+        fs::write(&plain, "- ./assets/old.png\n").unwrap();
+        let mut target_file = TargetFile::new(plain.clone()).unwrap();
+        target_file
+            .update_path("./assets/old.png", "./assets/new.png")
+            .unwrap();
+        assert_eq!(fs::read_to_string(&plain).unwrap(), "- ./assets/new.png\n");
+    }
+
+    #[test]
+    fn test_yaml_update_keeps_quoting_style() {
+        let temp_dir = TempDir::new().unwrap();
+        let yaml_file = temp_dir.path().join("styles.yaml");
+        let content = "# quoting is intentional\nsingle: './assets/old.png'\ndouble: \"./assets/old.png\"\nplain: ./assets/old.png # note\nlist:\n  - './assets/old.png'\n  - \"./assets/keep.png\"\n";
+        fs::write(&yaml_file, content).unwrap();
+
+        let mut target_file = TargetFile::new(yaml_file.clone()).unwrap();
+        target_file
+            .update_path("./assets/old.png", "./assets/new.png")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&yaml_file).unwrap(),
+            content.replace("old.png", "new.png")
+        );
+    }
+
+    #[test]
+    fn test_json_update_keeps_escaping_or_falls_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("nested.json");
+        let content = "{\n  \"b\": \"./x/old\",\n    \"a\": [\"./x/old\", \"./x/other\"]\n}\n";
+        fs::write(&json_file, content).unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file.update_path("./x/old", "./x/new").unwrap();
+        // Layout (including the odd indentation and key order) is kept
+        assert_eq!(
+            fs::read_to_string(&json_file).unwrap(),
+            content.replace("./x/old", "./x/new")
+        );
+
+        // An escaped spelling can't be edited in place; the value is still updated
+        fs::write(&json_file, r#"{"p": "./x\/old"}"#).unwrap();
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file.update_path("./x/old", "./x/new").unwrap();
+        let value: JsonValue =
+            serde_json::from_str(&fs::read_to_string(&json_file).unwrap()).unwrap();
+        assert_eq!(value["p"], "./x/new");
+    }
+
+    #[test]
+    fn test_update_path_preserves_element_order() {
+        let temp_dir = TempDir::new().unwrap();
+        // The middle entry is written with an escape in the second document,
+        // which forces the structural rewrite; CSV is only ever edited by line
+        let cases = [
+            (
+                "list.json",
+                "[\n  \"./x/a\",\n  \"./x/b\",\n  \"./x/c\"\n]\n",
+                Some("{\"paths\": [\"./x/a\", \"./x\\/b\", \"./x/c\"]}"),
+            ),
+            (
+                "list.yaml",
+                "paths:\n  - ./x/a\n  - ./x/b\n  - ./x/c\n",
+                Some("paths:\n  - ./x/a\n  - \"./x\\x2Fb\"\n  - ./x/c\n"),
+            ),
+            (
+                "list.toml",
+                "paths = [\"./x/a\", \"./x/b\", \"./x/c\"]\n",
+                Some("paths = [\"./x/a\", \"./x\\u002Fb\", \"./x/c\"]\n"),
+            ),
+            (
+                "list.csv",
+                "path,type\n./x/a,file\n./x/b,file\n./x/c,file\n",
+                None,
+            ),
+        ];
+
+        for (name, in_place, escaped) in cases {
+            let file = temp_dir.path().join(name);
+            for content in [Some(in_place), escaped].into_iter().flatten() {
+                fs::write(&file, content).unwrap();
+                let mut target_file = TargetFile::new(file.clone()).unwrap();
+                assert_eq!(
+                    target_file.update_path("./x/b", "./x/new").unwrap(),
+                    PathUpdate::Updated,
+                    "{}: {}",
+                    name,
+                    content
+                );
+
+                let reloaded = TargetFile::new(file.clone()).unwrap();
+                let paths: Vec<&str> = reloaded.paths.iter().map(|e| e.path.as_str()).collect();
+                assert_eq!(
+                    paths,
+                    ["./x/a", "./x/new", "./x/c"],
+                    "{}: {}",
+                    name,
+                    content
+                );
+            }
+            // The minimal edit changes nothing but the updated entry
+            fs::write(&file, in_place).unwrap();
+            let mut target_file = TargetFile::new(file.clone()).unwrap();
+            target_file.update_path("./x/b", "./x/new").unwrap();
+            assert_eq!(
+                fs::read_to_string(&file).unwrap(),
+                in_place.replace("./x/b", "./x/new"),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_unified_diff_of_json_update() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("targets.json");
+        let content = "[\n  \"./x/keep\",\n  \"./x/old\"\n]\n";
+        fs::write(&json_file, content).unwrap();
+        let target_file = TargetFile::new(json_file.clone()).unwrap();
+        let replacement = PathReplacement {
+            old: "./x/old",
+            new: "./x/new",
+            case_insensitive: false,
+            track_history: false,
+        };
+        let updated = target_file
+            .update_json_content(content, &replacement)
+            .unwrap();
+
+        let diff = unified_diff(&json_file, content, &updated);
+        let lines: Vec<&str> = diff.lines().collect();
+        assert!(lines.contains(&"-  \"./x/old\""), "{}", diff);
+        assert!(lines.contains(&"+  \"./x/new\""), "{}", diff);
+        // Unchanged lines are context, not edits
+        assert!(lines.contains(&"   \"./x/keep\","), "{}", diff);
+        assert!(diff.starts_with(&format!("--- {}", json_file.display())));
+    }
+
+    #[test]
+    fn test_replace_scalar_spans_only_whole_scalars() {
+        let replace = |text: &str| TargetFile::replace_scalar_spans(text, "./a", "./b");
+        assert_eq!(replace("k: ./a\n"), "k: ./b\n");
+        assert_eq!(replace("k: ./a/c\n"), "k: ./a/c\n");
+        assert_eq!(replace("k: 'x./a'\n"), "k: 'x./a'\n");
+        assert_eq!(replace("[\"./a\", ./a]"), "[\"./b\", ./b]");
+    }
+
     #[test]
     fn test_toml_file_path_update() {
         let temp_dir = TempDir::new().unwrap();
@@ -563,6 +2150,175 @@ other_field = "value"
         assert!(updated_content.contains("path,type,description"));
     }
 
+    #[test]
+    fn test_csv_comments_are_skipped_and_preserved() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_file = temp_dir.path().join("test.csv");
+
+        let initial_content =
+            "path,type\n# ./test_files/commented,file\n./test_files/old_path,file\n";
+        fs::write(&csv_file, initial_content).unwrap();
+
+        // Without a comment char the comment line is read as data
+        let plain = TargetFile::new(csv_file.clone()).unwrap();
+        assert_eq!(plain.paths.len(), 2);
+
+        let mut target_file = TargetFile::with_csv_comment(csv_file.clone(), Some(b'#')).unwrap();
+        assert_eq!(target_file.paths.len(), 1);
+        assert_eq!(target_file.paths[0].path, "./test_files/old_path");
+
+        target_file
+            .update_path("./test_files/old_path", "./test_files/new_path")
+            .unwrap();
+
+        let updated_content = fs::read_to_string(&csv_file).unwrap();
+        assert!(updated_content.contains("# ./test_files/commented,file"));
+        assert!(updated_content.contains("./test_files/new_path,file"));
+        assert!(!updated_content.contains("./test_files/old_path"));
+    }
+
+    #[test]
+    fn test_rewrite_rejects_unparseable_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("test.json");
+        let initial_content = r#"["./test_files/old_path"]"#;
+        fs::write(&json_file, initial_content).unwrap();
+
+        let target_file = TargetFile::new(json_file.clone()).unwrap();
+        let result = target_file.rewrite_content(|content| Ok(content.replace(']', "")));
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&json_file).unwrap(), initial_content);
+    }
+
+    #[test]
+    fn test_validate_content_per_format() {
+        assert!(TargetFile::validate_content(&TargetFileFormat::Json, "[\"a/b\"]", None).is_ok());
+        assert!(TargetFile::validate_content(&TargetFileFormat::Json, "[\"a/b\"", None).is_err());
+        assert!(
+            TargetFile::validate_content(&TargetFileFormat::Yaml, "paths: [a/b]", None).is_ok()
+        );
+        assert!(
+            TargetFile::validate_content(&TargetFileFormat::Yaml, "paths: [a/b", None).is_err()
+        );
+        assert!(
+            TargetFile::validate_content(&TargetFileFormat::Toml, "paths = [\"a/b\"]", None)
+                .is_ok()
+        );
+        assert!(TargetFile::validate_content(&TargetFileFormat::Toml, "paths = [", None).is_err());
+        assert!(
+            TargetFile::validate_content(&TargetFileFormat::Csv, "path,type\na/b,file\n", None)
+                .is_ok()
+        );
+        assert!(
+            TargetFile::validate_content(
+                &TargetFileFormat::Csv,
+                "path,type\na/b,file,extra\n",
+                None
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rebase_path_uses_new_casing() {
+        assert_eq!(
+            rebase_path("./Src/Main.rs", "./src/main.rs", "./src/lib.rs", true),
+            Some("./src/lib.rs".to_string())
+        );
+        assert_eq!(
+            rebase_path("./Src/Main/a.rs", "./src/main", "./src/core", true),
+            Some("./src/core/a.rs".to_string())
+        );
+        // A case-only rename takes the new spelling, below it too
+        assert_eq!(
+            rebase_path("./Docs", "./Docs", "./docs", true),
+            Some("./docs".to_string())
+        );
+        assert_eq!(
+            rebase_path("./Docs/Guide.md", "./Docs", "./docs", true),
+            Some("./docs/Guide.md".to_string())
+        );
+        assert_eq!(
+            rebase_path("./Src/Main.rs", "./src/main.rs", "./src/lib.rs", false),
+            None
+        );
+        assert_eq!(
+            rebase_path("./src/main.rs", "./src/main.rs", "./src/lib.rs", false),
+            Some("./src/lib.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_glob_characters_are_literal() {
+        assert_eq!(
+            rebase_path("./art/[draft]/*.png", "./art/[draft]", "./art/final", false),
+            Some("./art/final/*.png".to_string())
+        );
+        // Neither `[...]` nor `*` match anything but themselves
+        assert_eq!(
+            rebase_path("./art/d/x.png", "./art/[draft]", "./b", false),
+            None
+        );
+        assert_eq!(rebase_path("./art/ab.png", "./art/a*", "./b", false), None);
+
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("globs.json");
+        fs::write(&json_file, r#"["./art/[draft]/a*.png", "./art/d/ab.png"]"#).unwrap();
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file
+            .update_path("./art/[draft]/a*.png", "./art/a.png")
+            .unwrap();
+        let paths: Vec<String> =
+            serde_json::from_str(&fs::read_to_string(&json_file).unwrap()).unwrap();
+        assert_eq!(paths, vec!["./art/a.png", "./art/d/ab.png"]);
+
+        let yaml_file = temp_dir.path().join("globs.yaml");
+        fs::write(&yaml_file, "- './art/[draft]/a*.png'\n- ./art/d/ab.png\n").unwrap();
+        let mut target_file = TargetFile::new(yaml_file.clone()).unwrap();
+        target_file
+            .update_path("./art/[draft]/a*.png", "./art/[final]/a*.png")
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&yaml_file).unwrap(),
+            "- './art/[final]/a*.png'\n- ./art/d/ab.png\n"
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_update_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let json_file = temp_dir.path().join("test.json");
+        fs::write(&json_file, r#"["./Src/Main.rs", "./src/other.rs"]"#).unwrap();
+
+        let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+        target_file.case_insensitive = true;
+        target_file
+            .update_path("./src/main.rs", "./src/lib.rs")
+            .unwrap();
+
+        let content = fs::read_to_string(&json_file).unwrap();
+        assert!(content.contains("./src/lib.rs"));
+        assert!(content.contains("./src/other.rs"));
+        assert!(!content.contains("Main.rs"));
+        assert!(target_file.paths.iter().any(|p| p.path == "./src/lib.rs"));
+    }
+
+    #[test]
+    fn test_case_sensitive_update_path_ignores_other_casing() {
+        let temp_dir = TempDir::new().unwrap();
+        let csv_file = temp_dir.path().join("test.csv");
+        fs::write(&csv_file, "path,type\n./Src/Main.rs,file\n").unwrap();
+
+        let mut target_file = TargetFile::new(csv_file.clone()).unwrap();
+        target_file
+            .update_path("./src/main.rs", "./src/lib.rs")
+            .unwrap();
+
+        let content = fs::read_to_string(&csv_file).unwrap();
+        assert!(content.contains("./Src/Main.rs"));
+    }
+
     #[test]
     fn test_complex_path_scenarios() {
         let temp_dir = TempDir::new().unwrap();
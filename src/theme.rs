@@ -0,0 +1,105 @@
+use owo_colors::{AnsiColors, FgDynColorDisplay, OwoColorize};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Process-wide [`Theme`] for terminal output
+static THEME: AtomicU8 = AtomicU8::new(Theme::Default as u8);
+
+/// Palette used for terminal output
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    Default,
+    /// Blue and magenta instead of green and red, for red-green color blindness
+    Colorblind,
+    /// The terminal's own foreground color for everything
+    Plain,
+}
+
+/// What a piece of output means, which decides its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Success,
+    Warn,
+    Error,
+    Info,
+    Heading,
+    Path,
+    /// Emphasized plain text
+    Text,
+    /// Secondary details
+    Muted,
+}
+
+impl Theme {
+    pub fn color(self, role: Role) -> AnsiColors {
+        match (self, role) {
+            (Self::Plain, _) => AnsiColors::Default,
+            (Self::Default, Role::Success) => AnsiColors::Green,
+            (Self::Default, Role::Warn) => AnsiColors::Yellow,
+            (Self::Default, Role::Error) => AnsiColors::Red,
+            (Self::Default, Role::Info) => AnsiColors::Blue,
+            (Self::Default, Role::Heading) => AnsiColors::BrightBlue,
+            (Self::Colorblind, Role::Success) => AnsiColors::BrightBlue,
+            (Self::Colorblind, Role::Warn) => AnsiColors::BrightYellow,
+            (Self::Colorblind, Role::Error) => AnsiColors::BrightMagenta,
+            (Self::Colorblind, Role::Info) => AnsiColors::Blue,
+            (Self::Colorblind, Role::Heading) => AnsiColors::BrightWhite,
+            (_, Role::Path) => AnsiColors::Cyan,
+            (_, Role::Text) => AnsiColors::BrightWhite,
+            (_, Role::Muted) => AnsiColors::BrightBlack,
+        }
+    }
+}
+
+/// Select the output theme for the whole process
+pub fn set_theme(theme: Theme) {
+    THEME.store(theme as u8, Ordering::SeqCst);
+}
+
+pub fn theme() -> Theme {
+    match THEME.load(Ordering::SeqCst) {
+        value if value == Theme::Colorblind as u8 => Theme::Colorblind,
+        value if value == Theme::Plain as u8 => Theme::Plain,
+        _ => Theme::Default,
+    }
+}
+
+/// Color output by its [`Role`] in the current theme
+pub trait Themed: OwoColorize {
+    fn themed(&self, role: Role) -> FgDynColorDisplay<'_, AnsiColors, Self> {
+        self.color(theme().color(role))
+    }
+}
+
+impl<D> Themed for D {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_maps_roles_to_colors() {
+        assert_eq!(Theme::Default.color(Role::Success), AnsiColors::Green);
+        assert_eq!(Theme::Default.color(Role::Error), AnsiColors::Red);
+        assert_eq!(
+            Theme::Colorblind.color(Role::Success),
+            AnsiColors::BrightBlue
+        );
+        assert_eq!(
+            Theme::Colorblind.color(Role::Error),
+            AnsiColors::BrightMagenta
+        );
+        assert_eq!(Theme::Plain.color(Role::Warn), AnsiColors::Default);
+
+        let parsed: Theme = serde_yaml_ng::from_str("colorblind").unwrap();
+        assert_eq!(parsed, Theme::Colorblind);
+    }
+
+    #[test]
+    fn test_themed_uses_theme_color() {
+        let expected = "ok".color(Theme::Default.color(Role::Success)).to_string();
+        assert_eq!("ok".themed(Role::Success).to_string(), expected);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::i18n::tf;
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Process-wide switch for the `--no-write` sandbox mode
+static NO_WRITE: AtomicBool = AtomicBool::new(false);
+
+/// Process-wide switch for [`WriteStrategy::InPlace`]
+static IN_PLACE: AtomicBool = AtomicBool::new(false);
+
+/// How files are replaced when written
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteStrategy {
+    /// Write a temporary file next to the target, then rename it over the target
+    #[default]
+    AtomicRename,
+    /// Overwrite the target directly, for file systems without atomic rename
+    InPlace,
+}
+
+/// Process-wide [`LineEnding`] for config and target file writes
+static LINE_ENDING: AtomicU8 = AtomicU8::new(LineEnding::Native as u8);
+
+/// Newline style of written config and target files
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    /// CRLF on Windows, LF elsewhere
+    #[default]
+    Native,
+}
+
+impl LineEnding {
+    /// The newline sequence written for this style
+    pub fn newline(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+            Self::Native if cfg!(windows) => "\r\n",
+            Self::Native => "\n",
+        }
+    }
+
+    /// `text` with every line ending, LF or CRLF, converted to this style
+    pub fn apply(self, text: &str) -> String {
+        let newline = self.newline();
+        let mut converted = String::with_capacity(text.len());
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(line) => {
+                    converted.push_str(line.strip_suffix('\r').unwrap_or(line));
+                    converted.push_str(newline);
+                }
+                None => converted.push_str(line),
+            }
+        }
+        converted
+    }
+}
+
+/// Select the line ending for the whole process
+pub fn set_line_ending(line_ending: LineEnding) {
+    LINE_ENDING.store(line_ending as u8, Ordering::SeqCst);
+}
+
+pub fn line_ending() -> LineEnding {
+    match LINE_ENDING.load(Ordering::SeqCst) {
+        value if value == LineEnding::Lf as u8 => LineEnding::Lf,
+        value if value == LineEnding::CrLf as u8 => LineEnding::CrLf,
+        _ => LineEnding::Native,
+    }
+}
+
+/// Suffix of the temporary files written by [`WriteStrategy::AtomicRename`]
+pub const TEMP_WRITE_SUFFIX: &str = ".chaser-tmp";
+
+/// Whether `path` is a temporary file of an atomic write, whose events the
+/// monitor should not report
+pub fn is_temp_write_path(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(TEMP_WRITE_SUFFIX))
+}
+
+/// Select the write strategy for the whole process
+pub fn set_write_strategy(strategy: WriteStrategy) {
+    IN_PLACE.store(strategy == WriteStrategy::InPlace, Ordering::SeqCst);
+}
+
+pub fn write_strategy() -> WriteStrategy {
+    if IN_PLACE.load(Ordering::SeqCst) {
+        WriteStrategy::InPlace
+    } else {
+        WriteStrategy::AtomicRename
+    }
+}
+
+/// Enable or disable no-write mode for the whole process
+pub fn set_no_write(enabled: bool) {
+    NO_WRITE.store(enabled, Ordering::SeqCst);
+}
+
+/// Check whether file-mutating operations are currently disabled
+pub fn is_no_write() -> bool {
+    NO_WRITE.load(Ordering::SeqCst)
+}
+
+/// Write a file, or only report the intended write when no-write mode is on
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let contents = contents.as_ref();
+    if is_no_write() {
+        println!(
+            "{}",
+            tf(
+                "msg_no_write_file",
+                &[&path.display().to_string(), &contents.len().to_string()]
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    write_file_with(path, contents, write_strategy())
+}
+
+/// Write a file with an explicit strategy, ignoring no-write mode
+pub fn write_file_with(path: &Path, contents: &[u8], strategy: WriteStrategy) -> Result<()> {
+    match strategy {
+        WriteStrategy::InPlace => fs::write(path, contents)?,
+        WriteStrategy::AtomicRename => {
+            // Renaming over a symlink would replace the link itself, so write next to the real file
+            let real_path = if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+            {
+                match fs::canonicalize(path) {
+                    Ok(real_path) => real_path,
+                    Err(_) => return write_file_with(path, contents, WriteStrategy::InPlace),
+                }
+            } else {
+                path.to_path_buf()
+            };
+            // A rename would detach this name from the file's other hard links
+            if has_other_links(&real_path) {
+                return write_file_with(&real_path, contents, WriteStrategy::InPlace);
+            }
+            let path = real_path.as_path();
+
+            let file_name = path
+                .file_name()
+                .with_context(|| format!("Not a file path: {}", path.display()))?;
+            let temp_path = path.with_file_name(format!(
+                ".{}{}",
+                file_name.to_string_lossy(),
+                TEMP_WRITE_SUFFIX
+            ));
+
+            let written = fs::write(&temp_path, contents).and_then(|()| {
+                if let Ok(metadata) = fs::metadata(path) {
+                    fs::set_permissions(&temp_path, metadata.permissions())?;
+                }
+                fs::rename(&temp_path, path)
+            });
+            if written.is_err() {
+                let _ = fs::remove_file(&temp_path);
+            }
+            written.with_context(|| format!("Failed to replace {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Check whether a file is shared with other hard links
+#[cfg(unix)]
+fn has_other_links(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path).is_ok_and(|metadata| metadata.nlink() > 1)
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_path: &Path) -> bool {
+    false
+}
+
+/// Create a directory tree, or only report it when no-write mode is on
+pub fn create_dir_all(path: &Path) -> Result<()> {
+    if is_no_write() {
+        println!(
+            "{}",
+            tf("msg_no_write_dir", &[&path.display().to_string()]).yellow()
+        );
+        return Ok(());
+    }
+
+    fs::create_dir_all(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_strategies_produce_same_content() {
+        let temp_dir = TempDir::new().unwrap();
+        for strategy in [WriteStrategy::AtomicRename, WriteStrategy::InPlace] {
+            let path = temp_dir.path().join(format!("{:?}.json", strategy));
+            fs::write(&path, r#"["./old"]"#).unwrap();
+
+            write_file_with(&path, br#"["./new"]"#, strategy).unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), r#"["./new"]"#);
+        }
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_atomic_write_keeps_symlinks_and_hard_links() {
+        let temp_dir = TempDir::new().unwrap();
+        let real = temp_dir.path().join("real.json");
+        let link = temp_dir.path().join("link.json");
+        let hard = temp_dir.path().join("hard.json");
+        fs::write(&real, r#"["./old"]"#).unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        fs::hard_link(&real, &hard).unwrap();
+
+        write_file_with(&link, br#"["./new"]"#, WriteStrategy::AtomicRename).unwrap();
+        assert!(
+            fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink()
+        );
+        assert_eq!(fs::read_to_string(&real).unwrap(), r#"["./new"]"#);
+        assert_eq!(fs::read_to_string(&hard).unwrap(), r#"["./new"]"#);
+
+        write_file_with(&hard, br#"["./newer"]"#, WriteStrategy::AtomicRename).unwrap();
+        assert_eq!(fs::read_to_string(&real).unwrap(), r#"["./newer"]"#);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 3);
+    }
+
+    #[test]
+    fn test_line_ending_apply() {
+        let mixed = "a: 1\r\nb: 2\nc: 3";
+        assert_eq!(LineEnding::Lf.apply(mixed), "a: 1\nb: 2\nc: 3");
+        assert_eq!(LineEnding::CrLf.apply(mixed), "a: 1\r\nb: 2\r\nc: 3");
+        assert_eq!(
+            LineEnding::Native.apply("x\n"),
+            format!("x{}", LineEnding::Native.newline())
+        );
+    }
+
+    #[test]
+    fn test_is_temp_write_path() {
+        assert!(is_temp_write_path(Path::new("/w/.targets.json.chaser-tmp")));
+        assert!(!is_temp_write_path(Path::new("/w/targets.json")));
+        assert!(!is_temp_write_path(Path::new("/w/notes.chaser-tmp")));
+    }
+}
@@ -162,21 +162,21 @@ fn test_config_operations_integration() {
 #[test]
 fn test_cli_parsing_integration() {
     let command = setup_test_cli();
-    let matches = command.try_get_matches_from(&["chaser"]).unwrap();
+    let matches = command.try_get_matches_from(["chaser"]).unwrap();
     assert!(cli::parse_command(&matches).is_none());
 
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "add", "/new/path"])
+        .try_get_matches_from(["chaser", "add", "/new/path"])
         .unwrap();
     match cli::parse_command(&matches) {
-        Some(cli::Commands::Add { path }) => assert_eq!(path, "/new/path"),
+        Some(cli::Commands::Add { path, .. }) => assert_eq!(path, "/new/path"),
         _ => panic!("Expected Add command"),
     }
 
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "remove", "/old/path"])
+        .try_get_matches_from(["chaser", "remove", "/old/path"])
         .unwrap();
     match cli::parse_command(&matches) {
         Some(cli::Commands::Remove { path }) => assert_eq!(path, "/old/path"),
@@ -184,14 +184,14 @@ fn test_cli_parsing_integration() {
     }
 
     let command = setup_test_cli();
-    let matches = command.try_get_matches_from(&["chaser", "list"]).unwrap();
+    let matches = command.try_get_matches_from(["chaser", "list"]).unwrap();
     assert!(matches!(
         cli::parse_command(&matches),
         Some(cli::Commands::List)
     ));
 
     let command = setup_test_cli();
-    let matches = command.try_get_matches_from(&["chaser", "config"]).unwrap();
+    let matches = command.try_get_matches_from(["chaser", "config"]).unwrap();
     assert!(matches!(
         cli::parse_command(&matches),
         Some(cli::Commands::Config)
@@ -199,7 +199,7 @@ fn test_cli_parsing_integration() {
 
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "recursive", "false"])
+        .try_get_matches_from(["chaser", "recursive", "false"])
         .unwrap();
     match cli::parse_command(&matches) {
         Some(cli::Commands::Recursive { enabled }) => assert_eq!(enabled, "false"),
@@ -208,7 +208,7 @@ fn test_cli_parsing_integration() {
 
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "ignore", "*.backup"])
+        .try_get_matches_from(["chaser", "ignore", "*.backup"])
         .unwrap();
     match cli::parse_command(&matches) {
         Some(cli::Commands::Ignore { pattern }) => assert_eq!(pattern, "*.backup"),
@@ -216,7 +216,7 @@ fn test_cli_parsing_integration() {
     }
 
     let command = setup_test_cli();
-    let matches = command.try_get_matches_from(&["chaser", "reset"]).unwrap();
+    let matches = command.try_get_matches_from(["chaser", "reset"]).unwrap();
     assert!(matches!(
         cli::parse_command(&matches),
         Some(cli::Commands::Reset)
@@ -224,7 +224,7 @@ fn test_cli_parsing_integration() {
 
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "lang", "zh-cn"])
+        .try_get_matches_from(["chaser", "lang", "zh-cn"])
         .unwrap();
     match cli::parse_command(&matches) {
         Some(cli::Commands::Lang { language }) => assert_eq!(language, "zh-cn"),
@@ -238,11 +238,13 @@ fn test_config_persistence_integration() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("config.yaml");
 
-    let mut original_config = Config::default();
-    original_config.watch_paths = vec!["/test1".to_string(), "/test2".to_string()];
-    original_config.recursive = false;
-    original_config.ignore_patterns = vec!["*.test".to_string()];
-    original_config.language = Some("zh-cn".to_string());
+    let original_config = Config {
+        watch_paths: vec!["/test1".to_string(), "/test2".to_string()],
+        recursive: false,
+        ignore_patterns: vec!["*.test".to_string()],
+        language: Some("zh-cn".to_string()),
+        ..Default::default()
+    };
 
     let yaml_content = serde_yaml_ng::to_string(&original_config).unwrap();
     fs::write(&config_path, yaml_content).unwrap();
@@ -252,7 +254,7 @@ fn test_config_persistence_integration() {
 
     assert_eq!(original_config, loaded_config);
     assert_eq!(loaded_config.watch_paths, vec!["/test1", "/test2"]);
-    assert_eq!(loaded_config.recursive, false);
+    assert!(!loaded_config.recursive);
     assert_eq!(loaded_config.ignore_patterns, vec!["*.test"]);
     assert_eq!(loaded_config.language, Some("zh-cn".to_string()));
 }
@@ -273,7 +275,7 @@ fn test_recursive_option_parsing() {
     for (input, expected) in test_cases {
         let command = setup_test_cli();
         let matches = command
-            .try_get_matches_from(&["chaser", "recursive", input])
+            .try_get_matches_from(["chaser", "recursive", input])
             .unwrap();
         match cli::parse_command(&matches) {
             Some(cli::Commands::Recursive { enabled }) => {
@@ -292,27 +294,27 @@ fn test_recursive_option_parsing() {
 #[test]
 fn test_error_handling() {
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "invalid_command"]);
+    let result = command.try_get_matches_from(["chaser", "invalid_command"]);
     assert!(result.is_err());
 
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "add"]);
+    let result = command.try_get_matches_from(["chaser", "add"]);
     assert!(result.is_err());
 
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "remove"]);
+    let result = command.try_get_matches_from(["chaser", "remove"]);
     assert!(result.is_err());
 
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "recursive"]);
+    let result = command.try_get_matches_from(["chaser", "recursive"]);
     assert!(result.is_err());
 
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "ignore"]);
+    let result = command.try_get_matches_from(["chaser", "ignore"]);
     assert!(result.is_err());
 
     let command = setup_test_cli();
-    let result = command.try_get_matches_from(&["chaser", "lang"]);
+    let result = command.try_get_matches_from(["chaser", "lang"]);
     assert!(result.is_err());
 }
 
@@ -351,17 +353,17 @@ fn test_new_commands() {
     // Test add-target command
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "add-target", "config.json"])
+        .try_get_matches_from(["chaser", "add-target", "config.json"])
         .unwrap();
     match cli::parse_command(&matches) {
-        Some(cli::Commands::AddTarget { file }) => assert_eq!(file, "config.json"),
+        Some(cli::Commands::AddTarget { file, .. }) => assert_eq!(file, "config.json"),
         _ => panic!("Expected AddTarget command"),
     }
 
     // Test remove-target command
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "remove-target", "config.json"])
+        .try_get_matches_from(["chaser", "remove-target", "config.json"])
         .unwrap();
     match cli::parse_command(&matches) {
         Some(cli::Commands::RemoveTarget { file }) => assert_eq!(file, "config.json"),
@@ -371,7 +373,7 @@ fn test_new_commands() {
     // Test list-targets command
     let command = setup_test_cli();
     let matches = command
-        .try_get_matches_from(&["chaser", "list-targets"])
+        .try_get_matches_from(["chaser", "list-targets"])
         .unwrap();
     assert!(matches!(
         cli::parse_command(&matches),
@@ -380,9 +382,687 @@ fn test_new_commands() {
 
     // Test status command
     let command = setup_test_cli();
-    let matches = command.try_get_matches_from(&["chaser", "status"]).unwrap();
+    let matches = command.try_get_matches_from(["chaser", "status"]).unwrap();
     assert!(matches!(
         cli::parse_command(&matches),
-        Some(cli::Commands::Status)
+        Some(cli::Commands::Status { .. })
     ));
 }
+
+#[test]
+fn test_binary_runs_with_unwritable_config_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    // The config home is a regular file, so the config directory can't be created
+    let config_home = temp_dir.path().join("config-home");
+    fs::write(&config_home, "").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .arg("list")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("CHASER_LANG", "en")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not writable"));
+}
+
+#[test]
+fn test_add_target_dry_run_previews_without_saving() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config-home");
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let inside = watch_dir.join("inside.txt").display().to_string();
+    let outside = temp_dir.path().join("outside.txt").display().to_string();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(
+        &target,
+        serde_json::to_string(&[&inside, &outside]).unwrap(),
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env("CHASER_LANG", "en")
+            .env("CHASER_WATCH_PATHS", &watch_dir)
+            .output()
+            .unwrap()
+    };
+    let target_arg = target.display().to_string();
+    let output = run(&["add-target", &target_arg, "--dry-run"]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let inside_line = stdout.lines().find(|l| l.contains(&inside)).unwrap();
+    let outside_line = stdout.lines().find(|l| l.contains(&outside)).unwrap();
+    assert!(!inside_line.contains("outside watch directories"));
+    assert!(outside_line.contains("outside watch directories"));
+
+    let listed = run(&["list-targets"]);
+    assert!(!String::from_utf8_lossy(&listed.stdout).contains(&target_arg));
+}
+
+#[test]
+fn test_report_missing_on_start_lists_missing_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let present = watch_dir.join("present.txt").display().to_string();
+    let missing = watch_dir.join("missing.txt").display().to_string();
+    fs::write(&present, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(
+        &target,
+        serde_json::to_string(&[&present, &missing]).unwrap(),
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--report-missing-on-start", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env("CHASER_TARGET_FILES", &target)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.contains("already missing"))
+        .collect();
+    assert!(report.iter().any(|line| line.contains(&missing)));
+    assert!(!report.iter().any(|line| line.contains(&present)));
+}
+
+#[test]
+fn test_ignore_rejects_invalid_pattern_at_set_time() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config-home");
+    let run = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env("CHASER_LANG", "en")
+            .output()
+            .unwrap()
+    };
+
+    let rejected = run(&["ignore", "["]);
+    assert!(!rejected.status.success());
+    assert!(String::from_utf8_lossy(&rejected.stderr).contains("unclosed"));
+
+    assert!(run(&["ignore", "*.bak"]).status.success());
+
+    let saved = fs::read_to_string(config_home.join("chaser").join("config.yaml")).unwrap();
+    let saved: Config = serde_yaml_ng::from_str(&saved).unwrap();
+    assert!(saved.ignore_patterns.contains(&"*.bak".to_string()));
+    assert!(!saved.ignore_patterns.contains(&"[".to_string()));
+}
+
+#[test]
+fn test_startup_config_messages_are_localized() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config-home");
+    let run = || {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .arg("list")
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env("CHASER_LANG", "zh-cn")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).to_string()
+            + &String::from_utf8_lossy(&output.stderr)
+    };
+
+    // Both when the config is created and when it already exists
+    for expected in ["已在以下位置创建默认配置", "已从以下位置加载配置"] {
+        let printed = run();
+        for english in [
+            "Loaded config from",
+            "Created default config",
+            "Config saved to",
+        ] {
+            assert!(!printed.contains(english), "{}", printed);
+        }
+        assert!(printed.contains(expected), "{}", printed);
+    }
+}
+
+#[test]
+fn test_add_with_label_saves_label() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config-home");
+    let watched = temp_dir.path().display().to_string();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["add", &watched, "--label", "assets"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("CHASER_LANG", "en")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let saved = fs::read_to_string(config_home.join("chaser").join("config.yaml")).unwrap();
+    let saved: Config = serde_yaml_ng::from_str(&saved).unwrap();
+    assert_eq!(
+        saved.watch_labels.get(&watched),
+        Some(&"assets".to_string())
+    );
+}
+
+#[test]
+fn test_recursive_rejects_unknown_value_with_error_status() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config-home");
+    let run = |value: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .args(["recursive", value])
+            .env("XDG_CONFIG_HOME", &config_home)
+            .env("CHASER_LANG", "en")
+            .output()
+            .unwrap()
+    };
+
+    let rejected = run("sometimes");
+    assert!(!rejected.status.success());
+    assert!(String::from_utf8_lossy(&rejected.stderr).contains("sometimes"));
+
+    assert!(run(" Disable ").status.success());
+    let saved = fs::read_to_string(config_home.join("chaser").join("config.yaml")).unwrap();
+    let saved: Config = serde_yaml_ng::from_str(&saved).unwrap();
+    assert!(!saved.recursive);
+}
+
+#[test]
+fn test_duplicate_target_file_spellings_load_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(watch_dir.join("sub")).unwrap();
+    let tracked = watch_dir.join("tracked.txt").display().to_string();
+    fs::write(&tracked, "").unwrap();
+    let target = watch_dir.join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&tracked]).unwrap()).unwrap();
+    let other_spelling = watch_dir.join("sub").join("..").join("targets.json");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .arg("status")
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env(
+            "CHASER_TARGET_FILES",
+            std::env::join_paths([&target, &other_spelling]).unwrap(),
+        )
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    assert!(String::from_utf8_lossy(&output.stderr).contains("loading it once"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let listed = stdout.lines().filter(|line| line.contains("└─")).count();
+    assert_eq!(listed, 1, "{}", stdout);
+}
+
+#[test]
+fn test_summary_only_prints_counts_without_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--summary-only", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::write(watch_dir.join("new.txt"), "").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("new.txt"), "{}", stdout);
+    let stats = stdout
+        .lines()
+        .find(|line| line.contains("[stats]"))
+        .unwrap();
+    assert!(stats.contains("created"), "{}", stats);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_summary_only_prints_counts_on_ctrl_c_and_keeps_sync_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    fs::write(watch_dir.join("old.txt"), "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, "not json").unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .arg("--summary-only")
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env("CHASER_TARGET_FILES", &target)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::write(watch_dir.join("new.txt"), "").unwrap();
+    fs::rename(watch_dir.join("old.txt"), watch_dir.join("renamed.txt")).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    let killed = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Created:"), "{}", stdout);
+    assert!(
+        stdout.contains("Could not initialize path sync"),
+        "{}",
+        stdout
+    );
+    let stats = stdout
+        .lines()
+        .find(|line| line.contains("[stats]"))
+        .unwrap();
+    assert!(stats.contains("created"), "{}", stats);
+}
+
+#[test]
+fn test_synced_rename_is_not_reported_as_drift() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let old = watch_dir.join("old.txt");
+    let new = watch_dir.join("new.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+    let command = |args: &[&str]| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"));
+        command
+            .args(args)
+            .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+            .env("CHASER_LANG", "en")
+            .env("CHASER_WATCH_PATHS", &watch_dir)
+            .env("CHASER_TARGET_FILES", &target)
+            .stdout(std::process::Stdio::piped());
+        command
+    };
+
+    // Record the starting state
+    assert!(command(&["status"]).output().unwrap().status.success());
+
+    let child = command(&["--watch-timeout", "1s"]).spawn().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, &new).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.contains("new.txt"), "{}", content);
+
+    let status = command(&["status"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&status.stdout);
+    assert!(!stdout.contains("Changes since last run"), "{}", stdout);
+}
+
+#[test]
+fn test_rename_under_watch_root_from_targets_is_synced() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    let assets = temp_dir.path().join("assets");
+    fs::create_dir_all(&watch_dir).unwrap();
+    fs::create_dir_all(&assets).unwrap();
+    let old = assets.join("old.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--watch-roots-from-targets", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env("CHASER_TARGET_FILES", &target)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, assets.join("new.txt")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("outside all watch paths"), "{}", stdout);
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.contains("new.txt"), "{}", content);
+}
+
+#[test]
+fn test_explain_accounts_for_grep() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--explain", "--grep", "keep", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::write(watch_dir.join("keep.txt"), "").unwrap();
+    fs::write(watch_dir.join("skip.txt"), "").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let explanations: Vec<&str> = stdout.lines().filter(|l| l.contains("[explain]")).collect();
+    assert!(
+        explanations
+            .iter()
+            .any(|l| l.contains("skip.txt") && l.contains("does not match --grep")),
+        "{}",
+        stdout
+    );
+    assert!(
+        !explanations
+            .iter()
+            .any(|l| l.contains("skip.txt") && l.contains("shown")),
+        "{}",
+        stdout
+    );
+    assert!(
+        explanations
+            .iter()
+            .any(|l| l.contains("keep.txt") && l.contains("shown")),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_no_write_from_default_monitor_args_applies() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let old = watch_dir.join("old.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    let content = serde_json::to_string(&[&old]).unwrap();
+    fs::write(&target, &content).unwrap();
+    let command = |args: &[&str]| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"));
+        command
+            .args(args)
+            .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+            .env("CHASER_LANG", "en")
+            .env("CHASER_WATCH_PATHS", &watch_dir)
+            .env("CHASER_TARGET_FILES", &target)
+            .stdout(std::process::Stdio::piped());
+        command
+    };
+
+    // Create the config, then turn on the sandbox from its defaults
+    assert!(command(&["list"]).output().unwrap().status.success());
+    let config_file = temp_dir.path().join("config-home/chaser/config.yaml");
+    let config = fs::read_to_string(&config_file).unwrap();
+    fs::write(
+        &config_file,
+        config + "default_monitor_args: [--no-write]\n",
+    )
+    .unwrap();
+
+    let child = command(&["--watch-timeout", "1s"]).spawn().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, watch_dir.join("new.txt")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(fs::read_to_string(&target).unwrap(), content);
+}
+
+#[test]
+fn test_new_subdirs_are_watched_despite_grep() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let command = |args: &[&str]| {
+        let mut command = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"));
+        command
+            .args(args)
+            .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+            .env("CHASER_LANG", "en")
+            .env("CHASER_WATCH_PATHS", &watch_dir)
+            .env("CHASER_RECURSIVE", "false")
+            .stdout(std::process::Stdio::piped());
+        command
+    };
+    assert!(command(&["list"]).output().unwrap().status.success());
+    let config_file = temp_dir.path().join("config-home/chaser/config.yaml");
+    let config = fs::read_to_string(&config_file).unwrap();
+    fs::write(
+        &config_file,
+        config.replace("watch_new_subdirs: false", "watch_new_subdirs: true"),
+    )
+    .unwrap();
+
+    let child = command(&["--grep", "nothing-matches", "--watch-timeout", "1s"])
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::create_dir(watch_dir.join("sub")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Watching new directory"), "{}", stdout);
+}
+
+#[test]
+fn test_duplicate_target_file_warning_is_shown_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(watch_dir.join("sub")).unwrap();
+    let old = watch_dir.join("old.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+    let other_spelling = watch_dir.join("..").join("targets.json");
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--report-missing-on-start", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env(
+            "CHASER_TARGET_FILES",
+            std::env::join_paths([&target, &other_spelling]).unwrap(),
+        )
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, watch_dir.join("new.txt")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    assert!(fs::read_to_string(&target).unwrap().contains("new.txt"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("loading it once").count(), 1, "{}", stderr);
+}
+
+#[test]
+fn test_coverage_checks_tracked_paths_against_monitor_watches() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    let assets = temp_dir.path().join("assets");
+    fs::create_dir_all(&watch_dir).unwrap();
+    fs::create_dir_all(&assets).unwrap();
+    let outside = assets.join("logo.png");
+    fs::write(&outside, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&outside]).unwrap()).unwrap();
+
+    let coverage = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+            .env("CHASER_LANG", "en")
+            .env("CHASER_WATCH_PATHS", &watch_dir)
+            .env("CHASER_TARGET_FILES", &target)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    // A tracked path outside the watch paths is listed, not filtered out
+    let stdout = coverage(&["coverage"]);
+    assert!(
+        stdout.contains(&outside.display().to_string()),
+        "{}",
+        stdout
+    );
+    // The monitor would watch its directory as a root taken from the targets
+    let stdout = coverage(&["--watch-roots-from-targets", "coverage"]);
+    assert!(
+        stdout.contains("All 1 tracked paths are covered"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_rename_hidden_by_grep_is_still_synced() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let old = watch_dir.join("old.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--grep", "no-such-name", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env("CHASER_TARGET_FILES", &target)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, watch_dir.join("new.txt")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("File renamed"), "{}", stdout);
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.contains("new.txt"), "{}", content);
+}
+
+#[test]
+fn test_scan_add_skips_target_files_already_added() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir_all(project.join("sub")).unwrap();
+    fs::write(project.join("targets.json"), r#"["./assets/a.png"]"#).unwrap();
+    let other_spelling = project.join("sub").join("..").join("targets.json");
+
+    let chaser = |args: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+            .args(args)
+            .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+            .env("CHASER_LANG", "en")
+            .output()
+            .unwrap()
+    };
+
+    assert!(
+        chaser(&["add-target", &other_spelling.display().to_string()])
+            .status
+            .success()
+    );
+    let output = chaser(&["scan", &project.display().to_string(), "--add"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("already a target file"), "{}", stdout);
+    assert!(!stdout.contains("Added target file"), "{}", stdout);
+
+    let output = chaser(&["list-targets"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("targets.json").count(), 1, "{}", stdout);
+
+    // A root that doesn't exist is an error, not an empty scan
+    let output = chaser(&[
+        "scan",
+        &temp_dir.path().join("missing").display().to_string(),
+    ]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_summary_only_keeps_sync_progress_out_of_the_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let old = watch_dir.join("old.txt");
+    fs::write(&old, "").unwrap();
+    let target = temp_dir.path().join("targets.json");
+    fs::write(&target, serde_json::to_string(&[&old]).unwrap()).unwrap();
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_chaser"))
+        .args(["--summary-only", "--watch-timeout", "1s"])
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config-home"))
+        .env("CHASER_LANG", "en")
+        .env("CHASER_WATCH_PATHS", &watch_dir)
+        .env("CHASER_TARGET_FILES", &target)
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    fs::rename(&old, watch_dir.join("new.txt")).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let content = fs::read_to_string(&target).unwrap();
+    assert!(content.contains("new.txt"), "{}", content);
+    // Nothing is printed between the start of the watch and its closing lines
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (_, run) = stdout.split_once("File monitoring started").unwrap();
+    let run: Vec<&str> = run
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    assert_eq!(run.len(), 2, "{}", stdout);
+    assert!(run[0].contains("No events for"), "{}", stdout);
+    assert!(run[1].contains("[stats]"), "{}", stdout);
+}
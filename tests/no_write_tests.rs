@@ -0,0 +1,135 @@
+// Tests for the process-wide --no-write sandbox mode.
+// They live in their own test binary because the mode is global state.
+
+use chaser::path_sync::PathSyncManager;
+use chaser::state::{Rewrite, Transaction};
+use chaser::target_files::{PathUpdate, TargetFile};
+use chaser::write_mode::{is_no_write, set_no_write};
+use serial_test::serial;
+use std::collections::BTreeMap;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+#[serial]
+fn test_no_write_leaves_target_file_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let json_file = temp_dir.path().join("targets.json");
+    let initial_content = r#"["./test_files/old_path", "./test_files/keep_path"]"#;
+    fs::write(&json_file, initial_content).unwrap();
+
+    let mut target_file = TargetFile::new(json_file.clone()).unwrap();
+
+    set_no_write(true);
+    let result = target_file.update_path("./test_files/old_path", "./test_files/new_path");
+    set_no_write(false);
+
+    assert_eq!(fs::read_to_string(&json_file).unwrap(), initial_content);
+    // The skipped edit comes back as a diff instead of being printed
+    let PathUpdate::Previewed(diff) = result.unwrap() else {
+        panic!("expected a preview");
+    };
+    let lines: Vec<&str> = diff.lines().collect();
+    assert!(lines.contains(&r#"-["./test_files/old_path", "./test_files/keep_path"]"#));
+    assert!(lines.contains(&r#"+["./test_files/new_path", "./test_files/keep_path"]"#));
+}
+
+#[test]
+#[serial]
+fn test_no_write_skips_creating_missing_target_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let missing = temp_dir.path().join("nested").join("targets.json");
+
+    set_no_write(true);
+    let result = PathSyncManager::new(
+        vec![missing.to_string_lossy().to_string()],
+        vec![temp_dir.path().to_string_lossy().to_string()],
+    );
+    set_no_write(false);
+
+    assert!(result.is_ok());
+    assert!(!missing.exists());
+    assert!(!missing.parent().unwrap().exists());
+}
+
+#[test]
+#[serial]
+fn test_no_write_sync_path_change_keeps_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+
+    let old_path = watch_dir.join("old.txt");
+    fs::write(&old_path, "test").unwrap();
+
+    let json_file = temp_dir.path().join("targets.json");
+    let initial_content = format!(r#"["{}"]"#, old_path.to_string_lossy());
+    fs::write(&json_file, &initial_content).unwrap();
+
+    let mut manager = PathSyncManager::new(
+        vec![json_file.to_string_lossy().to_string()],
+        vec![watch_dir.to_string_lossy().to_string()],
+    )
+    .unwrap();
+
+    set_no_write(true);
+    let new_path = watch_dir.join("new.txt");
+    manager
+        .sync_path_change(&old_path.to_string_lossy(), &new_path.to_string_lossy())
+        .unwrap();
+    set_no_write(false);
+
+    assert_eq!(fs::read_to_string(&json_file).unwrap(), initial_content);
+}
+
+#[test]
+#[serial]
+fn test_no_write_toggle() {
+    set_no_write(true);
+    assert!(is_no_write());
+    set_no_write(false);
+    assert!(!is_no_write());
+}
+
+#[test]
+#[serial]
+fn test_no_write_leaves_transaction_journal_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let watch_dir = temp_dir.path().join("watch");
+    fs::create_dir_all(&watch_dir).unwrap();
+    let old_path = watch_dir.join("old.txt").to_string_lossy().to_string();
+    let json_file = temp_dir.path().join("targets.json");
+    let initial_content = format!(r#"["{}"]"#, old_path);
+    fs::write(&json_file, &initial_content).unwrap();
+
+    let journal = temp_dir.path().join("journal.yaml");
+    Transaction {
+        old_path: old_path.clone(),
+        new_path: watch_dir.join("new.txt").to_string_lossy().to_string(),
+        pending: BTreeMap::from([(
+            json_file.to_string_lossy().to_string(),
+            vec![Rewrite {
+                from: old_path.clone(),
+                to: watch_dir.join("new.txt").to_string_lossy().to_string(),
+            }],
+        )]),
+    }
+    .save_to(&journal)
+    .unwrap();
+    let journaled = fs::read_to_string(&journal).unwrap();
+
+    let mut manager = PathSyncManager::new(
+        vec![json_file.to_string_lossy().to_string()],
+        vec![watch_dir.to_string_lossy().to_string()],
+    )
+    .unwrap()
+    .with_journal(journal.clone());
+
+    set_no_write(true);
+    let resumed = manager.resume_pending();
+    set_no_write(false);
+
+    assert!(resumed.is_ok());
+    assert_eq!(fs::read_to_string(&journal).unwrap(), journaled);
+    assert_eq!(fs::read_to_string(&json_file).unwrap(), initial_content);
+}